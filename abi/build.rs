@@ -19,8 +19,135 @@ fn main() {
                 "page",
                 "page_size",
                 "desc",
+                "period",
+                "timezone",
             ],
         )
+        // json representation for the rest gateway
+        .with_serde(&[
+            "rsvp.Reservation",
+            "rsvp.UpdateRequest",
+            "rsvp.ReservationQuery",
+            "rsvp.ReservationFilter",
+            "rsvp.FilterPager",
+            "rsvp.FilterResponse",
+            "rsvp.WaitlistEntry",
+            "rsvp.ListWaitlistRequest",
+            "rsvp.ListWaitlistResponse",
+            "rsvp.HoldRequest",
+            "rsvp.HoldResponse",
+            "rsvp.PromoteHoldRequest",
+            "rsvp.QuotaUsage",
+            "rsvp.QuotaRequest",
+            "rsvp.QuotaResponse",
+            "rsvp.ApproveRequest",
+            "rsvp.RejectRequest",
+            "rsvp.AuditEntry",
+            "rsvp.HistoryResponse",
+            "rsvp.QueryAuditRequest",
+            "rsvp.QueryAuditResponse",
+            "rsvp.Webhook",
+            "rsvp.ListWebhooksResponse",
+            "rsvp.DeadLetter",
+            "rsvp.ListDeadLettersResponse",
+            "rsvp.ReplayWebhookRequest",
+            "rsvp.ReplayWebhookResponse",
+            "rsvp.ImportCalendarRequest",
+            "rsvp.ImportedEvent",
+            "rsvp.ImportCalendarResponse",
+            "rsvp.NoShowsRequest",
+            "rsvp.NoShowsResponse",
+            "rsvp.ExtendRequest",
+            "rsvp.ReservationConflict",
+            "rsvp.TimeWindow",
+        ])
+        .with_serde_with(
+            "rsvp.Reservation",
+            &["start_time", "end_time", "checked_in_at", "checked_out_at"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.ReservationQuery",
+            &["start", "end"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.WaitlistEntry",
+            &["start_time", "end_time", "expires_at"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.HoldResponse",
+            &["expires_at"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.AuditEntry",
+            &["changed_at"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.Webhook",
+            &["created_at"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.DeadLetter",
+            &["failed_at"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.QueryAuditRequest",
+            &["start", "end"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.ExtendRequest",
+            &["end_time"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.ReservationConflict",
+            &[
+                "start",
+                "end",
+                "conflicting_start",
+                "conflicting_end",
+                "max_end",
+            ],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.TimeWindow",
+            &["start", "end"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.NoShowsRequest",
+            &["since"],
+            "crate::serde_ext::timestamp",
+        )
+        .with_serde_with(
+            "rsvp.WaitlistEntry",
+            &["status"],
+            "crate::serde_ext::waitlist_status",
+        )
+        .with_serde_with("rsvp.Reservation", &["status"], "crate::serde_ext::status")
+        .with_serde_with(
+            "rsvp.ReservationConflict",
+            &["conflicting_status"],
+            "crate::serde_ext::status",
+        )
+        .with_serde_with(
+            "rsvp.ReservationQuery",
+            &["status"],
+            "crate::serde_ext::status",
+        )
+        .with_serde_with(
+            "rsvp.ReservationFilter",
+            &["status"],
+            "crate::serde_ext::status",
+        )
         .with_builder_into(
             "rsvp.ReservationFilter",
             &[
@@ -52,6 +179,10 @@ trait BuilderExt {
     fn with_builder_into(self, path: &str, fields: &[&str]) -> Self;
 
     fn with_builder_option(self, path: &str, fields: &[&str]) -> Self;
+
+    fn with_serde(self, paths: &[&str]) -> Self;
+
+    fn with_serde_with(self, path: &str, fields: &[&str], module: &str) -> Self;
 }
 
 impl BuilderExt for Builder {
@@ -84,4 +215,22 @@ impl BuilderExt for Builder {
             )
         })
     }
+
+    fn with_serde(self, paths: &[&str]) -> Self {
+        paths.iter().fold(self, |acc, path| {
+            acc.type_attribute(
+                path,
+                "#[derive(serde::Serialize, serde::Deserialize)]\n#[serde(default)]",
+            )
+        })
+    }
+
+    fn with_serde_with(self, path: &str, fields: &[&str], module: &str) -> Self {
+        fields.iter().fold(self, |acc, field| {
+            acc.field_attribute(
+                format!("{path}.{field}"),
+                format!("#[serde(with = \"{module}\")]"),
+            )
+        })
+    }
 }
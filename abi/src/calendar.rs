@@ -0,0 +1,454 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::{convert_to_timestamp, convert_to_utc_time, Error, Reservation, ReservationStatus};
+
+const PRODID: &str = "-//rsvp//reservation service//EN";
+/// the domain part of the uids, so the uid of a reservation is stable across the exports
+const UID_DOMAIN: &str = "rsvp";
+/// content lines longer than this are folded, in octets without the line break
+const MAX_LINE: usize = 75;
+
+/// the reservations as an iCalendar (RFC 5545) of one VEVENT per reservation
+pub fn to_calendar(rsvps: &[Reservation]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    let stamp = format_time(Utc::now());
+    for rsvp in rsvps {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:reservation-{}@{UID_DOMAIN}", rsvp.id));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        if let (Some(start), Some(end)) = (&rsvp.start_time, &rsvp.end_time) {
+            lines.push(format!(
+                "DTSTART:{}",
+                format_time(convert_to_utc_time(start))
+            ));
+            lines.push(format!("DTEND:{}", format_time(convert_to_utc_time(end))));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&rsvp.resource_id)));
+        lines.push(format!("LOCATION:{}", escape(&rsvp.resource_id)));
+        lines.push(format!("CONTACT:{}", escape(&rsvp.user_id)));
+        if !rsvp.note.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&rsvp.note)));
+        }
+        if let Some(status) = event_status(rsvp.status) {
+            lines.push(format!("STATUS:{status}"));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        fold(&mut calendar, &line);
+    }
+    calendar
+}
+
+/// a VEVENT of an imported calendar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+/// split the calendar into its events, which are converted into reservations one by one
+pub fn parse_calendar(ics: &str) -> Result<Vec<CalendarEvent>, Error> {
+    let mut events = vec![];
+    let mut event: Option<CalendarEvent> = None;
+    // the depth of the components in the event, such as VALARM
+    let mut nested = 0;
+    let mut in_calendar = false;
+    for line in unfold(ics) {
+        let property = parse_property(&line)?;
+        match (
+            property.name.as_str(),
+            property.value.to_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VCALENDAR") => in_calendar = true,
+            ("BEGIN", _) if event.is_some() => nested += 1,
+            ("END", _) if nested > 0 => nested -= 1,
+            ("BEGIN", "VEVENT") if in_calendar => event = Some(CalendarEvent::default()),
+            ("END", "VEVENT") => {
+                let e = event
+                    .take()
+                    .ok_or_else(|| invalid("END:VEVENT without BEGIN"))?;
+                events.push(e);
+            }
+            ("END", "VCALENDAR") => {
+                if event.is_some() {
+                    return Err(invalid("VEVENT is not ended"));
+                }
+                return Ok(events);
+            }
+            _ if nested > 0 => {}
+            _ => {
+                // the properties of VCALENDAR and the other components are ignored
+                if let Some(e) = event.as_mut() {
+                    if property.name == "UID" {
+                        e.uid = property.value.clone();
+                    }
+                    e.properties.push(property);
+                }
+            }
+        }
+    }
+    Err(invalid("VCALENDAR is not ended"))
+}
+
+impl CalendarEvent {
+    /// the reservation of the event for the user, on the resource or the LOCATION of the event
+    pub fn to_reservation(&self, user_id: &str, resource_id: &str) -> Result<Reservation, Error> {
+        if self.get("RRULE").is_some() || self.get("RDATE").is_some() {
+            return Err(invalid("recurring event is not supported"));
+        }
+        if let Some(status) = self.get("STATUS") {
+            if status.value.eq_ignore_ascii_case("CANCELLED") {
+                return Err(invalid("the event is cancelled"));
+            }
+        }
+
+        let dtstart = self
+            .get("DTSTART")
+            .ok_or_else(|| invalid("DTSTART is missing"))?;
+        let start = parse_time(dtstart)?;
+        let end = match (self.get("DTEND"), self.get("DURATION")) {
+            (Some(dtend), _) => parse_time(dtend)?,
+            (None, Some(duration)) => start + parse_duration(&duration.value)?,
+            // an all-day event without the end lasts for the day
+            (None, None) if is_date(dtstart) => start + Duration::days(1),
+            (None, None) => return Err(invalid("DTEND is missing")),
+        };
+
+        let resource_id = match resource_id {
+            "" => self.text("LOCATION"),
+            rid => rid.to_string(),
+        };
+        let note = match self.text("DESCRIPTION") {
+            note if note.is_empty() => self.text("SUMMARY"),
+            note => note,
+        };
+        Ok(Reservation {
+            user_id: user_id.to_string(),
+            resource_id,
+            start_time: Some(convert_to_timestamp(start)),
+            end_time: Some(convert_to_timestamp(end)),
+            note,
+            status: ReservationStatus::Pending as i32,
+            ..Default::default()
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    fn text(&self, name: &str) -> String {
+        self.get(name)
+            .map(|p| unescape(&p.value))
+            .unwrap_or_default()
+    }
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidCalendar(reason.to_string())
+}
+
+fn event_status(status: i32) -> Option<&'static str> {
+    match ReservationStatus::from_i32(status)? {
+        ReservationStatus::Pending | ReservationStatus::Held => Some("TENTATIVE"),
+        ReservationStatus::Confirmed => Some("CONFIRMED"),
+        ReservationStatus::Cancelled | ReservationStatus::Rejected | ReservationStatus::NoShow => {
+            Some("CANCELLED")
+        }
+        ReservationStatus::Unknown => None,
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// fold the line at the octet limit on the char boundaries, each line ends with CRLF
+fn fold(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            out.push_str("\r\n ");
+            // the leading space of the continuation line counts
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// join the folded lines, a line starting with a space or a tab continues the previous one
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in ics.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=VALUE;PARAM="QUOTED":VALUE`, the names are case-insensitive
+fn parse_property(line: &str) -> Result<Property, Error> {
+    let mut quoted = false;
+    let colon = line
+        .char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                quoted = !quoted;
+            }
+            *c == ':' && !quoted
+        })
+        .map(|(i, _)| i)
+        .ok_or_else(|| invalid("content line without value"))?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next().unwrap_or_default().to_uppercase();
+    if name.is_empty() {
+        return Err(invalid("content line without name"));
+    }
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Ok(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn is_date(property: &Property) -> bool {
+    property.param("VALUE") == Some("DATE") || property.value.len() == 8
+}
+
+/// a DATE or DATE-TIME in UTC, local to its TZID, or floating which is taken as UTC
+fn parse_time(property: &Property) -> Result<DateTime<Utc>, Error> {
+    let value = property.value.as_str();
+    let bad_time = || Error::InvalidCalendar(format!("bad {} {value}", property.name));
+    let local = if is_date(property) {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map_err(|_| bad_time())?
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(bad_time)?
+    } else if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| bad_time())?;
+        return Ok(Utc.from_utc_datetime(&time));
+    } else {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| bad_time())?
+    };
+    match property.param("TZID") {
+        Some(tzid) => {
+            let tz: Tz = tzid
+                .parse()
+                .map_err(|_| Error::InvalidCalendar(format!("unknown TZID {tzid}")))?;
+            // the earlier one of an ambiguous time, like the calendar apps do
+            tz.from_local_datetime(&local)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(bad_time)
+        }
+        None => Ok(Utc.from_utc_datetime(&local)),
+    }
+}
+
+/// `PnW` or `PnDTnHnMnS`, the negative durations are rejected
+fn parse_duration(value: &str) -> Result<Duration, Error> {
+    let bad_duration = || Error::InvalidCalendar(format!("bad DURATION {value}"));
+    let rest = value
+        .strip_prefix('+')
+        .unwrap_or(value)
+        .strip_prefix('P')
+        .ok_or_else(bad_duration)?;
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() => in_time = true,
+            _ => {
+                let n: i64 = number.parse().map_err(|_| bad_duration())?;
+                duration += match (c, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return Err(bad_duration()),
+                };
+                number.clear();
+            }
+        }
+    }
+    if !number.is_empty() || duration.is_zero() {
+        return Err(bad_duration());
+    }
+    Ok(duration)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rsvp(id: i64, status: ReservationStatus, note: &str) -> Reservation {
+        let mut rsvp = Reservation::new_pending(
+            "tosei",
+            "ocean view room 713",
+            "2023-01-02T09:00:00+08:00".parse().unwrap(),
+            "2023-01-02T10:30:00+08:00".parse().unwrap(),
+            note,
+        );
+        rsvp.id = id;
+        rsvp.status = status as i32;
+        rsvp
+    }
+
+    #[test]
+    fn reservations_should_be_exported_as_events() {
+        let long_note = "a long note; with the separators, and\na second line ".repeat(3);
+        let calendar = to_calendar(&[
+            rsvp(42, ReservationStatus::Confirmed, "team sync"),
+            rsvp(43, ReservationStatus::Cancelled, &long_note),
+        ]);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert!(calendar.contains("UID:reservation-42@rsvp\r\n"));
+        assert!(calendar.contains("DTSTART:20230102T010000Z\r\nDTEND:20230102T023000Z\r\n"));
+        assert!(calendar.contains("STATUS:CONFIRMED\r\n"));
+        assert!(calendar.contains("STATUS:CANCELLED\r\n"));
+        assert!(calendar.split("\r\n").all(|line| line.len() <= MAX_LINE));
+
+        // the exported events are imported back with the same window and note
+        let events = parse_calendar(&calendar).unwrap();
+        assert_eq!("reservation-43@rsvp", events[1].uid);
+        let err = events[1].to_reservation("wxy", "").unwrap_err();
+        assert_eq!(invalid("the event is cancelled"), err);
+        let mut events = events.into_iter().map(|mut e| {
+            e.properties.retain(|p| p.name != "STATUS");
+            e.to_reservation("tosei", "").unwrap()
+        });
+        assert_eq!(
+            rsvp(0, ReservationStatus::Pending, "team sync"),
+            events.next().unwrap()
+        );
+        assert_eq!(
+            rsvp(0, ReservationStatus::Pending, &long_note),
+            events.next().unwrap()
+        );
+    }
+
+    #[test]
+    fn events_should_be_converted_in_their_timezones() {
+        let calendar = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:new-year\r\nDTSTART;VALUE=DATE:20240101\r\n\
+            SUMMARY:New Year's Day\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:standup\r\nDTSTART;TZID=\"America/New_York\":20240301T093000\r\n\
+            DURATION:PT15M\r\nLOCATION:room-1\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\n\
+            END:VALARM\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:weekly\r\nDTSTART:20240301T093000Z\r\nDURATION:PT1H\r\n\
+            RRULE:FREQ=WEEKLY\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let events = parse_calendar(calendar).unwrap();
+        assert_eq!(3, events.len());
+
+        let holiday = events[0].to_reservation("admin", "hall").unwrap();
+        assert_eq!("hall", holiday.resource_id);
+        assert_eq!("New Year's Day", holiday.note);
+        let start = convert_to_utc_time(holiday.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(holiday.end_time.as_ref().unwrap());
+        assert_eq!("2024-01-01T00:00:00+00:00", start.to_rfc3339());
+        assert_eq!(Duration::days(1), end - start);
+
+        let standup = events[1].to_reservation("tosei", "").unwrap();
+        assert_eq!("room-1", standup.resource_id);
+        let start = convert_to_utc_time(standup.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(standup.end_time.as_ref().unwrap());
+        assert_eq!("2024-03-01T14:30:00+00:00", start.to_rfc3339());
+        assert_eq!(Duration::minutes(15), end - start);
+
+        let err = events[2].to_reservation("tosei", "room-1").unwrap_err();
+        assert_eq!(invalid("recurring event is not supported"), err);
+    }
+
+    #[test]
+    fn malformed_calendar_should_be_rejected() {
+        assert_eq!(
+            Err(invalid("VCALENDAR is not ended")),
+            parse_calendar("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n")
+        );
+        assert_eq!(
+            Err(invalid("content line without value")),
+            parse_calendar("BEGIN:VCALENDAR\r\nnot a content line\r\n")
+        );
+        assert_eq!(Err(bad_duration("P1X")), parse_duration("P1X"));
+        assert_eq!(Ok(Duration::hours(36)), parse_duration("P1DT12H"));
+    }
+
+    fn bad_duration(value: &str) -> Error {
+        Error::InvalidCalendar(format!("bad DURATION {value}"))
+    }
+}
@@ -1,5 +1,7 @@
-use std::{fs, path::Path};
+use std::{collections::HashMap, fs, path::Path};
 
+use chrono::{DateTime, Utc, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
 use crate::Error;
@@ -8,6 +10,36 @@ use crate::Error;
 pub struct Config {
     pub db: DbConfig,
     pub server: ServerConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub tracing: TracingConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub rest: RestConfig,
+    #[serde(default)]
+    pub waitlist: WaitlistConfig,
+    #[serde(default)]
+    pub hold: HoldConfig,
+    #[serde(default)]
+    pub buffers: BufferConfig,
+    #[serde(default)]
+    pub rules: RuleConfig,
+    #[serde(default)]
+    pub quotas: QuotaConfig,
+    #[serde(default)]
+    pub approvals: ApprovalConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub check_in: CheckInConfig,
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+    #[serde(default)]
+    pub tenancy: TenancyConfig,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +61,512 @@ fn default_max_connections() -> u32 {
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// seconds to wait for in-flight requests after SIGTERM/SIGINT
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+}
+
+fn default_shutdown_timeout() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(default = "default_metrics_host")]
+    pub host: String,
+    #[serde(default = "default_metrics_port")]
+    pub port: u16,
+    #[serde(default = "default_metrics_path")]
+    pub path: String,
+}
+
+fn default_metrics_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_metrics_port() -> u16 {
+    9090
+}
+
+fn default_metrics_path() -> String {
+    "/metrics".to_string()
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            host: default_metrics_host(),
+            port: default_metrics_port(),
+            path: default_metrics_path(),
+        }
+    }
+}
+
+/// the REST/JSON gateway served beside the grpc server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestConfig {
+    #[serde(default = "default_rest_host")]
+    pub host: String,
+    #[serde(default = "default_rest_port")]
+    pub port: u16,
+}
+
+fn default_rest_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_rest_port() -> u16 {
+    8080
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        Self {
+            host: default_rest_host(),
+            port: default_rest_port(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracingConfig {
+    /// env-filter directives, overridden by RUST_LOG
+    #[serde(default = "default_tracing_filter")]
+    pub filter: String,
+    /// output the logs in json format
+    #[serde(default)]
+    pub json: bool,
+    /// OTLP collector endpoint, e.g. http://localhost:4317. If empty, spans are not exported
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+fn default_tracing_filter() -> String {
+    "info".to_string()
+}
+
+fn default_service_name() -> String {
+    "rorder".to_string()
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        Self {
+            filter: default_tracing_filter(),
+            json: false,
+            otlp_endpoint: None,
+            service_name: default_service_name(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// max in-flight requests of the server, 0 means unlimited
+    #[serde(default)]
+    pub max_concurrency: usize,
+    /// limit for the rpc which is not listed in `rpcs`, if empty the rpc is unlimited
+    #[serde(default)]
+    pub default: Option<BucketConfig>,
+    /// limits by rpc name, e.g. add, query
+    #[serde(default)]
+    pub rpcs: HashMap<String, BucketConfig>,
+}
+
+/// token bucket of every client, refilled `per_second` tokens up to `burst`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketConfig {
+    pub burst: u32,
+    pub per_second: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaitlistConfig {
+    #[serde(default)]
+    pub mode: WaitlistMode,
+    /// seconds between the sweeps of the expired entries
+    #[serde(default = "default_sweep_interval")]
+    pub sweep_interval: u64,
+}
+
+/// what happens to the first waiting entry when its window is freed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitlistMode {
+    /// convert the entry into a pending reservation
+    #[default]
+    Reserve,
+    /// only mark the entry as notified and let the user book the window
+    Notify,
+}
+
+fn default_sweep_interval() -> u64 {
+    60
+}
+
+impl Default for WaitlistConfig {
+    fn default() -> Self {
+        Self {
+            mode: WaitlistMode::default(),
+            sweep_interval: default_sweep_interval(),
+        }
+    }
+}
+
+/// tentative holds of the checkout flows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoldConfig {
+    /// seconds a window is held if the request does not give the ttl
+    #[serde(default = "default_hold_ttl")]
+    pub default_ttl: u64,
+    /// the longest ttl a request could ask for
+    #[serde(default = "default_max_hold_ttl")]
+    pub max_ttl: u64,
+    /// seconds between the sweeps of the expired holds
+    #[serde(default = "default_hold_sweep_interval")]
+    pub sweep_interval: u64,
+}
+
+fn default_hold_ttl() -> u64 {
+    600
+}
+
+fn default_max_hold_ttl() -> u64 {
+    3600
+}
+
+fn default_hold_sweep_interval() -> u64 {
+    30
+}
+
+impl Default for HoldConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl: default_hold_ttl(),
+            max_ttl: default_max_hold_ttl(),
+            sweep_interval: default_hold_sweep_interval(),
+        }
+    }
+}
+
+/// the deleted and the old reservations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// seconds after the end until a reservation is moved to the archive, kept if not set
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    /// seconds between the sweeps of the old reservations
+    #[serde(default = "default_retention_sweep_interval")]
+    pub sweep_interval: u64,
+}
+
+fn default_retention_sweep_interval() -> u64 {
+    3600
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age: None,
+            sweep_interval: default_retention_sweep_interval(),
+        }
+    }
+}
+
+/// the actual use of the confirmed reservations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckInConfig {
+    /// seconds before the start a reservation could be checked in
+    #[serde(default = "default_early_check_in")]
+    pub early: u64,
+    /// seconds after the start until a reservation without a check-in is a no-show, never if
+    /// not set. The ones ended before the grace period are not marked, e.g. the old ones
+    #[serde(default)]
+    pub grace_period: Option<u64>,
+    /// seconds between the sweeps of the no-shows
+    #[serde(default = "default_check_in_sweep_interval")]
+    pub sweep_interval: u64,
+}
+
+fn default_early_check_in() -> u64 {
+    900
+}
+
+fn default_check_in_sweep_interval() -> u64 {
+    60
+}
+
+impl Default for CheckInConfig {
+    fn default() -> Self {
+        Self {
+            early: default_early_check_in(),
+            grace_period: None,
+            sweep_interval: default_check_in_sweep_interval(),
+        }
+    }
+}
+
+/// the delivery of the changes to the webhooks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// seconds between the sweeps of the outbox
+    #[serde(default = "default_webhook_sweep_interval")]
+    pub sweep_interval: u64,
+    /// seconds to wait for a webhook to respond
+    #[serde(default = "default_webhook_timeout")]
+    pub timeout: u64,
+    /// a delivery is moved to the dead letters after the attempts fail
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// seconds before the first retry, doubled after every failed attempt
+    #[serde(default = "default_webhook_initial_backoff")]
+    pub initial_backoff: u64,
+    /// the longest seconds between the retries
+    #[serde(default = "default_webhook_max_backoff")]
+    pub max_backoff: u64,
+}
+
+fn default_webhook_sweep_interval() -> u64 {
+    5
+}
+
+fn default_webhook_timeout() -> u64 {
+    10
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    8
+}
+
+fn default_webhook_initial_backoff() -> u64 {
+    10
+}
+
+fn default_webhook_max_backoff() -> u64 {
+    3600
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            sweep_interval: default_webhook_sweep_interval(),
+            timeout: default_webhook_timeout(),
+            max_attempts: default_webhook_max_attempts(),
+            initial_backoff: default_webhook_initial_backoff(),
+            max_backoff: default_webhook_max_backoff(),
+        }
+    }
+}
+
+/// the business units sharing the service, their rows are isolated by the row level security
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenancyConfig {
+    /// the tenant of the requests which do not carry one
+    #[serde(default = "default_tenant")]
+    pub default: String,
+    /// the metadata of the tenant id, set by the gateway like the user id
+    #[serde(default = "default_tenant_header")]
+    pub header: String,
+    /// the hs256 secret of the bearer tokens, the tenant is taken from their claim if it is set
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+    /// the claim of the tenant id in the bearer token
+    #[serde(default = "default_tenant_claim")]
+    pub claim: String,
+    /// the claim of the user in the bearer token, the admins and approvers are checked by it
+    #[serde(default = "default_user_claim")]
+    pub user_claim: String,
+    /// the settings which differ from the top level ones by tenant id
+    #[serde(default)]
+    pub tenants: HashMap<String, TenantConfig>,
+}
+
+fn default_tenant() -> String {
+    "default".to_string()
+}
+
+fn default_tenant_header() -> String {
+    "x-tenant-id".to_string()
+}
+
+fn default_tenant_claim() -> String {
+    "tenant_id".to_string()
+}
+
+fn default_user_claim() -> String {
+    "sub".to_string()
+}
+
+impl Default for TenancyConfig {
+    fn default() -> Self {
+        Self {
+            default: default_tenant(),
+            header: default_tenant_header(),
+            jwt_secret: None,
+            claim: default_tenant_claim(),
+            user_claim: default_user_claim(),
+            tenants: HashMap::new(),
+        }
+    }
+}
+
+/// the overrides of a tenant, the top level settings are used for the ones not set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantConfig {
+    #[serde(default)]
+    pub buffers: Option<BufferConfig>,
+    #[serde(default)]
+    pub rules: Option<RuleConfig>,
+    #[serde(default)]
+    pub quotas: Option<QuotaConfig>,
+    #[serde(default)]
+    pub approvals: Option<ApprovalConfig>,
+}
+
+/// turnover time around the reservations, e.g. cleaning of a room or inspection of a vehicle
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferConfig {
+    /// buffer of the resource which is not listed in `resources`
+    #[serde(default)]
+    pub default: Buffer,
+    /// buffers by resource id, an id ending with `*` matches the ids with the prefix, e.g. room-*
+    #[serde(default)]
+    pub resources: HashMap<String, Buffer>,
+}
+
+/// seconds kept free before the start and after the end of a reservation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Buffer {
+    #[serde(default)]
+    pub before: u64,
+    #[serde(default)]
+    pub after: u64,
+}
+
+/// booking rules of the resources, matched by id the same as the buffers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleConfig {
+    /// rules of the resource which is not listed in `resources`
+    #[serde(default)]
+    pub default: BookingRules,
+    /// rules by resource id, an id ending with `*` matches the ids with the prefix
+    #[serde(default)]
+    pub resources: HashMap<String, BookingRules>,
+}
+
+/// when and how long a resource could be booked, all the durations are in seconds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookingRules {
+    /// the timezone of the opening hours, e.g. Asia/Shanghai
+    #[serde(default = "default_timezone")]
+    pub timezone: Tz,
+    /// opening hours by weekday, e.g. mon: ["09:00-12:00", "13:00-18:00"].
+    /// If empty the resource is always open, otherwise the unlisted days are closed
+    #[serde(default)]
+    pub opening_hours: HashMap<Weekday, Vec<OpeningHours>>,
+    /// maintenance or other windows in which the resource could not be booked
+    #[serde(default)]
+    pub blackouts: Vec<Blackout>,
+    #[serde(default)]
+    pub min_duration: Option<u64>,
+    #[serde(default)]
+    pub max_duration: Option<u64>,
+    /// how long before the start a reservation must be made
+    #[serde(default)]
+    pub min_lead_time: Option<u64>,
+    /// how far in the future a reservation could start
+    #[serde(default)]
+    pub max_horizon: Option<u64>,
+}
+
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+impl Default for BookingRules {
+    fn default() -> Self {
+        Self {
+            timezone: default_timezone(),
+            opening_hours: HashMap::new(),
+            blackouts: vec![],
+            min_duration: None,
+            max_duration: None,
+            min_lead_time: None,
+            max_horizon: None,
+        }
+    }
+}
+
+/// an interval of a day in "HH:MM-HH:MM", the end could be 24:00
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct OpeningHours {
+    /// seconds since the midnight
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blackout {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// limits of the reservations of every user, nothing is limited if not set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    /// reservations which are not cancelled and not ended yet
+    #[serde(default)]
+    pub max_active: Option<u64>,
+    /// active reservations overlapping the window of the new one
+    #[serde(default)]
+    pub max_concurrent: Option<u64>,
+    /// seconds booked per week (monday to sunday in UTC) by resource id, an id ending with `*`
+    /// counts all the resources with the prefix
+    #[serde(default)]
+    pub weekly: HashMap<String, u64>,
+}
+
+/// the restricted resources whose reservations are confirmed by the approvers only
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalConfig {
+    /// policies by resource id, an id ending with `*` matches the ids with the prefix
+    #[serde(default)]
+    pub resources: HashMap<String, ApprovalPolicy>,
+}
+
+/// the steps are approved in order, the reservation is confirmed after the last one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    pub steps: Vec<ApprovalStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalStep {
+    pub approvers: Vec<String>,
+    #[serde(default)]
+    pub mode: ApprovalMode,
+}
+
+/// the privileged users of the service
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// verified users who could read the audit log and purge the reservations
+    #[serde(default)]
+    pub admins: Vec<String>,
+}
+
+/// how many approvers of a step must approve
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalMode {
+    #[default]
+    AnyOf,
+    AllOf,
 }
 
 impl Config {
@@ -56,6 +594,98 @@ impl DbConfig {
     }
 }
 
+impl BufferConfig {
+    /// the buffer of the exact id, or of the longest matched prefix, or the default one
+    pub fn for_resource(&self, resource_id: &str) -> Buffer {
+        match_resource(&self.resources, resource_id)
+            .map(|(_, buffer)| *buffer)
+            .unwrap_or(self.default)
+    }
+}
+
+impl RuleConfig {
+    /// the rules of the exact id, or of the longest matched prefix, or the default ones
+    pub fn for_resource(&self, resource_id: &str) -> &BookingRules {
+        match_resource(&self.resources, resource_id)
+            .map(|(_, rules)| rules)
+            .unwrap_or(&self.default)
+    }
+}
+
+impl ApprovalConfig {
+    /// the policy of the exact id, or of the longest matched prefix, None if not restricted
+    pub fn for_resource(&self, resource_id: &str) -> Option<&ApprovalPolicy> {
+        match_resource(&self.resources, resource_id).map(|(_, policy)| policy)
+    }
+}
+
+impl ApprovalPolicy {
+    /// the first step which is not approved yet by the approvers, None if all are approved
+    pub fn current_step(&self, approved: &[(usize, String)]) -> Option<usize> {
+        self.steps.iter().enumerate().position(|(i, step)| {
+            let approved_by = |approver: &String| approved.contains(&(i, approver.clone()));
+            match step.mode {
+                ApprovalMode::AnyOf => !step.approvers.iter().any(approved_by),
+                ApprovalMode::AllOf => !step.approvers.iter().all(approved_by),
+            }
+        })
+    }
+}
+
+impl QuotaConfig {
+    pub fn is_empty(&self) -> bool {
+        self.max_active.is_none() && self.max_concurrent.is_none() && self.weekly.is_empty()
+    }
+
+    /// the pattern and the seconds per week which limit the bookings of the resource
+    pub fn weekly_for_resource(&self, resource_id: &str) -> Option<(&str, u64)> {
+        match_resource(&self.weekly, resource_id).map(|(pattern, secs)| (pattern.as_str(), *secs))
+    }
+}
+
+/// the entry of the exact id, or of the longest matched prefix
+fn match_resource<'a, T>(
+    resources: &'a HashMap<String, T>,
+    resource_id: &str,
+) -> Option<(&'a String, &'a T)> {
+    if let Some(entry) = resources.get_key_value(resource_id) {
+        return Some(entry);
+    }
+    resources
+        .iter()
+        .filter(|(pattern, _)| {
+            pattern
+                .strip_suffix('*')
+                .is_some_and(|prefix| resource_id.starts_with(prefix))
+        })
+        .max_by_key(|(pattern, _)| pattern.len())
+}
+
+impl TryFrom<String> for OpeningHours {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid opening hours: {s}");
+        let time = |t: &str| -> Option<u32> {
+            let (h, m) = t.trim().split_once(':')?;
+            let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+            (m < 60 && (h < 24 || (h, m) == (24, 0))).then_some(h * 3600 + m * 60)
+        };
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        match (time(start), time(end)) {
+            (Some(start), Some(end)) if start < end => Ok(Self { start, end }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl From<OpeningHours> for String {
+    fn from(hours: OpeningHours) -> Self {
+        let time = |t: u32| format!("{:02}:{:02}", t / 3600, t % 3600 / 60);
+        format!("{}-{}", time(hours.start), time(hours.end))
+    }
+}
+
 impl ServerConfig {
     pub fn url(&self, https: bool) -> String {
         if https {
@@ -87,8 +717,220 @@ mod tests {
                 server: ServerConfig {
                     host: "0.0.0.0".to_string(),
                     port: 50051,
-                }
+                    shutdown_timeout: 30,
+                },
+                metrics: MetricsConfig {
+                    host: "0.0.0.0".to_string(),
+                    port: 9090,
+                    path: "/metrics".to_string(),
+                },
+                tracing: TracingConfig {
+                    filter: "info,sqlx=warn".to_string(),
+                    json: false,
+                    otlp_endpoint: None,
+                    service_name: "rorder".to_string(),
+                },
+                rate_limit: RateLimitConfig {
+                    max_concurrency: 100,
+                    default: None,
+                    rpcs: HashMap::from([(
+                        "add".to_string(),
+                        BucketConfig {
+                            burst: 10,
+                            per_second: 5,
+                        },
+                    )]),
+                },
+                rest: RestConfig {
+                    host: "0.0.0.0".to_string(),
+                    port: 8080,
+                },
+                waitlist: WaitlistConfig {
+                    mode: WaitlistMode::Reserve,
+                    sweep_interval: 60,
+                },
+                hold: HoldConfig {
+                    default_ttl: 600,
+                    max_ttl: 3600,
+                    sweep_interval: 30,
+                },
+                buffers: BufferConfig {
+                    default: Buffer::default(),
+                    resources: HashMap::from([
+                        (
+                            "meeting-room-*".to_string(),
+                            Buffer {
+                                before: 0,
+                                after: 900,
+                            },
+                        ),
+                        (
+                            "vehicle-*".to_string(),
+                            Buffer {
+                                before: 0,
+                                after: 3600,
+                            },
+                        ),
+                    ]),
+                },
+                rules: RuleConfig {
+                    default: BookingRules::default(),
+                    resources: HashMap::from([(
+                        "meeting-room-*".to_string(),
+                        BookingRules {
+                            timezone: chrono_tz::Asia::Shanghai,
+                            opening_hours: [
+                                Weekday::Mon,
+                                Weekday::Tue,
+                                Weekday::Wed,
+                                Weekday::Thu,
+                                Weekday::Fri,
+                            ]
+                            .into_iter()
+                            .map(|day| {
+                                let hours = OpeningHours {
+                                    start: 8 * 3600,
+                                    end: 20 * 3600,
+                                };
+                                (day, vec![hours])
+                            })
+                            .collect(),
+                            blackouts: vec![Blackout {
+                                start: "2023-09-30T16:00:00Z".parse().unwrap(),
+                                end: "2023-10-07T16:00:00Z".parse().unwrap(),
+                                reason: "national day".to_string(),
+                            }],
+                            min_duration: Some(900),
+                            max_duration: Some(28800),
+                            min_lead_time: Some(0),
+                            max_horizon: Some(7776000),
+                        },
+                    )]),
+                },
+                quotas: QuotaConfig {
+                    max_active: None,
+                    max_concurrent: None,
+                    weekly: HashMap::from([("meeting-room-*".to_string(), 36000)]),
+                },
+                approvals: ApprovalConfig {
+                    resources: HashMap::from([(
+                        "boardroom-*".to_string(),
+                        ApprovalPolicy {
+                            steps: vec![
+                                ApprovalStep {
+                                    approvers: vec!["alice".to_string(), "bob".to_string()],
+                                    mode: ApprovalMode::AnyOf,
+                                },
+                                ApprovalStep {
+                                    approvers: vec!["carol".to_string()],
+                                    mode: ApprovalMode::AllOf,
+                                },
+                            ],
+                        },
+                    )]),
+                },
+                auth: AuthConfig {
+                    admins: vec!["admin".to_string()],
+                },
+                retention: RetentionConfig {
+                    max_age: Some(31536000),
+                    sweep_interval: 3600,
+                },
+                check_in: CheckInConfig {
+                    early: 900,
+                    grace_period: Some(900),
+                    sweep_interval: 60,
+                },
+                webhooks: WebhookConfig {
+                    sweep_interval: 5,
+                    timeout: 10,
+                    max_attempts: 8,
+                    initial_backoff: 10,
+                    max_backoff: 3600,
+                },
+                tenancy: TenancyConfig {
+                    tenants: HashMap::from([(
+                        "acme".to_string(),
+                        TenantConfig {
+                            quotas: Some(QuotaConfig {
+                                max_active: Some(5),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                    )]),
+                    ..Default::default()
+                },
             }
         );
     }
+
+    #[test]
+    fn buffer_should_match_the_longest_prefix() {
+        let buffer = |before, after| Buffer { before, after };
+        let config = BufferConfig {
+            default: buffer(0, 60),
+            resources: HashMap::from([
+                ("room-*".to_string(), buffer(0, 900)),
+                ("room-vip-*".to_string(), buffer(600, 1800)),
+                ("room-vip-1".to_string(), buffer(0, 0)),
+            ]),
+        };
+        assert_eq!(buffer(0, 900), config.for_resource("room-1"));
+        assert_eq!(buffer(600, 1800), config.for_resource("room-vip-2"));
+        assert_eq!(buffer(0, 0), config.for_resource("room-vip-1"));
+        assert_eq!(buffer(0, 60), config.for_resource("vehicle-1"));
+
+        let rules = RuleConfig {
+            default: BookingRules::default(),
+            resources: HashMap::from([(
+                "room-*".to_string(),
+                BookingRules {
+                    max_duration: Some(3600),
+                    ..Default::default()
+                },
+            )]),
+        };
+        assert_eq!(Some(3600), rules.for_resource("room-1").max_duration);
+        assert_eq!(None, rules.for_resource("vehicle-1").max_duration);
+
+        let quotas = QuotaConfig {
+            weekly: HashMap::from([("room-*".to_string(), 36000)]),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(("room-*", 36000)),
+            quotas.weekly_for_resource("room-2")
+        );
+        assert_eq!(None, quotas.weekly_for_resource("vehicle-1"));
+    }
+
+    #[test]
+    fn approval_step_should_follow_the_mode() {
+        let step = |approvers: &[&str], mode| ApprovalStep {
+            approvers: approvers.iter().map(|a| a.to_string()).collect(),
+            mode,
+        };
+        let policy = ApprovalPolicy {
+            steps: vec![
+                step(&["alice", "bob"], ApprovalMode::AnyOf),
+                step(&["carol", "dave"], ApprovalMode::AllOf),
+            ],
+        };
+        let approved = |list: &[(usize, &str)]| -> Vec<(usize, String)> {
+            list.iter().map(|(i, a)| (*i, a.to_string())).collect()
+        };
+        assert_eq!(Some(0), policy.current_step(&[]));
+        assert_eq!(Some(1), policy.current_step(&approved(&[(0, "bob")])));
+        assert_eq!(
+            Some(1),
+            policy.current_step(&approved(&[(0, "alice"), (1, "carol")]))
+        );
+        // the approval of another step does not count
+        assert_eq!(Some(0), policy.current_step(&approved(&[(1, "alice")])));
+        assert_eq!(
+            None,
+            policy.current_step(&approved(&[(0, "alice"), (1, "carol"), (1, "dave")]))
+        );
+    }
 }
@@ -1,10 +1,10 @@
+use crate::{convert_to_timestamp, convert_to_utc_time, pb, Reservation, ReservationStatus};
 use chrono::{DateTime, Utc};
-use regex::Regex;
-use std::{collections::HashMap, convert::Infallible, str::FromStr};
+use std::fmt;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ReservationConflictInfo {
-    Parsed(ReservationConflict),
+    Parsed(Box<ReservationConflict>),
     Unparsed(String),
 }
 
@@ -12,6 +12,16 @@ pub enum ReservationConflictInfo {
 pub struct ReservationConflict {
     pub new: ReservationWindow,
     pub old: ReservationWindow,
+    /// the existing reservation which occupies the resource
+    pub conflicting_id: i64,
+    pub conflicting_user_id: String,
+    pub conflicting_status: ReservationStatus,
+    /// the booked periods do not overlap, they collide in the buffer time of the resource
+    pub buffer: bool,
+    /// free windows of the resource which could be booked instead
+    pub alternatives: Vec<ReservationWindow>,
+    /// the latest end the reservation could be extended to, if an extension is blocked
+    pub max_end: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -21,90 +31,122 @@ pub struct ReservationWindow {
     pub end: DateTime<Utc>,
 }
 
-impl FromStr for ReservationConflictInfo {
-    type Err = Infallible;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(conflict) = s.parse() {
-            Ok(ReservationConflictInfo::Parsed(conflict))
-        } else {
-            Ok(ReservationConflictInfo::Unparsed(s.to_string()))
+impl ReservationConflict {
+    /// the new reservation is rejected because of the existing one
+    pub fn new(new: &Reservation, existing: &Reservation) -> Self {
+        let new: ReservationWindow = new.into();
+        let old: ReservationWindow = existing.into();
+        Self {
+            buffer: new.end <= old.start || old.end <= new.start,
+            new,
+            old,
+            conflicting_id: existing.id,
+            conflicting_user_id: existing.user_id.clone(),
+            conflicting_status: ReservationStatus::from_i32(existing.status)
+                .unwrap_or(ReservationStatus::Unknown),
+            alternatives: vec![],
+            max_end: None,
         }
     }
 }
 
-/// error message
-///Key (resource_id, rperiod)=(ocean roon-745, [\"2022-11-04 07:00:00+00\",\"2022-11-08 04:00:00+00\"))
-/// conflicts with existing
-/// key (resource_id, rperiod)=(ocean roon-745, [\"2022-11-01 07:00:00+00\",\"2022-11-07 04:00:00+00\"))
-impl FromStr for ReservationConflict {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        ParsedInfo::from_str(s)?.try_into()
+impl From<&Reservation> for ReservationWindow {
+    fn from(rsvp: &Reservation) -> Self {
+        let time =
+            |ts: Option<&prost_types::Timestamp>| ts.map(convert_to_utc_time).unwrap_or_default();
+        Self {
+            rid: rsvp.resource_id.clone(),
+            start: time(rsvp.start_time.as_ref()),
+            end: time(rsvp.end_time.as_ref()),
+        }
     }
 }
 
-#[derive(Debug)]
-struct ParsedInfo {
-    new: HashMap<String, String>,
-    old: HashMap<String, String>,
+impl fmt::Display for ReservationConflictInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReservationConflictInfo::Parsed(conflict) => write!(f, "{conflict}"),
+            ReservationConflictInfo::Unparsed(s) => write!(f, "{s}"),
+        }
+    }
 }
 
-impl TryFrom<ParsedInfo> for ReservationConflict {
-    type Error = ();
-
-    fn try_from(value: ParsedInfo) -> Result<Self, Self::Error> {
-        Ok(Self {
-            new: value.new.try_into()?,
-            old: value.old.try_into()?,
-        })
+impl fmt::Display for ReservationConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cause = if self.buffer {
+            "is within the buffer time of the booking"
+        } else {
+            "is already booked"
+        };
+        write!(
+            f,
+            "{} {} from {} to {} by reservation #{}",
+            self.old.rid,
+            cause,
+            self.old.start.to_rfc3339(),
+            self.old.end.to_rfc3339(),
+            self.conflicting_id
+        )?;
+        match self.max_end {
+            Some(max_end) => write!(f, ", it could be extended to {}", max_end.to_rfc3339()),
+            None => Ok(()),
+        }
     }
 }
 
-impl TryFrom<HashMap<String, String>> for ReservationWindow {
-    type Error = ();
-
-    fn try_from(value: HashMap<String, String>) -> Result<Self, Self::Error> {
-        let rperoid_str = value.get("rperiod").ok_or(())?.replace(['"', '['], "");
-        let mut period = rperoid_str.splitn(2, ',');
-        let start = parse_datetime(period.next().ok_or(())?.trim())?;
-        let end = parse_datetime(period.next().ok_or(())?.trim())?;
-        Ok(Self {
-            rid: value.get("resource_id").ok_or(())?.to_string(),
-            start,
-            end,
-        })
+impl From<&ReservationConflict> for pb::ReservationConflict {
+    fn from(conflict: &ReservationConflict) -> Self {
+        Self {
+            resource_id: conflict.new.rid.clone(),
+            start: Some(convert_to_timestamp(conflict.new.start)),
+            end: Some(convert_to_timestamp(conflict.new.end)),
+            conflicting_id: conflict.conflicting_id,
+            conflicting_start: Some(convert_to_timestamp(conflict.old.start)),
+            conflicting_end: Some(convert_to_timestamp(conflict.old.end)),
+            conflicting_user_id: conflict.conflicting_user_id.clone(),
+            conflicting_status: conflict.conflicting_status as i32,
+            buffer: conflict.buffer,
+            alternatives: conflict
+                .alternatives
+                .iter()
+                .map(|w| pb::TimeWindow {
+                    start: Some(convert_to_timestamp(w.start)),
+                    end: Some(convert_to_timestamp(w.end)),
+                })
+                .collect(),
+            max_end: conflict.max_end.map(convert_to_timestamp),
+        }
     }
 }
 
-fn parse_datetime(s: &str) -> Result<DateTime<Utc>, ()> {
-    Ok(DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%#z")
-        .map_err(|e| println!("err is: {e}"))?
-        .into())
-}
+impl TryFrom<pb::ReservationConflict> for ReservationConflict {
+    type Error = ();
 
-impl FromStr for ParsedInfo {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // \((?P<k1>[a-zA-Z0-9_-]+),\s*(?P<k2>[a-zA-Z0-9]+)\)=\((?P<v1>[a-zA-Z0-9_-]+\s*[a-zA-Z0-9_-]+),\s*(?P<v2>\[[^\)\]]+)
-        let re = Regex::new(
-            r"\((?P<k1>[a-zA-Z0-9_-]+),\s*(?P<k2>[a-zA-Z0-9]+)\)=\((?P<v1>[a-zA-Z0-9_-]+\s*[a-zA-Z0-9_-]+),\s*(?P<v2>\[[^\)\]]+)",
-        ).unwrap();
-        let mut result = vec![];
-        for caps in re.captures_iter(s) {
-            let mut map = HashMap::new();
-            map.insert(caps["k1"].to_string(), caps["v1"].to_string());
-            map.insert(caps["k2"].to_string(), caps["v2"].to_string());
-            result.push(Some(map));
-        }
-        if result.len() != 2 {
-            return Err(());
-        }
-        Ok(ParsedInfo {
-            new: result[0].take().unwrap(),
-            old: result[1].take().unwrap(),
+    fn try_from(conflict: pb::ReservationConflict) -> Result<Self, Self::Error> {
+        let window = |start: Option<_>, end: Option<_>| -> Result<_, ()> {
+            Ok(ReservationWindow {
+                rid: conflict.resource_id.clone(),
+                start: convert_to_utc_time(start.as_ref().ok_or(())?),
+                end: convert_to_utc_time(end.as_ref().ok_or(())?),
+            })
+        };
+        Ok(Self {
+            new: window(conflict.start.clone(), conflict.end.clone())?,
+            old: window(
+                conflict.conflicting_start.clone(),
+                conflict.conflicting_end.clone(),
+            )?,
+            conflicting_id: conflict.conflicting_id,
+            conflicting_status: ReservationStatus::from_i32(conflict.conflicting_status)
+                .ok_or(())?,
+            buffer: conflict.buffer,
+            alternatives: conflict
+                .alternatives
+                .iter()
+                .map(|w| window(w.start.clone(), w.end.clone()))
+                .collect::<Result<_, _>>()?,
+            max_end: conflict.max_end.as_ref().map(convert_to_utc_time),
+            conflicting_user_id: conflict.conflicting_user_id,
         })
     }
 }
@@ -113,63 +155,58 @@ impl FromStr for ParsedInfo {
 mod tests {
     use super::*;
 
-    const ERR_MSG: &str = "Key (resource_id, rperiod)=(ocean roon-745, [\"2022-11-04 07:00:00+00\",
-    \"2022-11-08 04:00:00+00\")) conflicts with existing key (resource_id, rperiod)=(ocean roon-745,
-        [\"2022-11-01 07:00:00+00\",\"2022-11-07 04:00:00+00\"))";
-
-    #[test]
-    fn parse_datatime_should_work() {
-        let dt = parse_datetime("2022-11-04 07:00:00+00").unwrap();
-        assert_eq!(dt.to_rfc3339(), "2022-11-04T07:00:00+00:00");
-    }
-
     #[test]
-    fn get_regex_content() {
-        let result: ParsedInfo = ERR_MSG.parse().unwrap();
-        assert_eq!(
-            result.new.get_key_value("resource_id").unwrap(),
-            (&"resource_id".to_string(), &"ocean roon-745".to_string())
+    fn conflict_should_be_built_from_reservations() {
+        let new = Reservation::new_pending(
+            "wxy",
+            "ocean room, 745 号",
+            "2022-11-04T15:00:00+0800".parse().unwrap(),
+            "2022-11-08T12:00:00+0800".parse().unwrap(),
+            "love this room",
         );
-    }
-
-    #[test]
-    fn hash_map_to_reservation_window_should_work() {
-        let mut map = HashMap::new();
-        map.insert("resource_id".to_string(), "ocean roon-745".to_string());
-        map.insert(
-            "rperiod".to_string(),
-            "[\"2022-11-04 07:00:00+00\",\"2022-11-08 04:00:00+00\"".to_string(),
-        );
-        let reservation_window: ReservationWindow = map.try_into().unwrap();
-        assert_eq!("ocean roon-745", reservation_window.rid);
-        let start: DateTime<Utc> = parse_datetime("2022-11-04 07:00:00+00").unwrap();
-        assert_eq!(start, reservation_window.start);
-        let end: DateTime<Utc> = parse_datetime("2022-11-08 04:00:00+00").unwrap();
-        assert_eq!(end, reservation_window.end);
-    }
-
-    #[test]
-    fn conflict_error_message_should_parse() {
-        let result: ParsedInfo = ERR_MSG.parse().unwrap();
-        println!("result is : {result:?}");
-        let reservation_conflict: ReservationConflict = result.try_into().unwrap();
-        assert_eq!("ocean roon-745", reservation_conflict.new.rid);
-        assert_eq!(
-            parse_datetime("2022-11-04 07:00:00+00").unwrap(),
-            reservation_conflict.new.start
+        let mut existing = Reservation::new_pending(
+            "tosei",
+            "ocean room, 745 号",
+            "2022-11-01T15:00:00+0800".parse().unwrap(),
+            "2022-11-07T12:00:00+0800".parse().unwrap(),
+            "please check the room for me",
         );
+        existing.id = 7;
+        existing.status = ReservationStatus::Confirmed as i32;
+
+        let conflict = ReservationConflict::new(&new, &existing);
+        assert_eq!("ocean room, 745 号", conflict.new.rid);
+        assert_eq!("2022-11-04T07:00:00+00:00", conflict.new.start.to_rfc3339());
+        assert_eq!("2022-11-07T04:00:00+00:00", conflict.old.end.to_rfc3339());
+        assert_eq!(7, conflict.conflicting_id);
+        assert_eq!("tosei", conflict.conflicting_user_id);
+        assert_eq!(ReservationStatus::Confirmed, conflict.conflicting_status);
+
+        assert!(conflict.alternatives.is_empty());
+        assert!(!conflict.buffer);
         assert_eq!(
-            parse_datetime("2022-11-08 04:00:00+00").unwrap(),
-            reservation_conflict.new.end
+            "ocean room, 745 号 is already booked from 2022-11-01T07:00:00+00:00 to 2022-11-07T04:00:00+00:00 by reservation #7",
+            conflict.to_string()
         );
-        assert_eq!("ocean roon-745", reservation_conflict.old.rid);
-        assert_eq!(
-            parse_datetime("2022-11-01 07:00:00+00").unwrap(),
-            reservation_conflict.old.start
-        );
-        assert_eq!(
-            parse_datetime("2022-11-07 04:00:00+00").unwrap(),
-            reservation_conflict.old.end
+
+        // the new one starts right after the existing one, it is in the buffer time
+        let next = Reservation::new_pending(
+            "wxy",
+            "ocean room, 745 号",
+            "2022-11-07T12:00:00+0800".parse().unwrap(),
+            "2022-11-08T12:00:00+0800".parse().unwrap(),
+            "",
         );
+        assert!(ReservationConflict::new(&next, &existing).buffer);
+
+        let mut conflict = conflict;
+        conflict.max_end = Some("2022-11-01T06:00:00Z".parse().unwrap());
+        conflict.alternatives.push(ReservationWindow {
+            rid: "ocean room, 745 号".to_string(),
+            start: "2022-11-07T04:00:00Z".parse().unwrap(),
+            end: "2022-11-11T01:00:00Z".parse().unwrap(),
+        });
+        let details = pb::ReservationConflict::from(&conflict);
+        assert_eq!(Ok(conflict), details.try_into());
     }
 }
@@ -2,8 +2,12 @@ mod conflict;
 
 pub use conflict::*;
 
+use prost::Message;
+use prost_types::Any;
 use sqlx::postgres::PgDatabaseError;
+use std::collections::HashMap;
 use thiserror::Error;
+use tonic_types::{pb::ErrorInfo, Status as RpcStatus};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -40,9 +44,70 @@ pub enum Error {
     #[error("Invalid status: {0}")]
     InvalidStatus(i32),
 
+    #[error("Invalid status name: {0}")]
+    InvalidStatusName(String),
+
+    #[error("Invalid webhook: {0}")]
+    InvalidWebhook(String),
+
+    #[error("Invalid calendar: {0}")]
+    InvalidCalendar(String),
+
+    #[error("Invalid timezone: {0}")]
+    InvalidTimezone(String),
+
+    #[error("Invalid period: {0}")]
+    InvalidPeriod(String),
+
+    #[error("Invalid check-in: {0}")]
+    InvalidCheckIn(String),
+
+    #[error("Invalid change: {0}")]
+    InvalidChange(String),
+
+    #[error("Outside the opening hours of the resource: {0}")]
+    OutsideOpeningHours(String),
+
+    #[error("Within a blackout period of the resource: {0}")]
+    InBlackoutPeriod(String),
+
+    #[error("Shorter than the minimum duration in seconds: {0}")]
+    DurationTooShort(u64),
+
+    #[error("Longer than the maximum duration in seconds: {0}")]
+    DurationTooLong(u64),
+
+    #[error("Sooner than the minimum lead time in seconds: {0}")]
+    LeadTimeTooShort(u64),
+
+    #[error("Beyond the booking horizon in seconds: {0}")]
+    BeyondBookingHorizon(u64),
+
+    #[error("Quota exceeded: {quota}, limit {limit}, usage {usage}")]
+    QuotaExceeded {
+        quota: String,
+        limit: u64,
+        usage: u64,
+    },
+
+    #[error("Approval required for the resource: {0}")]
+    ApprovalRequired(String),
+
+    #[error("Not an approver of the current step: {0}")]
+    NotApprover(String),
+
+    #[error("Not an admin: {0}")]
+    NotAdmin(String),
+
+    #[error("A verified user is required")]
+    Unauthenticated,
+
     #[error("No reservation found by the given condition")]
     NotFound,
 
+    #[error("Rpc error: {0}")]
+    RpcError(Box<tonic::Status>),
+
     #[error("unknown error")]
     Unknown,
 }
@@ -53,8 +118,12 @@ impl From<sqlx::Error> for Error {
             sqlx::Error::Database(e) => {
                 let err: &PgDatabaseError = e.downcast_ref();
                 match (err.code(), err.schema(), err.table()) {
+                    // the manager looks up the existing reservation to fill the conflict
                     ("23P01", Some("rsvt"), Some("reservations")) => {
-                        Error::ConfilictReservation(err.detail().unwrap().parse().unwrap())
+                        let detail = err.detail().unwrap_or_else(|| err.message());
+                        Error::ConfilictReservation(ReservationConflictInfo::Unparsed(
+                            detail.to_string(),
+                        ))
                     }
                     _ => Error::DbError(sqlx::Error::Database(e)),
                 }
@@ -70,12 +139,49 @@ impl PartialEq for Error {
         match (self, other) {
             // TODO: this is not a good way to compare DB errors, but we don't do that in the code
             (Self::DbError(_), Self::DbError(_)) => true,
+            (Self::ConfigReadError, Self::ConfigReadError) => true,
+            (Self::ConfigParseError, Self::ConfigParseError) => true,
             (Self::InvalidTime, Self::InvalidTime) => true,
             (Self::ConfilictReservation(v1), Self::ConfilictReservation(v2)) => v1 == v2,
             (Self::NotFound, Self::NotFound) => true,
+            (Self::ApprovalRequired(v1), Self::ApprovalRequired(v2)) => v1 == v2,
+            (Self::NotApprover(v1), Self::NotApprover(v2)) => v1 == v2,
+            (Self::NotAdmin(v1), Self::NotAdmin(v2)) => v1 == v2,
+            (Self::Unauthenticated, Self::Unauthenticated) => true,
+            (
+                Self::QuotaExceeded {
+                    quota: q1,
+                    limit: l1,
+                    usage: u1,
+                },
+                Self::QuotaExceeded {
+                    quota: q2,
+                    limit: l2,
+                    usage: u2,
+                },
+            ) => q1 == q2 && l1 == l2 && u1 == u2,
             (Self::InvalidResourceId(v1), Self::InvalidResourceId(v2)) => v1 == v2,
             (Self::InvalidUserId(v1), Self::InvalidUserId(v2)) => v1 == v2,
-            // (Self::InvalidResourceId(v1), Self::InvalidResourceId(v2)) => v1 == v2,
+            (Self::InvalidReservationId(v1), Self::InvalidReservationId(v2)) => v1 == v2,
+            (Self::InvalidPageSize(v1), Self::InvalidPageSize(v2)) => v1 == v2,
+            (Self::InvalidCursor(v1), Self::InvalidCursor(v2)) => v1 == v2,
+            (Self::InvalidStatus(v1), Self::InvalidStatus(v2)) => v1 == v2,
+            (Self::InvalidStatusName(v1), Self::InvalidStatusName(v2)) => v1 == v2,
+            (Self::InvalidWebhook(v1), Self::InvalidWebhook(v2)) => v1 == v2,
+            (Self::InvalidCalendar(v1), Self::InvalidCalendar(v2)) => v1 == v2,
+            (Self::InvalidTimezone(v1), Self::InvalidTimezone(v2)) => v1 == v2,
+            (Self::InvalidPeriod(v1), Self::InvalidPeriod(v2)) => v1 == v2,
+            (Self::InvalidCheckIn(v1), Self::InvalidCheckIn(v2)) => v1 == v2,
+            (Self::InvalidChange(v1), Self::InvalidChange(v2)) => v1 == v2,
+            (Self::OutsideOpeningHours(v1), Self::OutsideOpeningHours(v2)) => v1 == v2,
+            (Self::InBlackoutPeriod(v1), Self::InBlackoutPeriod(v2)) => v1 == v2,
+            (Self::DurationTooShort(v1), Self::DurationTooShort(v2)) => v1 == v2,
+            (Self::DurationTooLong(v1), Self::DurationTooLong(v2)) => v1 == v2,
+            (Self::LeadTimeTooShort(v1), Self::LeadTimeTooShort(v2)) => v1 == v2,
+            (Self::BeyondBookingHorizon(v1), Self::BeyondBookingHorizon(v2)) => v1 == v2,
+            (Self::RpcError(v1), Self::RpcError(v2)) => {
+                v1.code() == v2.code() && v1.message() == v2.message()
+            }
             (Self::Unknown, Self::Unknown) => true,
             _ => false,
         }
@@ -95,14 +201,375 @@ impl From<Error> for tonic::Status {
             | Error::InvalidResourceId(_)
             | Error::InvalidPageSize(_)
             | Error::InvalidCursor(_)
-            | Error::InvalidStatus(_) => tonic::Status::invalid_argument(e.to_string()),
-            Error::ConfilictReservation(info) => {
-                tonic::Status::failed_precondition(format!("Conflict reservation: {info:?}"))
+            | Error::InvalidStatus(_)
+            | Error::InvalidStatusName(_)
+            | Error::InvalidWebhook(_)
+            | Error::InvalidCalendar(_)
+            | Error::InvalidTimezone(_)
+            | Error::InvalidPeriod(_)
+            | Error::InvalidCheckIn(_)
+            | Error::InvalidChange(_)
+            | Error::OutsideOpeningHours(_)
+            | Error::InBlackoutPeriod(_)
+            | Error::DurationTooShort(_)
+            | Error::DurationTooLong(_)
+            | Error::LeadTimeTooShort(_)
+            | Error::BeyondBookingHorizon(_) => reason_status(tonic::Code::InvalidArgument, &e),
+            Error::ConfilictReservation(info) => conflict_status(&info),
+            Error::QuotaExceeded { .. } => quota_status(&e),
+            Error::ApprovalRequired(_) | Error::NotApprover(_) | Error::NotAdmin(_) => {
+                reason_status(tonic::Code::PermissionDenied, &e)
             }
+            Error::Unauthenticated => reason_status(tonic::Code::Unauthenticated, &e),
             Error::NotFound => {
                 tonic::Status::not_found("No reservation found by the given condition")
             }
+            Error::RpcError(status) => *status,
             Error::Unknown => tonic::Status::unknown("unknown error"),
         }
     }
 }
+
+// from tonic Status back to Error, used by the client
+impl From<tonic::Status> for Error {
+    fn from(status: tonic::Status) -> Self {
+        match status.code() {
+            tonic::Code::FailedPrecondition => Error::ConfilictReservation(conflict_info(&status)),
+            tonic::Code::NotFound => Error::NotFound,
+            // the rate limiter also returns resource exhausted, but without the error info
+            tonic::Code::ResourceExhausted => {
+                quota_exceeded(&status).unwrap_or(Error::RpcError(Box::new(status)))
+            }
+            // the tenant layer and the rate limiter reject without the error info
+            tonic::Code::InvalidArgument
+            | tonic::Code::PermissionDenied
+            | tonic::Code::Unauthenticated => {
+                from_reason(&status).unwrap_or(Error::RpcError(Box::new(status)))
+            }
+            _ => Error::RpcError(Box::new(status)),
+        }
+    }
+}
+
+const CONFLICT_REASON: &str = "RESERVATION_CONFLICT";
+const QUOTA_REASON: &str = "QUOTA_EXCEEDED";
+const CONFLICT_PREFIX: &str = "Conflict reservation: ";
+const ERROR_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+pub const CONFLICT_TYPE_URL: &str = "type.googleapis.com/rsvp.ReservationConflict";
+
+/// the conflict is encoded as google.rpc.Status details: an ErrorInfo and a ReservationConflict
+fn conflict_status(info: &ReservationConflictInfo) -> tonic::Status {
+    let code = tonic::Code::FailedPrecondition;
+    let message = format!("{CONFLICT_PREFIX}{info}");
+    let mut metadata = HashMap::new();
+    let mut conflict = None;
+    if let ReservationConflictInfo::Parsed(parsed) = info {
+        metadata.insert("resource_id".to_string(), parsed.new.rid.clone());
+        metadata.insert(
+            "conflicting_id".to_string(),
+            parsed.conflicting_id.to_string(),
+        );
+        let cause = if parsed.buffer { "buffer" } else { "booking" };
+        metadata.insert("cause".to_string(), cause.to_string());
+        if let Some(max_end) = parsed.max_end {
+            metadata.insert("max_end".to_string(), max_end.to_rfc3339());
+        }
+        conflict = Some(Any {
+            type_url: CONFLICT_TYPE_URL.to_string(),
+            value: crate::pb::ReservationConflict::from(parsed.as_ref()).encode_to_vec(),
+        });
+    }
+    let error_info = ErrorInfo {
+        reason: CONFLICT_REASON.to_string(),
+        domain: "rsvp".to_string(),
+        metadata,
+    };
+    let error_info = Any {
+        type_url: ERROR_INFO_TYPE_URL.to_string(),
+        value: error_info.encode_to_vec(),
+    };
+
+    let status = RpcStatus {
+        code: code as i32,
+        message: message.clone(),
+        details: std::iter::once(error_info).chain(conflict).collect(),
+    };
+    tonic::Status::with_details(code, message, status.encode_to_vec().into())
+}
+
+/// the quota, limit and usage are kept in the metadata of an ErrorInfo
+fn quota_status(e: &Error) -> tonic::Status {
+    let mut metadata = HashMap::new();
+    if let Error::QuotaExceeded {
+        quota,
+        limit,
+        usage,
+    } = e
+    {
+        metadata.insert("quota".to_string(), quota.clone());
+        metadata.insert("limit".to_string(), limit.to_string());
+        metadata.insert("usage".to_string(), usage.to_string());
+    }
+    error_info_status(tonic::Code::ResourceExhausted, e, QUOTA_REASON, metadata)
+}
+
+fn quota_exceeded(status: &tonic::Status) -> Option<Error> {
+    let info = error_info(status).filter(|info| info.reason == QUOTA_REASON)?;
+    Some(Error::QuotaExceeded {
+        quota: info.metadata.get("quota")?.clone(),
+        limit: info.metadata.get("limit")?.parse().ok()?,
+        usage: info.metadata.get("usage")?.parse().ok()?,
+    })
+}
+
+/// the reason of the error is kept in an ErrorInfo and its value in the `value` metadata,
+/// so the client does not depend on the message which is written for humans
+fn reason_status(code: tonic::Code, e: &Error) -> tonic::Status {
+    let (reason, value) = match reason(e) {
+        Some(reason) => reason,
+        None => return tonic::Status::new(code, e.to_string()),
+    };
+    let metadata = value
+        .map(|value| HashMap::from([("value".to_string(), value)]))
+        .unwrap_or_default();
+    error_info_status(code, e, reason, metadata)
+}
+
+fn error_info_status(
+    code: tonic::Code,
+    e: &Error,
+    reason: &str,
+    metadata: HashMap<String, String>,
+) -> tonic::Status {
+    let message = e.to_string();
+    let error_info = ErrorInfo {
+        reason: reason.to_string(),
+        domain: "rsvp".to_string(),
+        metadata,
+    };
+    let status = RpcStatus {
+        code: code as i32,
+        message: message.clone(),
+        details: vec![Any {
+            type_url: ERROR_INFO_TYPE_URL.to_string(),
+            value: error_info.encode_to_vec(),
+        }],
+    };
+    tonic::Status::with_details(code, message, status.encode_to_vec().into())
+}
+
+/// the ErrorInfo of the status details
+fn error_info(status: &tonic::Status) -> Option<ErrorInfo> {
+    RpcStatus::decode(status.details())
+        .ok()?
+        .details
+        .into_iter()
+        .find(|d| d.type_url == ERROR_INFO_TYPE_URL)
+        .and_then(|d| ErrorInfo::decode(d.value.as_slice()).ok())
+}
+
+/// the conflict of the status details, or the message if the details are missing
+pub fn conflict_info(status: &tonic::Status) -> ReservationConflictInfo {
+    let conflict = RpcStatus::decode(status.details())
+        .ok()
+        .and_then(|s| {
+            s.details
+                .into_iter()
+                .find(|d| d.type_url == CONFLICT_TYPE_URL)
+        })
+        .and_then(|d| crate::pb::ReservationConflict::decode(d.value.as_slice()).ok())
+        .and_then(|c| c.try_into().ok());
+    match conflict {
+        Some(conflict) => ReservationConflictInfo::Parsed(Box::new(conflict)),
+        None => {
+            let message = status.message();
+            let message = message.strip_prefix(CONFLICT_PREFIX).unwrap_or(message);
+            ReservationConflictInfo::Unparsed(message.to_string())
+        }
+    }
+}
+
+/// the reason of the errors which are converted to a status with it, and their value
+fn reason(e: &Error) -> Option<(&'static str, Option<String>)> {
+    let reason = match e {
+        Error::InvalidTime => return Some(("INVALID_TIME", None)),
+        Error::Unauthenticated => return Some(("UNAUTHENTICATED", None)),
+        Error::InvalidUserId(v) => ("INVALID_USER_ID", v.clone()),
+        Error::InvalidResourceId(v) => ("INVALID_RESOURCE_ID", v.clone()),
+        Error::InvalidReservationId(v) => ("INVALID_RESERVATION_ID", v.clone()),
+        Error::InvalidPageSize(v) => ("INVALID_PAGE_SIZE", v.to_string()),
+        Error::InvalidCursor(v) => ("INVALID_CURSOR", v.to_string()),
+        Error::InvalidStatus(v) => ("INVALID_STATUS", v.to_string()),
+        Error::InvalidStatusName(v) => ("INVALID_STATUS_NAME", v.clone()),
+        Error::InvalidWebhook(v) => ("INVALID_WEBHOOK", v.clone()),
+        Error::InvalidCalendar(v) => ("INVALID_CALENDAR", v.clone()),
+        Error::InvalidTimezone(v) => ("INVALID_TIMEZONE", v.clone()),
+        Error::InvalidPeriod(v) => ("INVALID_PERIOD", v.clone()),
+        Error::InvalidCheckIn(v) => ("INVALID_CHECK_IN", v.clone()),
+        Error::InvalidChange(v) => ("INVALID_CHANGE", v.clone()),
+        Error::OutsideOpeningHours(v) => ("OUTSIDE_OPENING_HOURS", v.clone()),
+        Error::InBlackoutPeriod(v) => ("IN_BLACKOUT_PERIOD", v.clone()),
+        Error::DurationTooShort(v) => ("DURATION_TOO_SHORT", v.to_string()),
+        Error::DurationTooLong(v) => ("DURATION_TOO_LONG", v.to_string()),
+        Error::LeadTimeTooShort(v) => ("LEAD_TIME_TOO_SHORT", v.to_string()),
+        Error::BeyondBookingHorizon(v) => ("BEYOND_BOOKING_HORIZON", v.to_string()),
+        Error::ApprovalRequired(v) => ("APPROVAL_REQUIRED", v.clone()),
+        Error::NotApprover(v) => ("NOT_APPROVER", v.clone()),
+        Error::NotAdmin(v) => ("NOT_ADMIN", v.clone()),
+        _ => return None,
+    };
+    Some((reason.0, Some(reason.1)))
+}
+
+/// the Error of the reason in the ErrorInfo of the status details
+fn from_reason(status: &tonic::Status) -> Option<Error> {
+    let info = error_info(status)?;
+    let value = info.metadata.get("value").cloned().unwrap_or_default();
+    let error = match info.reason.as_str() {
+        "INVALID_TIME" => Error::InvalidTime,
+        "UNAUTHENTICATED" => Error::Unauthenticated,
+        "INVALID_USER_ID" => Error::InvalidUserId(value),
+        "INVALID_RESOURCE_ID" => Error::InvalidResourceId(value),
+        "INVALID_RESERVATION_ID" => Error::InvalidReservationId(value),
+        "INVALID_PAGE_SIZE" => Error::InvalidPageSize(value.parse().ok()?),
+        "INVALID_CURSOR" => Error::InvalidCursor(value.parse().ok()?),
+        "INVALID_STATUS" => Error::InvalidStatus(value.parse().ok()?),
+        "INVALID_STATUS_NAME" => Error::InvalidStatusName(value),
+        "INVALID_WEBHOOK" => Error::InvalidWebhook(value),
+        "INVALID_CALENDAR" => Error::InvalidCalendar(value),
+        "INVALID_TIMEZONE" => Error::InvalidTimezone(value),
+        "INVALID_PERIOD" => Error::InvalidPeriod(value),
+        "INVALID_CHECK_IN" => Error::InvalidCheckIn(value),
+        "INVALID_CHANGE" => Error::InvalidChange(value),
+        "OUTSIDE_OPENING_HOURS" => Error::OutsideOpeningHours(value),
+        "IN_BLACKOUT_PERIOD" => Error::InBlackoutPeriod(value),
+        "DURATION_TOO_SHORT" => Error::DurationTooShort(value.parse().ok()?),
+        "DURATION_TOO_LONG" => Error::DurationTooLong(value.parse().ok()?),
+        "LEAD_TIME_TOO_SHORT" => Error::LeadTimeTooShort(value.parse().ok()?),
+        "BEYOND_BOOKING_HORIZON" => Error::BeyondBookingHorizon(value.parse().ok()?),
+        "APPROVAL_REQUIRED" => Error::ApprovalRequired(value),
+        "NOT_APPROVER" => Error::NotApprover(value),
+        "NOT_ADMIN" => Error::NotAdmin(value),
+        _ => return None,
+    };
+    Some(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservationStatus;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn conflict_should_be_converted_back_from_status() {
+        let start: DateTime<Utc> = "2022-11-01T07:00:00Z".parse().unwrap();
+        let end: DateTime<Utc> = "2022-11-07T04:00:00Z".parse().unwrap();
+        let window = || ReservationWindow {
+            rid: "ocean room-745".to_string(),
+            start,
+            end,
+        };
+        let conflict = || ReservationConflict {
+            new: window(),
+            old: window(),
+            conflicting_id: 42,
+            conflicting_user_id: "tosei".to_string(),
+            conflicting_status: ReservationStatus::Confirmed,
+            buffer: false,
+            alternatives: vec![window()],
+            max_end: None,
+        };
+        let info = ReservationConflictInfo::Parsed(Box::new(conflict()));
+        let status: tonic::Status = Error::ConfilictReservation(info).into();
+        assert_eq!(tonic::Code::FailedPrecondition, status.code());
+        assert_eq!(
+            "Conflict reservation: ocean room-745 is already booked from 2022-11-01T07:00:00+00:00 to 2022-11-07T04:00:00+00:00 by reservation #42",
+            status.message()
+        );
+
+        // the details is a google.rpc.Status which could be decoded by any grpc client
+        let details = RpcStatus::decode(status.details()).unwrap();
+        assert_eq!(ERROR_INFO_TYPE_URL, details.details[0].type_url);
+        let error_info = ErrorInfo::decode(details.details[0].value.as_slice()).unwrap();
+        assert_eq!(CONFLICT_REASON, error_info.reason);
+        assert_eq!("42", error_info.metadata["conflicting_id"]);
+        assert_eq!("booking", error_info.metadata["cause"]);
+
+        let err: Error = status.into();
+        let expected = ReservationConflictInfo::Parsed(Box::new(conflict()));
+        assert_eq!(Error::ConfilictReservation(expected), err);
+
+        let info = ReservationConflictInfo::Unparsed("unknown conflict".to_string());
+        let status: tonic::Status = Error::ConfilictReservation(info).into();
+        let expected = ReservationConflictInfo::Unparsed("unknown conflict".to_string());
+        assert_eq!(Error::ConfilictReservation(expected), status.into());
+    }
+
+    #[test]
+    fn invalid_argument_should_be_converted_back_from_status() {
+        let status: tonic::Status = Error::InvalidUserId("".to_string()).into();
+        assert_eq!(Error::InvalidUserId("".to_string()), status.into());
+
+        let status: tonic::Status = Error::InvalidTime.into();
+        assert_eq!(Error::InvalidTime, status.into());
+
+        let status: tonic::Status = Error::InvalidPageSize(-1).into();
+        assert_eq!(Error::InvalidPageSize(-1), status.into());
+        let status: tonic::Status = Error::InvalidStatusName("done".to_string()).into();
+        assert_eq!(Error::InvalidStatusName("done".to_string()), status.into());
+        assert_ne!(Error::InvalidCursor(1), Error::InvalidCursor(2));
+
+        let status: tonic::Status = Error::DurationTooLong(28800).into();
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+        assert_eq!(Error::DurationTooLong(28800), status.into());
+
+        let quota = || Error::QuotaExceeded {
+            quota: "weekly:meeting-room-*".to_string(),
+            limit: 36000,
+            usage: 32400,
+        };
+        let status: tonic::Status = quota().into();
+        assert_eq!(tonic::Code::ResourceExhausted, status.code());
+        assert_eq!(
+            "Quota exceeded: weekly:meeting-room-*, limit 36000, usage 32400",
+            status.message()
+        );
+        assert_eq!(quota(), status.into());
+
+        let status = tonic::Status::resource_exhausted("rate limit exceeded");
+        assert!(matches!(Error::from(status), Error::RpcError(_)));
+
+        let status: tonic::Status = Error::NotApprover("mallory".to_string()).into();
+        assert_eq!(tonic::Code::PermissionDenied, status.code());
+        assert_eq!(Error::NotApprover("mallory".to_string()), status.into());
+        let status: tonic::Status = Error::NotAdmin("mallory".to_string()).into();
+        assert_eq!(Error::NotAdmin("mallory".to_string()), status.into());
+        let status: tonic::Status = Error::Unauthenticated.into();
+        assert_eq!(tonic::Code::Unauthenticated, status.code());
+        assert_eq!(Error::Unauthenticated, status.into());
+        let status = tonic::Status::unauthenticated("bearer token is required");
+        assert!(matches!(Error::from(status), Error::RpcError(_)));
+
+        let status = tonic::Status::unavailable("server is shutting down");
+        assert!(matches!(Error::from(status), Error::RpcError(_)));
+    }
+
+    #[test]
+    fn status_should_be_converted_back_by_its_reason() {
+        let status: tonic::Status = Error::LeadTimeTooShort(900).into();
+        let info = error_info(&status).unwrap();
+        assert_eq!("LEAD_TIME_TOO_SHORT", info.reason);
+        assert_eq!("900", info.metadata["value"]);
+
+        // the message is for humans, the client does not read it
+        let status = tonic::Status::with_details(
+            status.code(),
+            "the reservation is too soon",
+            status.details().to_vec().into(),
+        );
+        assert_eq!(Error::LeadTimeTooShort(900), status.into());
+        let status = tonic::Status::permission_denied("Not an admin: mallory");
+        assert!(matches!(Error::from(status), Error::RpcError(_)));
+    }
+}
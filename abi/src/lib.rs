@@ -1,13 +1,18 @@
+mod calendar;
 mod config;
 mod error;
 mod pb;
+pub mod serde_ext;
 mod types;
 mod utils;
 
+pub use calendar::*;
 pub use config::*;
 pub use error::*;
 pub use pb::*;
-pub use types::*;
+// the proto message is the encoding of the conflict in the error details
+pub use error::ReservationConflict;
+pub use pb::ReservationConflict as ReservationConflictDetails;
 pub use utils::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
@@ -16,5 +21,30 @@ enum RsvpStatus {
     Pending,
     Confirmed,
     Blocked,
+    Held,
+    Rejected,
+    #[sqlx(rename = "no_show")]
+    NoShow,
     Unknown,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(type_name = "reservation_update_type", rename_all = "snake_case")]
+enum UpdateType {
+    Unknown,
+    Create,
+    Update,
+    Delete,
+    ApprovalRequested,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(type_name = "waitlist_status", rename_all = "lowercase")]
+enum WaitStatus {
+    Unknown,
+    Waiting,
+    Notified,
+    Fulfilled,
+    Left,
+    Expired,
+}
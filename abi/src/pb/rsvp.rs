@@ -1,4 +1,6 @@
 /// reservation
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Reservation {
@@ -9,13 +11,32 @@ pub struct Reservation {
     #[prost(string, tag = "3")]
     pub resource_id: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "4")]
+    #[serde(with = "crate::serde_ext::timestamp")]
     pub start_time: ::core::option::Option<::prost_types::Timestamp>,
     #[prost(message, optional, tag = "5")]
+    #[serde(with = "crate::serde_ext::timestamp")]
     pub end_time: ::core::option::Option<::prost_types::Timestamp>,
     #[prost(enumeration = "ReservationStatus", tag = "6")]
+    #[serde(with = "crate::serde_ext::status")]
     pub status: i32,
     #[prost(string, tag = "7")]
     pub note: ::prost::alloc::string::String,
+    /// IANA name of the timezone of the reservation, e.g. Asia/Tokyo. If empty, the timezone of
+    /// the resource is used
+    #[prost(string, tag = "8")]
+    pub timezone: ::prost::alloc::string::String,
+    /// start and end time in the timezone of the reservation (rfc3339), set by the server
+    #[prost(string, tag = "9")]
+    pub local_start: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub local_end: ::prost::alloc::string::String,
+    /// when the resource is actually taken and left, set by check_in and check_out
+    #[prost(message, optional, tag = "11")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub checked_in_at: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "12")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub checked_out_at: ::core::option::Option<::prost_types::Timestamp>,
 }
 /// add reservation request
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -46,6 +67,8 @@ pub struct ConfirmResponse {
     pub reservation: ::core::option::Option<Reservation>,
 }
 /// update reservation request
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateRequest {
@@ -90,7 +113,8 @@ pub struct GetResponse {
     pub reservation: ::core::option::Option<Reservation>,
 }
 /// query reservations with user id, resource id, start time, end time, and status
-#[derive(derive_builder::Builder)]
+#[derive(derive_builder::Builder, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ReservationQuery {
@@ -105,14 +129,17 @@ pub struct ReservationQuery {
     /// use status to filter result. If UNKNOWN, return all reservations
     #[prost(enumeration = "ReservationStatus", tag = "3")]
     #[builder(setter(into), default)]
+    #[serde(with = "crate::serde_ext::status")]
     pub status: i32,
     /// start time for the reservation query, if 0, use Infinity for start time
     #[prost(message, optional, tag = "4")]
     #[builder(setter(into, strip_option))]
+    #[serde(with = "crate::serde_ext::timestamp")]
     pub start: ::core::option::Option<::prost_types::Timestamp>,
     /// end time for the reservation query, if 0, use Infinity for end time
     #[prost(message, optional, tag = "5")]
     #[builder(setter(into, strip_option))]
+    #[serde(with = "crate::serde_ext::timestamp")]
     pub end: ::core::option::Option<::prost_types::Timestamp>,
     /// sort direction
     #[prost(bool, tag = "6")]
@@ -126,6 +153,15 @@ pub struct ReservationQuery {
     #[prost(int32, tag = "8")]
     #[builder(setter(into), default)]
     pub page_size: i32,
+    /// "day" or "week" (monday to sunday) containing the start in the timezone, the end is ignored.
+    /// If empty, the range from start to end is queried
+    #[prost(string, tag = "9")]
+    #[builder(setter(into), default)]
+    pub period: ::prost::alloc::string::String,
+    /// IANA name of the timezone of the period, UTC if empty
+    #[prost(string, tag = "10")]
+    #[builder(setter(into), default)]
+    pub timezone: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -148,7 +184,8 @@ pub struct ListenResponse {
     pub reservation: ::core::option::Option<Reservation>,
 }
 /// query reservations, order by reservation id
-#[derive(derive_builder::Builder)]
+#[derive(derive_builder::Builder, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ReservationFilter {
@@ -162,6 +199,7 @@ pub struct ReservationFilter {
     pub user_id: ::prost::alloc::string::String,
     /// use status to filter result. If UNKNOWN, return all reservations
     #[prost(enumeration = "ReservationStatus", tag = "3")]
+    #[serde(with = "crate::serde_ext::status")]
     #[builder(setter(into), default)]
     pub status: i32,
     #[prost(int64, optional, tag = "4")]
@@ -184,6 +222,8 @@ pub struct FilterRequest {
     pub filter: ::core::option::Option<ReservationFilter>,
 }
 /// filter pager info
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FilterPager {
@@ -194,6 +234,8 @@ pub struct FilterPager {
     #[prost(int64, optional, tag = "3")]
     pub total: ::core::option::Option<i64>,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FilterResponse {
@@ -202,393 +244,2363 @@ pub struct FilterResponse {
     #[prost(message, optional, tag = "2")]
     pub pager: ::core::option::Option<FilterPager>,
 }
-/// reservation status
-#[derive(
-    sqlx::Type, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration,
-)]
-#[repr(i32)]
-pub enum ReservationStatus {
-    Unknown = 0,
-    Pending = 1,
-    Confirmed = 2,
-    Cancelled = 3,
+/// details of the FAILED_PRECONDITION error when the reservation conflicts with an existing one,
+/// sent in the details of google.rpc.Status together with google.rpc.ErrorInfo
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReservationConflict {
+    #[prost(string, tag = "1")]
+    pub resource_id: ::prost::alloc::string::String,
+    /// period of the rejected reservation
+    #[prost(message, optional, tag = "2")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub start: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "3")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub end: ::core::option::Option<::prost_types::Timestamp>,
+    /// the existing reservation which occupies the resource
+    #[prost(int64, tag = "4")]
+    pub conflicting_id: i64,
+    #[prost(message, optional, tag = "5")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub conflicting_start: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "6")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub conflicting_end: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(string, tag = "7")]
+    pub conflicting_user_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "ReservationStatus", tag = "8")]
+    #[serde(with = "crate::serde_ext::status")]
+    pub conflicting_status: i32,
+    /// the nearest free windows with the same duration on the resource
+    #[prost(message, repeated, tag = "9")]
+    pub alternatives: ::prost::alloc::vec::Vec<TimeWindow>,
+    /// the booked periods do not overlap, they collide in the buffer time of the resource
+    #[prost(bool, tag = "10")]
+    pub buffer: bool,
+    /// the latest end the reservation could be extended to, set if an extension is blocked
+    #[prost(message, optional, tag = "11")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub max_end: ::core::option::Option<::prost_types::Timestamp>,
 }
-impl ReservationStatus {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            ReservationStatus::Unknown => "RESERVATION_STATUS_UNKNOWN",
-            ReservationStatus::Pending => "RESERVATION_STATUS_PENDING",
-            ReservationStatus::Confirmed => "RESERVATION_STATUS_CONFIRMED",
-            ReservationStatus::Cancelled => "RESERVATION_STATUS_CANCELLED",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "RESERVATION_STATUS_UNKNOWN" => Some(Self::Unknown),
-            "RESERVATION_STATUS_PENDING" => Some(Self::Pending),
-            "RESERVATION_STATUS_CONFIRMED" => Some(Self::Confirmed),
-            "RESERVATION_STATUS_CANCELLED" => Some(Self::Cancelled),
-            _ => None,
-        }
-    }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TimeWindow {
+    #[prost(message, optional, tag = "1")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub start: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "2")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub end: ::core::option::Option<::prost_types::Timestamp>,
 }
-/// when reservation is updated, record the update type
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum ReservationUpdateType {
-    Unknown = 0,
-    Create = 1,
-    Update = 2,
-    Delete = 3,
+/// lock the window of the reservation without booking it
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HoldRequest {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+    /// seconds the window is held, if 0, use the configured ttl
+    #[prost(uint32, tag = "2")]
+    pub ttl_seconds: u32,
 }
-impl ReservationUpdateType {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            ReservationUpdateType::Unknown => "RESERVATION_UPDATE_TYPE_UNKNOWN",
-            ReservationUpdateType::Create => "RESERVATION_UPDATE_TYPE_CREATE",
-            ReservationUpdateType::Update => "RESERVATION_UPDATE_TYPE_UPDATE",
-            ReservationUpdateType::Delete => "RESERVATION_UPDATE_TYPE_DELETE",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "RESERVATION_UPDATE_TYPE_UNKNOWN" => Some(Self::Unknown),
-            "RESERVATION_UPDATE_TYPE_CREATE" => Some(Self::Create),
-            "RESERVATION_UPDATE_TYPE_UPDATE" => Some(Self::Update),
-            "RESERVATION_UPDATE_TYPE_DELETE" => Some(Self::Delete),
-            _ => None,
-        }
-    }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HoldResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+    /// the hold is released after this time unless it is promoted
+    #[prost(message, optional, tag = "2")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub expires_at: ::core::option::Option<::prost_types::Timestamp>,
 }
-/// Generated client implementations.
-pub mod reservation_service_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::http::Uri;
-    use tonic::codegen::*;
-    /// Reservation service
-    #[derive(Debug, Clone)]
-    pub struct ReservationServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl ReservationServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: std::convert::TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> ReservationServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> ReservationServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
-                Into<StdError> + Send + Sync,
-        {
-            ReservationServiceClient::new(InterceptedService::new(inner, interceptor))
-        }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
-        }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
-        }
-        /// make a reservation
-        pub async fn add(
-            &mut self,
-            request: impl tonic::IntoRequest<super::AddRequest>,
-        ) -> Result<tonic::Response<super::AddResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/add");
-            self.inner.unary(request.into_request(), path, codec).await
-        }
-        /// confirm a valid perid resource, if reservation is not pending, do nothing
-        pub async fn confirm(
-            &mut self,
-            request: impl tonic::IntoRequest<super::ConfirmRequest>,
-        ) -> Result<tonic::Response<super::ConfirmResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/confirm");
-            self.inner.unary(request.into_request(), path, codec).await
-        }
-        /// update a reservation
-        pub async fn update(
-            &mut self,
-            request: impl tonic::IntoRequest<super::UpdateRequest>,
-        ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/update");
-            self.inner.unary(request.into_request(), path, codec).await
-        }
-        /// cancel a reservation
-        pub async fn cancel(
-            &mut self,
-            request: impl tonic::IntoRequest<super::CancelRequest>,
-        ) -> Result<tonic::Response<super::CancelResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/cancel");
-            self.inner.unary(request.into_request(), path, codec).await
-        }
-        /// get reservation by reservation id
-        pub async fn get(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetRequest>,
-        ) -> Result<tonic::Response<super::GetResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/get");
-            self.inner.unary(request.into_request(), path, codec).await
-        }
-        /// get reservations by resource id, user id, start time, end time, and status
-        pub async fn query(
-            &mut self,
-            request: impl tonic::IntoRequest<super::QueryRequest>,
-        ) -> Result<tonic::Response<tonic::codec::Streaming<super::Reservation>>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/query");
-            self.inner
-                .server_streaming(request.into_request(), path, codec)
-                .await
-        }
-        /// filter reservations, order by reservation id
-        pub async fn filter(
-            &mut self,
-            request: impl tonic::IntoRequest<super::FilterRequest>,
-        ) -> Result<tonic::Response<super::FilterResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/filter");
-            self.inner.unary(request.into_request(), path, codec).await
-        }
-        /// another system could monitor newly added/confirmed/cancelled reservations
-        pub async fn listen(
-            &mut self,
-            request: impl tonic::IntoRequest<super::ListenRequest>,
-        ) -> Result<tonic::Response<tonic::codec::Streaming<super::Reservation>>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/listen");
-            self.inner
-                .server_streaming(request.into_request(), path, codec)
-                .await
-        }
-    }
+/// turn the hold into a pending reservation, or a confirmed one if confirm is set
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PromoteHoldRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(bool, tag = "2")]
+    pub confirm: bool,
 }
-/// Generated server implementations.
-pub mod reservation_service_server {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with ReservationServiceServer.
-    #[async_trait]
-    pub trait ReservationService: Send + Sync + 'static {
-        /// make a reservation
-        async fn add(
-            &self,
-            request: tonic::Request<super::AddRequest>,
-        ) -> Result<tonic::Response<super::AddResponse>, tonic::Status>;
-        /// confirm a valid perid resource, if reservation is not pending, do nothing
-        async fn confirm(
-            &self,
-            request: tonic::Request<super::ConfirmRequest>,
-        ) -> Result<tonic::Response<super::ConfirmResponse>, tonic::Status>;
-        /// update a reservation
-        async fn update(
-            &self,
-            request: tonic::Request<super::UpdateRequest>,
-        ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
-        /// cancel a reservation
-        async fn cancel(
-            &self,
-            request: tonic::Request<super::CancelRequest>,
-        ) -> Result<tonic::Response<super::CancelResponse>, tonic::Status>;
-        /// get reservation by reservation id
-        async fn get(
-            &self,
-            request: tonic::Request<super::GetRequest>,
-        ) -> Result<tonic::Response<super::GetResponse>, tonic::Status>;
-        /// Server streaming response type for the query method.
-        type queryStream: futures_core::Stream<Item = Result<super::Reservation, tonic::Status>>
-            + Send
-            + 'static;
-        /// get reservations by resource id, user id, start time, end time, and status
-        async fn query(
-            &self,
-            request: tonic::Request<super::QueryRequest>,
-        ) -> Result<tonic::Response<Self::queryStream>, tonic::Status>;
-        /// filter reservations, order by reservation id
-        async fn filter(
-            &self,
-            request: tonic::Request<super::FilterRequest>,
-        ) -> Result<tonic::Response<super::FilterResponse>, tonic::Status>;
-        /// Server streaming response type for the listen method.
-        type listenStream: futures_core::Stream<Item = Result<super::Reservation, tonic::Status>>
-            + Send
-            + 'static;
-        /// another system could monitor newly added/confirmed/cancelled reservations
-        async fn listen(
-            &self,
-            request: tonic::Request<super::ListenRequest>,
-        ) -> Result<tonic::Response<Self::listenStream>, tonic::Status>;
-    }
-    /// Reservation service
-    #[derive(Debug)]
-    pub struct ReservationServiceServer<T: ReservationService> {
-        inner: _Inner<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-    }
-    struct _Inner<T>(Arc<T>);
-    impl<T: ReservationService> ReservationServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            let inner = _Inner(inner);
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-            }
-        }
-        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
-        }
-        /// Enable decompressing requests with the given encoding.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.accept_compression_encodings.enable(encoding);
-            self
-        }
-        /// Compress responses with the given encoding, if the client supports it.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.send_compression_encodings.enable(encoding);
-            self
-        }
-    }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for ReservationServiceServer<T>
-    where
-        T: ReservationService,
-        B: Body + Send + 'static,
-        B::Error: Into<StdError> + Send + 'static,
-    {
-        type Response = http::Response<tonic::body::BoxBody>;
-        type Error = std::convert::Infallible;
-        type Future = BoxFuture<Self::Response, Self::Error>;
-        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
-        }
-        fn call(&mut self, req: http::Request<B>) -> Self::Future {
-            let inner = self.inner.clone();
-            match req.uri().path() {
-                "/rsvp.ReservationService/add" => {
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PromoteHoldResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// a user queued for a resource which is booked in the desired window
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WaitlistEntry {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub resource_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub start_time: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "5")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub end_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// the entry is dropped after this time, default to the start time
+    #[prost(message, optional, tag = "6")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub expires_at: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(enumeration = "WaitlistStatus", tag = "7")]
+    #[serde(with = "crate::serde_ext::waitlist_status")]
+    pub status: i32,
+    /// the reservation which the entry is converted into
+    #[prost(int64, optional, tag = "8")]
+    pub reservation_id: ::core::option::Option<i64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JoinWaitlistRequest {
+    #[prost(message, optional, tag = "1")]
+    pub entry: ::core::option::Option<WaitlistEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JoinWaitlistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub entry: ::core::option::Option<WaitlistEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeaveWaitlistRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeaveWaitlistResponse {
+    #[prost(message, optional, tag = "1")]
+    pub entry: ::core::option::Option<WaitlistEntry>,
+}
+/// list the waiting and notified entries in queue order
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListWaitlistRequest {
+    /// if empty, list all resources
+    #[prost(string, tag = "1")]
+    pub resource_id: ::prost::alloc::string::String,
+    /// if empty, list all users
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListWaitlistResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<WaitlistEntry>,
+}
+/// approve the current step of a pending reservation of a restricted resource
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApproveRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApproveResponse {
+    /// confirmed when every step is approved
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RejectRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(string, tag = "3")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RejectResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// a limit of the reservations of a user
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotaUsage {
+    /// active, concurrent, or weekly:<resource id pattern>
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// reservations for active and concurrent, seconds for weekly
+    #[prost(uint64, tag = "2")]
+    pub limit: u64,
+    #[prost(uint64, tag = "3")]
+    pub usage: u64,
+    #[prost(uint64, tag = "4")]
+    pub remaining: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotaRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+/// the configured quotas of the user, the weekly ones are of the current week
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotaResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub quotas: ::prost::alloc::vec::Vec<QuotaUsage>,
+}
+/// soft delete a reservation, it is hidden but kept for the audit
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// remove a reservation permanently, only the admins could purge
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PurgeRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PurgeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// a change of a reservation in the audit log
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuditEntry {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(int64, tag = "2")]
+    pub reservation_id: i64,
+    #[prost(enumeration = "ReservationUpdateType", tag = "3")]
+    pub op: i32,
+    /// the user of the request, or system for the background jobs
+    #[prost(string, tag = "4")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub request_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "6")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub changed_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// json of the reservation row before and after the change, empty if there is none
+    #[prost(string, tag = "7")]
+    pub before: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub after: ::prost::alloc::string::String,
+}
+/// record that the confirmed reservation is being used
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckInRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckInResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// record that the checked in reservation is left
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckOutRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckOutResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// the no-shows of the user which started after the time
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NoShowsRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// if not set, all the no-shows of the user
+    #[prost(message, optional, tag = "2")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub since: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NoShowsResponse {
+    #[prost(int64, tag = "1")]
+    pub count: i64,
+}
+/// grow the end of the reservation, a conflict has the latest end which could be taken
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtendRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(message, optional, tag = "2")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub end_time: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtendResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+/// end the ongoing reservation now, the rest of the window could be booked again
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReleaseEarlyRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReleaseEarlyResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HistoryRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+/// the changes of the reservation in the order they are made
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HistoryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<AuditEntry>,
+}
+/// query the audit log, order by entry id
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryAuditRequest {
+    /// if empty, the changes of all actors
+    #[prost(string, tag = "1")]
+    pub actor: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub start: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "3")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub end: ::core::option::Option<::prost_types::Timestamp>,
+    /// the entries after the cursor
+    #[prost(int64, tag = "4")]
+    pub cursor: i64,
+    #[prost(int64, tag = "5")]
+    pub page_size: i64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryAuditResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<AuditEntry>,
+    /// the id of the last entry, 0 if there are no more entries
+    #[prost(int64, tag = "2")]
+    pub next_cursor: i64,
+}
+/// a subscriber of the reservation changes over http
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Webhook {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    /// the changes are posted to the url as json
+    #[prost(string, tag = "2")]
+    pub url: ::prost::alloc::string::String,
+    /// the key of the hmac-sha256 signature of the payloads, not returned after the registration
+    #[prost(string, tag = "3")]
+    pub secret: ::prost::alloc::string::String,
+    /// the update types to deliver, all if empty
+    #[prost(enumeration = "ReservationUpdateType", repeated, tag = "4")]
+    pub events: ::prost::alloc::vec::Vec<i32>,
+    /// the resource id to deliver, an id ending with * matches the prefix, all if empty
+    #[prost(string, tag = "5")]
+    pub resource_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "6")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub created_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// the changes after the registration are delivered
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterWebhookRequest {
+    #[prost(message, optional, tag = "1")]
+    pub webhook: ::core::option::Option<Webhook>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterWebhookResponse {
+    #[prost(message, optional, tag = "1")]
+    pub webhook: ::core::option::Option<Webhook>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnregisterWebhookRequest {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnregisterWebhookResponse {
+    #[prost(message, optional, tag = "1")]
+    pub webhook: ::core::option::Option<Webhook>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListWebhooksRequest {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListWebhooksResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub webhooks: ::prost::alloc::vec::Vec<Webhook>,
+}
+/// a delivery which failed every attempt
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeadLetter {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(int64, tag = "2")]
+    pub webhook_id: i64,
+    #[prost(int64, tag = "3")]
+    pub change_id: i64,
+    /// the json posted to the webhook
+    #[prost(string, tag = "4")]
+    pub payload: ::prost::alloc::string::String,
+    #[prost(int32, tag = "5")]
+    pub attempts: i32,
+    #[prost(string, tag = "6")]
+    pub last_error: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "7")]
+    #[serde(with = "crate::serde_ext::timestamp")]
+    pub failed_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListDeadLettersRequest {
+    #[prost(int64, tag = "1")]
+    pub webhook_id: i64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListDeadLettersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub dead_letters: ::prost::alloc::vec::Vec<DeadLetter>,
+}
+/// deliver the dead letters of the webhook again
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayWebhookRequest {
+    #[prost(int64, tag = "1")]
+    pub webhook_id: i64,
+    /// the dead letters to replay, all of the webhook if empty
+    #[prost(int64, repeated, tag = "2")]
+    pub ids: ::prost::alloc::vec::Vec<i64>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayWebhookResponse {
+    /// the number of the dead letters moved back to the outbox
+    #[prost(int64, tag = "1")]
+    pub replayed: i64,
+}
+/// the reservations of a user or a resource as an iCalendar (RFC 5545)
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportCalendarRequest {
+    /// user_id or resource_id is required
+    #[prost(message, optional, tag = "1")]
+    pub query: ::core::option::Option<ReservationQuery>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportCalendarResponse {
+    /// one VEVENT per reservation, the uid is derived from the reservation id
+    #[prost(string, tag = "1")]
+    pub calendar: ::prost::alloc::string::String,
+}
+/// make a reservation of each VEVENT in an iCalendar
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportCalendarRequest {
+    /// the owner of the imported reservations
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// the resource of the events, if empty, use the LOCATION of each event
+    #[prost(string, tag = "2")]
+    pub resource_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub calendar: ::prost::alloc::string::String,
+}
+/// the outcome of an event, either the reservation or the error is set
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportedEvent {
+    #[prost(string, tag = "1")]
+    pub uid: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub reservation: ::core::option::Option<Reservation>,
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportCalendarResponse {
+    /// in the order of the events in the calendar
+    #[prost(message, repeated, tag = "1")]
+    pub events: ::prost::alloc::vec::Vec<ImportedEvent>,
+}
+/// a row of the bulk import, streamed by the client
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportRequest {
+    #[prost(message, optional, tag = "1")]
+    pub reservation: ::core::option::Option<Reservation>,
+    /// report the failed rows without committing, read from the first message only
+    #[prost(bool, tag = "2")]
+    pub dry_run: bool,
+    /// rows committed in one transaction, if 0, use 500. Read from the first message only
+    #[prost(int32, tag = "3")]
+    pub chunk_size: i32,
+}
+/// the outcome of a row, sent after the chunk of the row is committed
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportResult {
+    /// the position of the row in the stream, from 1
+    #[prost(int64, tag = "1")]
+    pub row: i64,
+    /// the imported reservation, its id is 0 in a dry run
+    #[prost(message, optional, tag = "2")]
+    pub reservation: ::core::option::Option<Reservation>,
+    /// why the row is not imported
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+    /// the existing reservation which the row conflicts with
+    #[prost(message, optional, tag = "4")]
+    pub conflict: ::core::option::Option<ReservationConflict>,
+}
+/// export every reservation matching the filter, the cursor and the page size are ignored
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportRequest {
+    #[prost(message, optional, tag = "1")]
+    pub filter: ::core::option::Option<ReservationFilter>,
+}
+/// reservation status
+#[derive(
+    sqlx::Type, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum ReservationStatus {
+    Unknown = 0,
+    Pending = 1,
+    Confirmed = 2,
+    Cancelled = 3,
+    /// the window is locked by a hold until it expires
+    Held = 4,
+    /// rejected by an approver, the window is freed
+    Rejected = 5,
+    /// not checked in within the grace period, the rest of the window is freed
+    NoShow = 6,
+}
+impl ReservationStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ReservationStatus::Unknown => "RESERVATION_STATUS_UNKNOWN",
+            ReservationStatus::Pending => "RESERVATION_STATUS_PENDING",
+            ReservationStatus::Confirmed => "RESERVATION_STATUS_CONFIRMED",
+            ReservationStatus::Cancelled => "RESERVATION_STATUS_CANCELLED",
+            ReservationStatus::Held => "RESERVATION_STATUS_HELD",
+            ReservationStatus::Rejected => "RESERVATION_STATUS_REJECTED",
+            ReservationStatus::NoShow => "RESERVATION_STATUS_NO_SHOW",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RESERVATION_STATUS_UNKNOWN" => Some(Self::Unknown),
+            "RESERVATION_STATUS_PENDING" => Some(Self::Pending),
+            "RESERVATION_STATUS_CONFIRMED" => Some(Self::Confirmed),
+            "RESERVATION_STATUS_CANCELLED" => Some(Self::Cancelled),
+            "RESERVATION_STATUS_HELD" => Some(Self::Held),
+            "RESERVATION_STATUS_REJECTED" => Some(Self::Rejected),
+            "RESERVATION_STATUS_NO_SHOW" => Some(Self::NoShow),
+            _ => None,
+        }
+    }
+}
+/// when reservation is updated, record the update type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ReservationUpdateType {
+    Unknown = 0,
+    Create = 1,
+    Update = 2,
+    Delete = 3,
+    /// the pending reservation waits for the approvers of the next step
+    ApprovalRequested = 4,
+}
+impl ReservationUpdateType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ReservationUpdateType::Unknown => "RESERVATION_UPDATE_TYPE_UNKNOWN",
+            ReservationUpdateType::Create => "RESERVATION_UPDATE_TYPE_CREATE",
+            ReservationUpdateType::Update => "RESERVATION_UPDATE_TYPE_UPDATE",
+            ReservationUpdateType::Delete => "RESERVATION_UPDATE_TYPE_DELETE",
+            ReservationUpdateType::ApprovalRequested => {
+                "RESERVATION_UPDATE_TYPE_APPROVAL_REQUESTED"
+            }
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RESERVATION_UPDATE_TYPE_UNKNOWN" => Some(Self::Unknown),
+            "RESERVATION_UPDATE_TYPE_CREATE" => Some(Self::Create),
+            "RESERVATION_UPDATE_TYPE_UPDATE" => Some(Self::Update),
+            "RESERVATION_UPDATE_TYPE_DELETE" => Some(Self::Delete),
+            "RESERVATION_UPDATE_TYPE_APPROVAL_REQUESTED" => Some(Self::ApprovalRequested),
+            _ => None,
+        }
+    }
+}
+/// waitlist entry status
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WaitlistStatus {
+    Unknown = 0,
+    /// queued for the window
+    Waiting = 1,
+    /// the window is free, the user is told to book it
+    Notified = 2,
+    /// converted into a pending reservation
+    Fulfilled = 3,
+    Left = 4,
+    Expired = 5,
+}
+impl WaitlistStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WaitlistStatus::Unknown => "WAITLIST_STATUS_UNKNOWN",
+            WaitlistStatus::Waiting => "WAITLIST_STATUS_WAITING",
+            WaitlistStatus::Notified => "WAITLIST_STATUS_NOTIFIED",
+            WaitlistStatus::Fulfilled => "WAITLIST_STATUS_FULFILLED",
+            WaitlistStatus::Left => "WAITLIST_STATUS_LEFT",
+            WaitlistStatus::Expired => "WAITLIST_STATUS_EXPIRED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WAITLIST_STATUS_UNKNOWN" => Some(Self::Unknown),
+            "WAITLIST_STATUS_WAITING" => Some(Self::Waiting),
+            "WAITLIST_STATUS_NOTIFIED" => Some(Self::Notified),
+            "WAITLIST_STATUS_FULFILLED" => Some(Self::Fulfilled),
+            "WAITLIST_STATUS_LEFT" => Some(Self::Left),
+            "WAITLIST_STATUS_EXPIRED" => Some(Self::Expired),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod reservation_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    /// Reservation service
+    #[derive(Debug, Clone)]
+    pub struct ReservationServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ReservationServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ReservationServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ReservationServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + Send + Sync,
+        {
+            ReservationServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// make a reservation
+        pub async fn add(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddRequest>,
+        ) -> Result<tonic::Response<super::AddResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/add");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// confirm a valid perid resource, if reservation is not pending, do nothing
+        pub async fn confirm(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfirmRequest>,
+        ) -> Result<tonic::Response<super::ConfirmResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/confirm");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// update a reservation
+        pub async fn update(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateRequest>,
+        ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/update");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// cancel a reservation
+        pub async fn cancel(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelRequest>,
+        ) -> Result<tonic::Response<super::CancelResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/cancel");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// get reservation by reservation id
+        pub async fn get(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRequest>,
+        ) -> Result<tonic::Response<super::GetResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/get");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// get reservations by resource id, user id, start time, end time, and status
+        pub async fn query(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::Reservation>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/query");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        /// filter reservations, order by reservation id
+        pub async fn filter(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FilterRequest>,
+        ) -> Result<tonic::Response<super::FilterResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/filter");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// another system could monitor newly added/confirmed/cancelled reservations
+        pub async fn listen(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListenRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::Reservation>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/listen");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        /// queue for a window which is booked
+        pub async fn join_waitlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::JoinWaitlistRequest>,
+        ) -> Result<tonic::Response<super::JoinWaitlistResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/join_waitlist");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// leave the waitlist
+        pub async fn leave_waitlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LeaveWaitlistRequest>,
+        ) -> Result<tonic::Response<super::LeaveWaitlistResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/leave_waitlist");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// list the waitlist of a resource or a user
+        pub async fn list_waitlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListWaitlistRequest>,
+        ) -> Result<tonic::Response<super::ListWaitlistResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/list_waitlist");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// monitor the joined/notified/fulfilled/left/expired waitlist entries
+        pub async fn listen_waitlist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListenRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::WaitlistEntry>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/listen_waitlist");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        /// lock a window for a short time, e.g. while the user is paying
+        pub async fn hold(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HoldRequest>,
+        ) -> Result<tonic::Response<super::HoldResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/hold");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// turn an unexpired hold into a pending or confirmed reservation
+        pub async fn promote_hold(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PromoteHoldRequest>,
+        ) -> Result<tonic::Response<super::PromoteHoldResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/promote_hold");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// the usage and the remaining quotas of a user
+        pub async fn quota(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QuotaRequest>,
+        ) -> Result<tonic::Response<super::QuotaResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/quota");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// approve a pending reservation of a restricted resource as an approver of its current step
+        pub async fn approve(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ApproveRequest>,
+        ) -> Result<tonic::Response<super::ApproveResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/approve");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// reject a pending reservation of a restricted resource, the window is freed
+        pub async fn reject(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RejectRequest>,
+        ) -> Result<tonic::Response<super::RejectResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/reject");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// the changes of the reservations with their update type, e.g. approval requested
+        pub async fn listen_events(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListenRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::ListenResponse>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/listen_events");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        /// hide a reservation from get, query and filter, an active one is cancelled
+        pub async fn delete(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteRequest>,
+        ) -> Result<tonic::Response<super::DeleteResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/delete");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// remove a reservation and its history of approvals and holds, for the admins
+        pub async fn purge(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PurgeRequest>,
+        ) -> Result<tonic::Response<super::PurgeResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/purge");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// post the changes of the reservations to an http endpoint
+        pub async fn register_webhook(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterWebhookRequest>,
+        ) -> Result<tonic::Response<super::RegisterWebhookResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/register_webhook");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// stop the deliveries of a webhook, its outbox and dead letters are removed
+        pub async fn unregister_webhook(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UnregisterWebhookRequest>,
+        ) -> Result<tonic::Response<super::UnregisterWebhookResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/unregister_webhook");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// the registered webhooks without their secrets
+        pub async fn list_webhooks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListWebhooksRequest>,
+        ) -> Result<tonic::Response<super::ListWebhooksResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/list_webhooks");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// the deliveries of a webhook which failed every attempt
+        pub async fn list_dead_letters(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListDeadLettersRequest>,
+        ) -> Result<tonic::Response<super::ListDeadLettersResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/list_dead_letters");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// move the dead letters of a webhook back to its outbox
+        pub async fn replay_webhook(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReplayWebhookRequest>,
+        ) -> Result<tonic::Response<super::ReplayWebhookResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/replay_webhook");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// export the reservations of a user or a resource as an iCalendar
+        pub async fn export_calendar(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportCalendarRequest>,
+        ) -> Result<tonic::Response<super::ExportCalendarResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/export_calendar");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// make a reservation of each event in an iCalendar, with a report per event
+        pub async fn import_calendar(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ImportCalendarRequest>,
+        ) -> Result<tonic::Response<super::ImportCalendarResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/import_calendar");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// import the reservations in chunks, one result per row
+        pub async fn import(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::ImportRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::ImportResult>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/import");
+            self.inner
+                .streaming(request.into_streaming_request(), path, codec)
+                .await
+        }
+        /// export the reservations matching the filter, order by id
+        pub async fn export(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::Reservation>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/export");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        /// who changed the reservation and what it was before, in the order of the changes
+        pub async fn history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HistoryRequest>,
+        ) -> Result<tonic::Response<super::HistoryResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/history");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// the audit log of an actor in a time range, for the admins
+        pub async fn query_audit(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryAuditRequest>,
+        ) -> Result<tonic::Response<super::QueryAuditResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/query_audit");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// record the actual start of the confirmed reservation
+        pub async fn check_in(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CheckInRequest>,
+        ) -> Result<tonic::Response<super::CheckInResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/check_in");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// record the actual end of the checked in reservation
+        pub async fn check_out(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CheckOutRequest>,
+        ) -> Result<tonic::Response<super::CheckOutResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/check_out");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// count the no-shows of a user, for the booking policies
+        pub async fn no_shows(
+            &mut self,
+            request: impl tonic::IntoRequest<super::NoShowsRequest>,
+        ) -> Result<tonic::Response<super::NoShowsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/no_shows");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// move the end of the reservation later if the resource is free until then
+        pub async fn extend(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExtendRequest>,
+        ) -> Result<tonic::Response<super::ExtendResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/rsvp.ReservationService/extend");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// end the ongoing reservation now and free the rest of its window
+        pub async fn release_early(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReleaseEarlyRequest>,
+        ) -> Result<tonic::Response<super::ReleaseEarlyResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/rsvp.ReservationService/release_early");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod reservation_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ReservationServiceServer.
+    #[async_trait]
+    pub trait ReservationService: Send + Sync + 'static {
+        /// make a reservation
+        async fn add(
+            &self,
+            request: tonic::Request<super::AddRequest>,
+        ) -> Result<tonic::Response<super::AddResponse>, tonic::Status>;
+        /// confirm a valid perid resource, if reservation is not pending, do nothing
+        async fn confirm(
+            &self,
+            request: tonic::Request<super::ConfirmRequest>,
+        ) -> Result<tonic::Response<super::ConfirmResponse>, tonic::Status>;
+        /// update a reservation
+        async fn update(
+            &self,
+            request: tonic::Request<super::UpdateRequest>,
+        ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+        /// cancel a reservation
+        async fn cancel(
+            &self,
+            request: tonic::Request<super::CancelRequest>,
+        ) -> Result<tonic::Response<super::CancelResponse>, tonic::Status>;
+        /// get reservation by reservation id
+        async fn get(
+            &self,
+            request: tonic::Request<super::GetRequest>,
+        ) -> Result<tonic::Response<super::GetResponse>, tonic::Status>;
+        /// Server streaming response type for the query method.
+        type queryStream: futures_core::Stream<Item = Result<super::Reservation, tonic::Status>>
+            + Send
+            + 'static;
+        /// get reservations by resource id, user id, start time, end time, and status
+        async fn query(
+            &self,
+            request: tonic::Request<super::QueryRequest>,
+        ) -> Result<tonic::Response<Self::queryStream>, tonic::Status>;
+        /// filter reservations, order by reservation id
+        async fn filter(
+            &self,
+            request: tonic::Request<super::FilterRequest>,
+        ) -> Result<tonic::Response<super::FilterResponse>, tonic::Status>;
+        /// Server streaming response type for the listen method.
+        type listenStream: futures_core::Stream<Item = Result<super::Reservation, tonic::Status>>
+            + Send
+            + 'static;
+        /// another system could monitor newly added/confirmed/cancelled reservations
+        async fn listen(
+            &self,
+            request: tonic::Request<super::ListenRequest>,
+        ) -> Result<tonic::Response<Self::listenStream>, tonic::Status>;
+        /// queue for a window which is booked
+        async fn join_waitlist(
+            &self,
+            request: tonic::Request<super::JoinWaitlistRequest>,
+        ) -> Result<tonic::Response<super::JoinWaitlistResponse>, tonic::Status>;
+        /// leave the waitlist
+        async fn leave_waitlist(
+            &self,
+            request: tonic::Request<super::LeaveWaitlistRequest>,
+        ) -> Result<tonic::Response<super::LeaveWaitlistResponse>, tonic::Status>;
+        /// list the waitlist of a resource or a user
+        async fn list_waitlist(
+            &self,
+            request: tonic::Request<super::ListWaitlistRequest>,
+        ) -> Result<tonic::Response<super::ListWaitlistResponse>, tonic::Status>;
+        /// Server streaming response type for the listen_waitlist method.
+        type listen_waitlistStream: futures_core::Stream<Item = Result<super::WaitlistEntry, tonic::Status>>
+            + Send
+            + 'static;
+        /// monitor the joined/notified/fulfilled/left/expired waitlist entries
+        async fn listen_waitlist(
+            &self,
+            request: tonic::Request<super::ListenRequest>,
+        ) -> Result<tonic::Response<Self::listen_waitlistStream>, tonic::Status>;
+        /// lock a window for a short time, e.g. while the user is paying
+        async fn hold(
+            &self,
+            request: tonic::Request<super::HoldRequest>,
+        ) -> Result<tonic::Response<super::HoldResponse>, tonic::Status>;
+        /// turn an unexpired hold into a pending or confirmed reservation
+        async fn promote_hold(
+            &self,
+            request: tonic::Request<super::PromoteHoldRequest>,
+        ) -> Result<tonic::Response<super::PromoteHoldResponse>, tonic::Status>;
+        /// the usage and the remaining quotas of a user
+        async fn quota(
+            &self,
+            request: tonic::Request<super::QuotaRequest>,
+        ) -> Result<tonic::Response<super::QuotaResponse>, tonic::Status>;
+        /// approve a pending reservation of a restricted resource as an approver of its current step
+        async fn approve(
+            &self,
+            request: tonic::Request<super::ApproveRequest>,
+        ) -> Result<tonic::Response<super::ApproveResponse>, tonic::Status>;
+        /// reject a pending reservation of a restricted resource, the window is freed
+        async fn reject(
+            &self,
+            request: tonic::Request<super::RejectRequest>,
+        ) -> Result<tonic::Response<super::RejectResponse>, tonic::Status>;
+        /// Server streaming response type for the listen_events method.
+        type listen_eventsStream: futures_core::Stream<Item = Result<super::ListenResponse, tonic::Status>>
+            + Send
+            + 'static;
+        /// the changes of the reservations with their update type, e.g. approval requested
+        async fn listen_events(
+            &self,
+            request: tonic::Request<super::ListenRequest>,
+        ) -> Result<tonic::Response<Self::listen_eventsStream>, tonic::Status>;
+        /// hide a reservation from get, query and filter, an active one is cancelled
+        async fn delete(
+            &self,
+            request: tonic::Request<super::DeleteRequest>,
+        ) -> Result<tonic::Response<super::DeleteResponse>, tonic::Status>;
+        /// remove a reservation and its history of approvals and holds, for the admins
+        async fn purge(
+            &self,
+            request: tonic::Request<super::PurgeRequest>,
+        ) -> Result<tonic::Response<super::PurgeResponse>, tonic::Status>;
+        /// post the changes of the reservations to an http endpoint
+        async fn register_webhook(
+            &self,
+            request: tonic::Request<super::RegisterWebhookRequest>,
+        ) -> Result<tonic::Response<super::RegisterWebhookResponse>, tonic::Status>;
+        /// stop the deliveries of a webhook, its outbox and dead letters are removed
+        async fn unregister_webhook(
+            &self,
+            request: tonic::Request<super::UnregisterWebhookRequest>,
+        ) -> Result<tonic::Response<super::UnregisterWebhookResponse>, tonic::Status>;
+        /// the registered webhooks without their secrets
+        async fn list_webhooks(
+            &self,
+            request: tonic::Request<super::ListWebhooksRequest>,
+        ) -> Result<tonic::Response<super::ListWebhooksResponse>, tonic::Status>;
+        /// the deliveries of a webhook which failed every attempt
+        async fn list_dead_letters(
+            &self,
+            request: tonic::Request<super::ListDeadLettersRequest>,
+        ) -> Result<tonic::Response<super::ListDeadLettersResponse>, tonic::Status>;
+        /// move the dead letters of a webhook back to its outbox
+        async fn replay_webhook(
+            &self,
+            request: tonic::Request<super::ReplayWebhookRequest>,
+        ) -> Result<tonic::Response<super::ReplayWebhookResponse>, tonic::Status>;
+        /// export the reservations of a user or a resource as an iCalendar
+        async fn export_calendar(
+            &self,
+            request: tonic::Request<super::ExportCalendarRequest>,
+        ) -> Result<tonic::Response<super::ExportCalendarResponse>, tonic::Status>;
+        /// make a reservation of each event in an iCalendar, with a report per event
+        async fn import_calendar(
+            &self,
+            request: tonic::Request<super::ImportCalendarRequest>,
+        ) -> Result<tonic::Response<super::ImportCalendarResponse>, tonic::Status>;
+        /// Server streaming response type for the import method.
+        type importStream: futures_core::Stream<Item = Result<super::ImportResult, tonic::Status>>
+            + Send
+            + 'static;
+        /// import the reservations in chunks, one result per row
+        async fn import(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::ImportRequest>>,
+        ) -> Result<tonic::Response<Self::importStream>, tonic::Status>;
+        /// Server streaming response type for the export method.
+        type exportStream: futures_core::Stream<Item = Result<super::Reservation, tonic::Status>>
+            + Send
+            + 'static;
+        /// export the reservations matching the filter, order by id
+        async fn export(
+            &self,
+            request: tonic::Request<super::ExportRequest>,
+        ) -> Result<tonic::Response<Self::exportStream>, tonic::Status>;
+        /// who changed the reservation and what it was before, in the order of the changes
+        async fn history(
+            &self,
+            request: tonic::Request<super::HistoryRequest>,
+        ) -> Result<tonic::Response<super::HistoryResponse>, tonic::Status>;
+        /// the audit log of an actor in a time range, for the admins
+        async fn query_audit(
+            &self,
+            request: tonic::Request<super::QueryAuditRequest>,
+        ) -> Result<tonic::Response<super::QueryAuditResponse>, tonic::Status>;
+        /// record the actual start of the confirmed reservation
+        async fn check_in(
+            &self,
+            request: tonic::Request<super::CheckInRequest>,
+        ) -> Result<tonic::Response<super::CheckInResponse>, tonic::Status>;
+        /// record the actual end of the checked in reservation
+        async fn check_out(
+            &self,
+            request: tonic::Request<super::CheckOutRequest>,
+        ) -> Result<tonic::Response<super::CheckOutResponse>, tonic::Status>;
+        /// count the no-shows of a user, for the booking policies
+        async fn no_shows(
+            &self,
+            request: tonic::Request<super::NoShowsRequest>,
+        ) -> Result<tonic::Response<super::NoShowsResponse>, tonic::Status>;
+        /// move the end of the reservation later if the resource is free until then
+        async fn extend(
+            &self,
+            request: tonic::Request<super::ExtendRequest>,
+        ) -> Result<tonic::Response<super::ExtendResponse>, tonic::Status>;
+        /// end the ongoing reservation now and free the rest of its window
+        async fn release_early(
+            &self,
+            request: tonic::Request<super::ReleaseEarlyRequest>,
+        ) -> Result<tonic::Response<super::ReleaseEarlyResponse>, tonic::Status>;
+    }
+    /// Reservation service
+    #[derive(Debug)]
+    pub struct ReservationServiceServer<T: ReservationService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: ReservationService> ReservationServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ReservationServiceServer<T>
+    where
+        T: ReservationService,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/rsvp.ReservationService/add" => {
+                    #[allow(non_camel_case_types)]
+                    struct addSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::AddRequest> for addSvc<T> {
+                        type Response = super::AddResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).add(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = addSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/confirm" => {
+                    #[allow(non_camel_case_types)]
+                    struct confirmSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::ConfirmRequest> for confirmSvc<T> {
+                        type Response = super::ConfirmResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConfirmRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).confirm(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = confirmSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/update" => {
+                    #[allow(non_camel_case_types)]
+                    struct updateSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::UpdateRequest> for updateSvc<T> {
+                        type Response = super::UpdateResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = updateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/cancel" => {
+                    #[allow(non_camel_case_types)]
+                    struct cancelSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::CancelRequest> for cancelSvc<T> {
+                        type Response = super::CancelResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).cancel(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = cancelSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/get" => {
+                    #[allow(non_camel_case_types)]
+                    struct getSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::GetRequest> for getSvc<T> {
+                        type Response = super::GetResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = getSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/query" => {
+                    #[allow(non_camel_case_types)]
+                    struct querySvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::ServerStreamingService<super::QueryRequest> for querySvc<T>
+                    {
+                        type Response = super::Reservation;
+                        type ResponseStream = T::queryStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).query(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = querySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/filter" => {
+                    #[allow(non_camel_case_types)]
+                    struct filterSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::FilterRequest> for filterSvc<T> {
+                        type Response = super::FilterResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FilterRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).filter(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = filterSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/listen" => {
+                    #[allow(non_camel_case_types)]
+                    struct listenSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::ServerStreamingService<super::ListenRequest>
+                        for listenSvc<T>
+                    {
+                        type Response = super::Reservation;
+                        type ResponseStream = T::listenStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListenRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).listen(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = listenSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/join_waitlist" => {
+                    #[allow(non_camel_case_types)]
+                    struct join_waitlistSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::JoinWaitlistRequest>
+                        for join_waitlistSvc<T>
+                    {
+                        type Response = super::JoinWaitlistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::JoinWaitlistRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).join_waitlist(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = join_waitlistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/leave_waitlist" => {
+                    #[allow(non_camel_case_types)]
+                    struct leave_waitlistSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::LeaveWaitlistRequest>
+                        for leave_waitlistSvc<T>
+                    {
+                        type Response = super::LeaveWaitlistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LeaveWaitlistRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).leave_waitlist(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = leave_waitlistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/list_waitlist" => {
+                    #[allow(non_camel_case_types)]
+                    struct list_waitlistSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::ListWaitlistRequest>
+                        for list_waitlistSvc<T>
+                    {
+                        type Response = super::ListWaitlistResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListWaitlistRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_waitlist(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = list_waitlistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/listen_waitlist" => {
+                    #[allow(non_camel_case_types)]
+                    struct listen_waitlistSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::ServerStreamingService<super::ListenRequest>
+                        for listen_waitlistSvc<T>
+                    {
+                        type Response = super::WaitlistEntry;
+                        type ResponseStream = T::listen_waitlistStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListenRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).listen_waitlist(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = listen_waitlistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/hold" => {
+                    #[allow(non_camel_case_types)]
+                    struct holdSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::HoldRequest> for holdSvc<T> {
+                        type Response = super::HoldResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HoldRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).hold(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = holdSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/promote_hold" => {
+                    #[allow(non_camel_case_types)]
+                    struct promote_holdSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::PromoteHoldRequest>
+                        for promote_holdSvc<T>
+                    {
+                        type Response = super::PromoteHoldResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PromoteHoldRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).promote_hold(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = promote_holdSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/quota" => {
+                    #[allow(non_camel_case_types)]
+                    struct quotaSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::QuotaRequest> for quotaSvc<T> {
+                        type Response = super::QuotaResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QuotaRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).quota(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = quotaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/approve" => {
+                    #[allow(non_camel_case_types)]
+                    struct approveSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::ApproveRequest> for approveSvc<T> {
+                        type Response = super::ApproveResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ApproveRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).approve(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = approveSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/reject" => {
+                    #[allow(non_camel_case_types)]
+                    struct rejectSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::RejectRequest> for rejectSvc<T> {
+                        type Response = super::RejectResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RejectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).reject(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = rejectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/listen_events" => {
+                    #[allow(non_camel_case_types)]
+                    struct listen_eventsSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::ServerStreamingService<super::ListenRequest>
+                        for listen_eventsSvc<T>
+                    {
+                        type Response = super::ListenResponse;
+                        type ResponseStream = T::listen_eventsStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListenRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).listen_events(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = listen_eventsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/delete" => {
+                    #[allow(non_camel_case_types)]
+                    struct deleteSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::DeleteRequest> for deleteSvc<T> {
+                        type Response = super::DeleteResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = deleteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/purge" => {
+                    #[allow(non_camel_case_types)]
+                    struct purgeSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::PurgeRequest> for purgeSvc<T> {
+                        type Response = super::PurgeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PurgeRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).purge(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = purgeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/register_webhook" => {
+                    #[allow(non_camel_case_types)]
+                    struct register_webhookSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::RegisterWebhookRequest>
+                        for register_webhookSvc<T>
+                    {
+                        type Response = super::RegisterWebhookResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RegisterWebhookRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).register_webhook(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = register_webhookSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/unregister_webhook" => {
                     #[allow(non_camel_case_types)]
-                    struct addSvc<T: ReservationService>(pub Arc<T>);
-                    impl<T: ReservationService> tonic::server::UnaryService<super::AddRequest> for addSvc<T> {
-                        type Response = super::AddResponse;
+                    struct unregister_webhookSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::UnregisterWebhookRequest>
+                        for unregister_webhookSvc<T>
+                    {
+                        type Response = super::UnregisterWebhookResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AddRequest>,
+                            request: tonic::Request<super::UnregisterWebhookRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).add(request).await };
+                            let fut = async move { (*inner).unregister_webhook(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -597,7 +2609,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = addSvc(inner);
+                        let method = unregister_webhookSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -608,18 +2620,21 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/confirm" => {
+                "/rsvp.ReservationService/list_webhooks" => {
                     #[allow(non_camel_case_types)]
-                    struct confirmSvc<T: ReservationService>(pub Arc<T>);
-                    impl<T: ReservationService> tonic::server::UnaryService<super::ConfirmRequest> for confirmSvc<T> {
-                        type Response = super::ConfirmResponse;
+                    struct list_webhooksSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::ListWebhooksRequest>
+                        for list_webhooksSvc<T>
+                    {
+                        type Response = super::ListWebhooksResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ConfirmRequest>,
+                            request: tonic::Request<super::ListWebhooksRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).confirm(request).await };
+                            let fut = async move { (*inner).list_webhooks(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -628,7 +2643,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = confirmSvc(inner);
+                        let method = list_webhooksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -639,18 +2654,21 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/update" => {
+                "/rsvp.ReservationService/list_dead_letters" => {
                     #[allow(non_camel_case_types)]
-                    struct updateSvc<T: ReservationService>(pub Arc<T>);
-                    impl<T: ReservationService> tonic::server::UnaryService<super::UpdateRequest> for updateSvc<T> {
-                        type Response = super::UpdateResponse;
+                    struct list_dead_lettersSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::ListDeadLettersRequest>
+                        for list_dead_lettersSvc<T>
+                    {
+                        type Response = super::ListDeadLettersResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateRequest>,
+                            request: tonic::Request<super::ListDeadLettersRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).update(request).await };
+                            let fut = async move { (*inner).list_dead_letters(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -659,7 +2677,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = updateSvc(inner);
+                        let method = list_dead_lettersSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -670,18 +2688,21 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/cancel" => {
+                "/rsvp.ReservationService/replay_webhook" => {
                     #[allow(non_camel_case_types)]
-                    struct cancelSvc<T: ReservationService>(pub Arc<T>);
-                    impl<T: ReservationService> tonic::server::UnaryService<super::CancelRequest> for cancelSvc<T> {
-                        type Response = super::CancelResponse;
+                    struct replay_webhookSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::ReplayWebhookRequest>
+                        for replay_webhookSvc<T>
+                    {
+                        type Response = super::ReplayWebhookResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CancelRequest>,
+                            request: tonic::Request<super::ReplayWebhookRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).cancel(request).await };
+                            let fut = async move { (*inner).replay_webhook(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -690,7 +2711,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = cancelSvc(inner);
+                        let method = replay_webhookSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -701,18 +2722,21 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/get" => {
+                "/rsvp.ReservationService/export_calendar" => {
                     #[allow(non_camel_case_types)]
-                    struct getSvc<T: ReservationService>(pub Arc<T>);
-                    impl<T: ReservationService> tonic::server::UnaryService<super::GetRequest> for getSvc<T> {
-                        type Response = super::GetResponse;
+                    struct export_calendarSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::ExportCalendarRequest>
+                        for export_calendarSvc<T>
+                    {
+                        type Response = super::ExportCalendarResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetRequest>,
+                            request: tonic::Request<super::ExportCalendarRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).get(request).await };
+                            let fut = async move { (*inner).export_calendar(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -721,7 +2745,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = getSvc(inner);
+                        let method = export_calendarSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -732,22 +2756,92 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/query" => {
+                "/rsvp.ReservationService/import_calendar" => {
                     #[allow(non_camel_case_types)]
-                    struct querySvc<T: ReservationService>(pub Arc<T>);
+                    struct import_calendarSvc<T: ReservationService>(pub Arc<T>);
                     impl<T: ReservationService>
-                        tonic::server::ServerStreamingService<super::QueryRequest> for querySvc<T>
+                        tonic::server::UnaryService<super::ImportCalendarRequest>
+                        for import_calendarSvc<T>
+                    {
+                        type Response = super::ImportCalendarResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ImportCalendarRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).import_calendar(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = import_calendarSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/import" => {
+                    #[allow(non_camel_case_types)]
+                    struct importSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::StreamingService<super::ImportRequest> for importSvc<T>
+                    {
+                        type Response = super::ImportResult;
+                        type ResponseStream = T::importStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::ImportRequest>>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).import(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = importSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/export" => {
+                    #[allow(non_camel_case_types)]
+                    struct exportSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::ServerStreamingService<super::ExportRequest>
+                        for exportSvc<T>
                     {
                         type Response = super::Reservation;
-                        type ResponseStream = T::queryStream;
+                        type ResponseStream = T::exportStream;
                         type Future =
                             BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::QueryRequest>,
+                            request: tonic::Request<super::ExportRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).query(request).await };
+                            let fut = async move { (*inner).export(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -756,7 +2850,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = querySvc(inner);
+                        let method = exportSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -767,18 +2861,18 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/filter" => {
+                "/rsvp.ReservationService/history" => {
                     #[allow(non_camel_case_types)]
-                    struct filterSvc<T: ReservationService>(pub Arc<T>);
-                    impl<T: ReservationService> tonic::server::UnaryService<super::FilterRequest> for filterSvc<T> {
-                        type Response = super::FilterResponse;
+                    struct historySvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::HistoryRequest> for historySvc<T> {
+                        type Response = super::HistoryResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::FilterRequest>,
+                            request: tonic::Request<super::HistoryRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).filter(request).await };
+                            let fut = async move { (*inner).history(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -787,7 +2881,7 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = filterSvc(inner);
+                        let method = historySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -798,23 +2892,21 @@ pub mod reservation_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/rsvp.ReservationService/listen" => {
+                "/rsvp.ReservationService/query_audit" => {
                     #[allow(non_camel_case_types)]
-                    struct listenSvc<T: ReservationService>(pub Arc<T>);
+                    struct query_auditSvc<T: ReservationService>(pub Arc<T>);
                     impl<T: ReservationService>
-                        tonic::server::ServerStreamingService<super::ListenRequest>
-                        for listenSvc<T>
+                        tonic::server::UnaryService<super::QueryAuditRequest>
+                        for query_auditSvc<T>
                     {
-                        type Response = super::Reservation;
-                        type ResponseStream = T::listenStream;
-                        type Future =
-                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        type Response = super::QueryAuditResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListenRequest>,
+                            request: tonic::Request<super::QueryAuditRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).listen(request).await };
+                            let fut = async move { (*inner).query_audit(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -823,13 +2915,173 @@ pub mod reservation_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = listenSvc(inner);
+                        let method = query_auditSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
                             send_compression_encodings,
                         );
-                        let res = grpc.server_streaming(method, req).await;
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/check_in" => {
+                    #[allow(non_camel_case_types)]
+                    struct check_inSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::CheckInRequest> for check_inSvc<T> {
+                        type Response = super::CheckInResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CheckInRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).check_in(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = check_inSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/check_out" => {
+                    #[allow(non_camel_case_types)]
+                    struct check_outSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::CheckOutRequest>
+                        for check_outSvc<T>
+                    {
+                        type Response = super::CheckOutResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CheckOutRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).check_out(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = check_outSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/no_shows" => {
+                    #[allow(non_camel_case_types)]
+                    struct no_showsSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::NoShowsRequest> for no_showsSvc<T> {
+                        type Response = super::NoShowsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::NoShowsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).no_shows(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = no_showsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/extend" => {
+                    #[allow(non_camel_case_types)]
+                    struct extendSvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService> tonic::server::UnaryService<super::ExtendRequest> for extendSvc<T> {
+                        type Response = super::ExtendResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExtendRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).extend(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = extendSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rsvp.ReservationService/release_early" => {
+                    #[allow(non_camel_case_types)]
+                    struct release_earlySvc<T: ReservationService>(pub Arc<T>);
+                    impl<T: ReservationService>
+                        tonic::server::UnaryService<super::ReleaseEarlyRequest>
+                        for release_earlySvc<T>
+                    {
+                        type Response = super::ReleaseEarlyResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReleaseEarlyRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).release_early(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = release_earlySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
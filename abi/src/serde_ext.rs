@@ -0,0 +1,92 @@
+/// rfc3339 representation of the prost timestamp, e.g. 2022-11-01T15:00:00+08:00
+pub mod timestamp {
+    use chrono::{DateTime, Utc};
+    use prost_types::Timestamp;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::{convert_to_timestamp, convert_to_utc_time};
+
+    pub fn serialize<S: Serializer>(ts: &Option<Timestamp>, s: S) -> Result<S::Ok, S::Error> {
+        match ts {
+            Some(ts) => s.serialize_str(&convert_to_utc_time(ts).to_rfc3339()),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Timestamp>, D::Error> {
+        match Option::<String>::deserialize(d)? {
+            Some(s) => {
+                let dt = DateTime::parse_from_rfc3339(&s).map_err(D::Error::custom)?;
+                Ok(Some(convert_to_timestamp(dt.with_timezone(&Utc))))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// lowercase name of the reservation status, e.g. pending
+pub mod status {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::ReservationStatus;
+
+    pub fn serialize<S: Serializer>(status: &i32, s: S) -> Result<S::Ok, S::Error> {
+        let status = ReservationStatus::from_i32(*status).unwrap_or(ReservationStatus::Unknown);
+        s.serialize_str(&status.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<i32, D::Error> {
+        let s = String::deserialize(d)?;
+        let status: ReservationStatus = s.parse().map_err(D::Error::custom)?;
+        Ok(status as i32)
+    }
+}
+
+/// lowercase name of the waitlist status, e.g. waiting
+pub mod waitlist_status {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::WaitlistStatus;
+
+    pub fn serialize<S: Serializer>(status: &i32, s: S) -> Result<S::Ok, S::Error> {
+        let status = WaitlistStatus::from_i32(*status).unwrap_or(WaitlistStatus::Unknown);
+        s.serialize_str(&status.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<i32, D::Error> {
+        let s = String::deserialize(d)?;
+        let status: WaitlistStatus = s.parse().map_err(D::Error::custom)?;
+        Ok(status as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Reservation, ReservationStatus};
+
+    #[test]
+    fn reservation_json_should_round_trip() {
+        let rsvp = Reservation::new_pending(
+            "tosei",
+            "ocean room-745",
+            "2022-11-01T15:00:00+0800".parse().unwrap(),
+            "2022-11-07T12:00:00+0800".parse().unwrap(),
+            "please check the room for me",
+        );
+        let json = serde_json::to_value(&rsvp).unwrap();
+        assert_eq!("2022-11-01T07:00:00+00:00", json["start_time"]);
+        assert_eq!("pending", json["status"]);
+
+        let parsed: Reservation = serde_json::from_value(json).unwrap();
+        assert_eq!(rsvp, parsed);
+    }
+
+    #[test]
+    fn missing_fields_should_be_default() {
+        let rsvp: Reservation =
+            serde_json::from_str(r#"{"user_id": "tosei", "status": "confirmed"}"#).unwrap();
+        assert_eq!("tosei", rsvp.user_id);
+        assert_eq!(ReservationStatus::Confirmed as i32, rsvp.status);
+        assert!(rsvp.start_time.is_none());
+    }
+}
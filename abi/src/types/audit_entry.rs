@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::{convert_to_timestamp, AuditEntry, ReservationUpdateType, UpdateType};
+
+/// a row of the audit log, the snapshots are selected as text
+impl FromRow<'_, PgRow> for AuditEntry {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        let op: UpdateType = row.try_get("op")?;
+        let changed_at: DateTime<Utc> = row.try_get("changed_at")?;
+        let before: Option<String> = row.try_get("before")?;
+        let after: Option<String> = row.try_get("after")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            reservation_id: row.try_get("reservation_id")?,
+            op: ReservationUpdateType::from(op) as i32,
+            actor: row.try_get("actor")?,
+            request_id: row.try_get("request_id")?,
+            changed_at: Some(convert_to_timestamp(changed_at)),
+            before: before.unwrap_or_default(),
+            after: after.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,209 @@
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+
+use crate::{BookingRules, Error};
+
+impl BookingRules {
+    /// check the window of a reservation of `resource_id` which is made at `now`
+    pub fn check(
+        &self,
+        resource_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let duration = (end - start).num_seconds();
+        if let Some(min) = self.min_duration {
+            if duration < min as i64 {
+                return Err(Error::DurationTooShort(min));
+            }
+        }
+        if let Some(max) = self.max_duration {
+            if duration > max as i64 {
+                return Err(Error::DurationTooLong(max));
+            }
+        }
+
+        let lead_time = (start - now).num_seconds();
+        if let Some(min) = self.min_lead_time {
+            if lead_time < min as i64 {
+                return Err(Error::LeadTimeTooShort(min));
+            }
+        }
+        if let Some(max) = self.max_horizon {
+            if lead_time > max as i64 {
+                return Err(Error::BeyondBookingHorizon(max));
+            }
+        }
+
+        if self
+            .blackouts
+            .iter()
+            .any(|blackout| blackout.start < end && start < blackout.end)
+        {
+            return Err(Error::InBlackoutPeriod(resource_id.to_string()));
+        }
+
+        if !self.is_open(start, end) {
+            return Err(Error::OutsideOpeningHours(resource_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// the window must be held by one interval of the opening hours of its local day
+    fn is_open(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        if self.opening_hours.is_empty() {
+            return true;
+        }
+        let start = start.with_timezone(&self.timezone);
+        let end = end.with_timezone(&self.timezone);
+        let day = start.date_naive();
+        let from = start.num_seconds_from_midnight();
+        let to = if end.date_naive() == day {
+            end.num_seconds_from_midnight()
+        } else if end.date_naive() == day + Duration::days(1)
+            && end.num_seconds_from_midnight() == 0
+        {
+            24 * 3600
+        } else {
+            return false;
+        };
+
+        self.opening_hours
+            .get(&start.weekday())
+            .map(|hours| hours.iter().any(|h| h.start <= from && to <= h.end))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blackout, BookingRules, Error, OpeningHours};
+    use chrono::{DateTime, Utc, Weekday};
+    use std::collections::HashMap;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().into()
+    }
+
+    fn rules() -> BookingRules {
+        let hours = |s: &str| OpeningHours::try_from(s.to_string()).unwrap();
+        BookingRules {
+            timezone: chrono_tz::Asia::Shanghai,
+            opening_hours: HashMap::from([
+                (
+                    Weekday::Mon,
+                    vec![hours("08:00-12:00"), hours("13:00-20:00")],
+                ),
+                (Weekday::Sat, vec![hours("10:00-24:00")]),
+            ]),
+            blackouts: vec![Blackout {
+                start: time("2023-01-02T17:00:00+08:00"),
+                end: time("2023-01-02T18:00:00+08:00"),
+                reason: "maintenance".to_string(),
+            }],
+            min_duration: Some(900),
+            max_duration: Some(8 * 3600),
+            min_lead_time: Some(3600),
+            max_horizon: Some(30 * 86400),
+        }
+    }
+
+    fn check(start: &str, end: &str) -> Result<(), Error> {
+        // a sunday in Shanghai
+        let now = time("2023-01-01T09:00:00+08:00");
+        rules().check("meeting-room-1", time(start), time(end), now)
+    }
+
+    #[test]
+    fn window_within_the_rules_should_pass() {
+        assert!(check("2023-01-02T08:00:00+08:00", "2023-01-02T12:00:00+08:00").is_ok());
+        assert!(check("2023-01-07T20:00:00+08:00", "2023-01-08T00:00:00+08:00").is_ok());
+        assert!(BookingRules::default()
+            .check(
+                "room-1",
+                time("2023-01-01T03:00:00Z"),
+                time("2023-02-10T03:00:00Z"),
+                time("2023-01-20T00:00:00Z"),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn opening_hours_should_follow_the_daylight_saving_time() {
+        let rules = BookingRules {
+            timezone: chrono_tz::America::New_York,
+            opening_hours: HashMap::from([(
+                Weekday::Sun,
+                vec![OpeningHours::try_from("09:00-17:00".to_string()).unwrap()],
+            )]),
+            ..Default::default()
+        };
+        let now = time("2030-01-01T00:00:00Z");
+        let check = |start, end| rules.check("room-1", time(start), time(end), now);
+        // 9am is 13:00 UTC after the clocks spring forward, 14:00 UTC before
+        assert!(check("2030-03-10T13:00:00Z", "2030-03-10T21:00:00Z").is_ok());
+        assert!(check("2030-03-03T13:00:00Z", "2030-03-03T21:00:00Z").is_err());
+        assert!(check("2030-03-03T14:00:00Z", "2030-03-03T22:00:00Z").is_ok());
+    }
+
+    #[test]
+    fn window_outside_the_opening_hours_should_be_rejected() {
+        let outside = Err(Error::OutsideOpeningHours("meeting-room-1".to_string()));
+        // 8am on monday in Shanghai is still sunday in UTC
+        assert!(check("2023-01-02T00:00:00Z", "2023-01-02T01:00:00Z").is_ok());
+        // across the lunch break
+        assert_eq!(
+            outside,
+            check("2023-01-02T11:00:00+08:00", "2023-01-02T14:00:00+08:00")
+        );
+        // closed on the unlisted days
+        assert_eq!(
+            outside,
+            check("2023-01-03T09:00:00+08:00", "2023-01-03T10:00:00+08:00")
+        );
+        assert_eq!(
+            outside,
+            check("2023-01-07T23:00:00+08:00", "2023-01-08T01:00:00+08:00")
+        );
+    }
+
+    #[test]
+    fn window_breaking_the_limits_should_be_rejected() {
+        assert_eq!(
+            Err(Error::InBlackoutPeriod("meeting-room-1".to_string())),
+            check("2023-01-02T16:00:00+08:00", "2023-01-02T17:30:00+08:00")
+        );
+        assert_eq!(
+            Err(Error::DurationTooShort(900)),
+            check("2023-01-02T09:00:00+08:00", "2023-01-02T09:10:00+08:00")
+        );
+        assert_eq!(
+            Err(Error::DurationTooLong(8 * 3600)),
+            check("2023-01-07T10:00:00+08:00", "2023-01-07T19:00:00+08:00")
+        );
+        assert_eq!(
+            Err(Error::LeadTimeTooShort(3600)),
+            check("2023-01-01T09:30:00+08:00", "2023-01-01T10:30:00+08:00")
+        );
+        assert_eq!(
+            Err(Error::BeyondBookingHorizon(30 * 86400)),
+            check("2023-02-06T09:00:00+08:00", "2023-02-06T10:00:00+08:00")
+        );
+    }
+
+    #[test]
+    fn opening_hours_should_be_parsed() {
+        let hours = |s: &str| OpeningHours::try_from(s.to_string());
+        assert_eq!(
+            Ok(OpeningHours {
+                start: 9 * 3600 + 30 * 60,
+                end: 24 * 3600
+            }),
+            hours("09:30-24:00")
+        );
+        assert_eq!("09:30-24:00", String::from(hours("09:30-24:00").unwrap()));
+        assert!(hours("18:00-09:00").is_err());
+        assert!(hours("09:00-24:30").is_err());
+        assert!(hours("9am-5pm").is_err());
+    }
+}
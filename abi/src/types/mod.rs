@@ -1,8 +1,10 @@
+mod audit_entry;
+mod booking_rules;
 mod reservation;
 mod reservation_filter;
 mod reservation_query;
 mod reservation_status;
-
-pub use reservation::*;
-pub use reservation_query::*;
-pub use reservation_status::*;
+mod reservation_update_type;
+mod waitlist_entry;
+mod waitlist_status;
+mod webhook;
@@ -1,11 +1,15 @@
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use chrono_tz::Tz;
 use sqlx::{
     postgres::{types::PgRange, PgRow},
     FromRow, Row,
 };
 use std::ops::Bound;
 
-use crate::{convert_to_timestamp, Error, Reservation, ReservationStatus, RsvpStatus, Validator};
+use crate::{
+    convert_to_timestamp, convert_to_utc_time, BookingRules, Error, Reservation, ReservationStatus,
+    RsvpStatus, Validator,
+};
 
 impl Reservation {
     pub fn new_pending(
@@ -23,8 +27,24 @@ impl Reservation {
             end_time: Some(convert_to_timestamp(end.with_timezone(&Utc))),
             note: note.into(),
             status: ReservationStatus::Pending as i32,
+            ..Default::default()
         }
     }
+
+    /// the reservation is in the timezone of its resource unless the request has one
+    pub fn set_default_timezone(&mut self, tz: Tz) {
+        if self.timezone.is_empty() {
+            self.timezone = tz.name().to_string();
+        }
+    }
+
+    /// validate the reservation, then the booking rules of its resource at the time `now`
+    pub fn validate_rules(&self, rules: &BookingRules, now: DateTime<Utc>) -> Result<(), Error> {
+        self.validate()?;
+        let start = convert_to_utc_time(self.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(self.end_time.as_ref().unwrap());
+        rules.check(&self.resource_id, start, end, now)
+    }
 }
 
 impl Validator for Reservation {
@@ -47,6 +67,10 @@ impl Validator for Reservation {
             return Err(Error::InvalidTime);
         }
 
+        if !self.timezone.is_empty() && self.timezone.parse::<Tz>().is_err() {
+            return Err(Error::InvalidTimezone(self.timezone.clone()));
+        }
+
         Ok(())
     }
 }
@@ -58,26 +82,38 @@ impl FromRow<'_, PgRow> for Reservation {
 
         let period: PgRange<DateTime<Utc>> = row.get("rperiod");
         let period: NaviRange<DateTime<Utc>> = period.into();
-        let start = convert_to_timestamp(period.start.unwrap());
-        let end = convert_to_timestamp(period.end.unwrap());
+        let (start, end) = (period.start.unwrap(), period.end.unwrap());
 
         let status: RsvpStatus = row.get("rstatus");
+        let timezone: String = row.get("timezone");
+        let checked_in_at: Option<DateTime<Utc>> = row.get("checked_in_at");
+        let checked_out_at: Option<DateTime<Utc>> = row.get("checked_out_at");
+        let tz: Tz = timezone.parse().unwrap_or(Tz::UTC);
+        let local = |time: DateTime<Utc>| {
+            time.with_timezone(&tz)
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        };
 
         Ok(Self {
             id,
             user_id: row.get("user_id"),
             resource_id: row.get("resource_id"),
-            start_time: Some(start),
-            end_time: Some(end),
+            start_time: Some(convert_to_timestamp(start)),
+            end_time: Some(convert_to_timestamp(end)),
             status: ReservationStatus::from(status) as i32,
             note: row.get("note"),
+            local_start: local(start),
+            local_end: local(end),
+            timezone,
+            checked_in_at: checked_in_at.map(convert_to_timestamp),
+            checked_out_at: checked_out_at.map(convert_to_timestamp),
         })
     }
 }
 
-struct NaviRange<T> {
-    start: Option<T>,
-    end: Option<T>,
+pub(crate) struct NaviRange<T> {
+    pub(crate) start: Option<T>,
+    pub(crate) end: Option<T>,
 }
 
 impl<T> From<PgRange<T>> for NaviRange<T> {
@@ -1,31 +1,139 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
 use sqlx::postgres::types::PgRange;
 use std::ops::Bound;
 
 use crate::{convert_to_utc_time, Error, ReservationQuery, Validator};
 
+const DAY: &str = "day";
+const WEEK: &str = "week";
+
 // #[allow(clippy::too_many_arguments)] use the derive_builder solve this clippy problem
 impl ReservationQuery {
     pub fn timespan(&self) -> PgRange<DateTime<Utc>> {
+        let (start, end) = self.range().unwrap();
         PgRange {
-            start: Bound::Included(convert_to_utc_time(self.start.as_ref().unwrap())),
-            end: Bound::Excluded(convert_to_utc_time(self.end.as_ref().unwrap())),
+            start: Bound::Included(start),
+            end: Bound::Excluded(end),
+        }
+    }
+
+    /// the day or week of the start in the timezone, or the range from start to end
+    fn range(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), Error> {
+        let start = convert_to_utc_time(self.start.as_ref().ok_or(Error::InvalidTime)?);
+        let days = match self.period.as_str() {
+            "" => {
+                let end = convert_to_utc_time(self.end.as_ref().ok_or(Error::InvalidTime)?);
+                return Ok((start, end));
+            }
+            DAY => 1,
+            WEEK => 7,
+            period => return Err(Error::InvalidPeriod(period.to_string())),
+        };
+        let tz: Tz = match self.timezone.as_str() {
+            "" => Tz::UTC,
+            tz => tz
+                .parse()
+                .map_err(|_| Error::InvalidTimezone(tz.to_string()))?,
+        };
+        let mut first = start.with_timezone(&tz).date_naive();
+        if days == 7 {
+            first = first - Duration::days(first.weekday().num_days_from_monday() as i64);
         }
+        Ok((
+            start_of_day(&tz, first),
+            start_of_day(&tz, first + Duration::days(days)),
+        ))
     }
 }
 
+/// the first instant of the local day, which is after the gap if the midnight is skipped by the
+/// daylight saving time, and the earlier one if the midnight is repeated
+fn start_of_day(tz: &Tz, day: NaiveDate) -> DateTime<Utc> {
+    let midnight = day.and_hms_opt(0, 0, 0).unwrap();
+    // the gaps are multiples of 15 minutes and shorter than a day
+    (0..96)
+        .find_map(|i| {
+            tz.from_local_datetime(&(midnight + Duration::minutes(15 * i)))
+                .earliest()
+        })
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
 impl Validator for ReservationQuery {
     fn validate(&self) -> Result<(), crate::Error> {
-        if self.start.is_none() || self.end.is_none() {
-            return Err(Error::InvalidTime);
-        }
-
-        let start = self.start.as_ref().unwrap();
-        let end = self.end.as_ref().unwrap();
-        if start.seconds >= end.seconds {
+        let (start, end) = self.range()?;
+        if start >= end {
             return Err(Error::InvalidTime);
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{convert_to_timestamp, ReservationQueryBuilder};
+
+    fn query(start: &str, period: &str, timezone: &str) -> ReservationQuery {
+        ReservationQueryBuilder::default()
+            .start(convert_to_timestamp(start.parse().unwrap()))
+            .end(convert_to_timestamp(start.parse().unwrap()))
+            .period(period)
+            .timezone(timezone)
+            .build()
+            .unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn day_and_week_should_be_in_the_timezone() {
+        // 2030-01-01 is a tuesday, it is still 2029-12-31 in new york
+        let q = query("2030-01-01T03:00:00Z", "day", "Asia/Tokyo");
+        let range = (utc("2029-12-31T15:00:00Z"), utc("2030-01-01T15:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+        let q = query("2030-01-01T03:00:00Z", "day", "America/New_York");
+        let range = (utc("2029-12-31T05:00:00Z"), utc("2030-01-01T05:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+        let q = query("2030-01-01T03:00:00Z", "week", "Asia/Tokyo");
+        let range = (utc("2029-12-30T15:00:00Z"), utc("2030-01-06T15:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+        let q = query("2030-01-01T03:00:00Z", "week", "");
+        let range = (utc("2029-12-31T00:00:00Z"), utc("2030-01-07T00:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+    }
+
+    #[test]
+    fn day_should_follow_the_daylight_saving_time() {
+        // the spring forward day has 23 hours, the fall back day 25
+        let q = query("2030-03-10T12:00:00Z", "day", "America/New_York");
+        let range = (utc("2030-03-10T05:00:00Z"), utc("2030-03-11T04:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+        let q = query("2030-11-03T12:00:00Z", "day", "America/New_York");
+        let range = (utc("2030-11-03T04:00:00Z"), utc("2030-11-04T05:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+        // the midnight is skipped in santiago, the day starts at 01:00
+        let q = query("2030-09-08T12:00:00Z", "day", "America/Santiago");
+        let range = (utc("2030-09-08T04:00:00Z"), utc("2030-09-09T03:00:00Z"));
+        assert_eq!(Ok(range), q.range());
+    }
+
+    #[test]
+    fn invalid_period_should_be_rejected() {
+        let q = query("2030-01-01T03:00:00Z", "month", "");
+        assert_eq!(Err(Error::InvalidPeriod("month".to_string())), q.validate());
+        let q = query("2030-01-01T03:00:00Z", "day", "Mars/Olympus");
+        assert_eq!(
+            Err(Error::InvalidTimezone("Mars/Olympus".to_string())),
+            q.validate()
+        );
+        // without a period the end must be after the start
+        let q = query("2030-01-01T03:00:00Z", "", "");
+        assert_eq!(Err(Error::InvalidTime), q.validate());
+    }
+}
@@ -1,6 +1,6 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
-use crate::{ReservationStatus, RsvpStatus};
+use crate::{Error, ReservationStatus, RsvpStatus};
 
 impl fmt::Display for ReservationStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -9,6 +9,26 @@ impl fmt::Display for ReservationStatus {
             ReservationStatus::Unknown => write!(f, "unknown"),
             ReservationStatus::Confirmed => write!(f, "confirmed"),
             ReservationStatus::Cancelled => write!(f, "cancelled"),
+            ReservationStatus::Held => write!(f, "held"),
+            ReservationStatus::Rejected => write!(f, "rejected"),
+            ReservationStatus::NoShow => write!(f, "no_show"),
+        }
+    }
+}
+
+impl FromStr for ReservationStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ReservationStatus::Pending),
+            "unknown" => Ok(ReservationStatus::Unknown),
+            "confirmed" => Ok(ReservationStatus::Confirmed),
+            "cancelled" => Ok(ReservationStatus::Cancelled),
+            "held" => Ok(ReservationStatus::Held),
+            "rejected" => Ok(ReservationStatus::Rejected),
+            "no_show" => Ok(ReservationStatus::NoShow),
+            _ => Err(Error::InvalidStatusName(s.to_string())),
         }
     }
 }
@@ -20,6 +40,9 @@ impl From<RsvpStatus> for ReservationStatus {
             RsvpStatus::Pending => ReservationStatus::Pending,
             RsvpStatus::Confirmed => ReservationStatus::Confirmed,
             RsvpStatus::Blocked => ReservationStatus::Cancelled,
+            RsvpStatus::Held => ReservationStatus::Held,
+            RsvpStatus::Rejected => ReservationStatus::Rejected,
+            RsvpStatus::NoShow => ReservationStatus::NoShow,
             RsvpStatus::Unknown => ReservationStatus::Unknown,
         }
     }
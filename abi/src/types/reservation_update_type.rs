@@ -0,0 +1,40 @@
+use sqlx::{postgres::PgRow, FromRow, Row};
+use std::fmt;
+
+use crate::{ListenResponse, Reservation, ReservationUpdateType, UpdateType};
+
+impl fmt::Display for ReservationUpdateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReservationUpdateType::Unknown => write!(f, "unknown"),
+            ReservationUpdateType::Create => write!(f, "create"),
+            ReservationUpdateType::Update => write!(f, "update"),
+            ReservationUpdateType::Delete => write!(f, "delete"),
+            ReservationUpdateType::ApprovalRequested => write!(f, "approval_requested"),
+        }
+    }
+}
+
+/// database equivalent of enum op column of the changes queue
+impl From<UpdateType> for ReservationUpdateType {
+    fn from(op: UpdateType) -> Self {
+        match op {
+            UpdateType::Unknown => ReservationUpdateType::Unknown,
+            UpdateType::Create => ReservationUpdateType::Create,
+            UpdateType::Update => ReservationUpdateType::Update,
+            UpdateType::Delete => ReservationUpdateType::Delete,
+            UpdateType::ApprovalRequested => ReservationUpdateType::ApprovalRequested,
+        }
+    }
+}
+
+/// a row of the changes queue joined with the changed reservation
+impl FromRow<'_, PgRow> for ListenResponse {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        let op: UpdateType = row.try_get("op")?;
+        Ok(Self {
+            op: ReservationUpdateType::from(op) as i32,
+            reservation: Some(Reservation::from_row(row)?),
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use sqlx::{
+    postgres::{types::PgRange, PgRow},
+    FromRow, Row,
+};
+
+use super::reservation::NaviRange;
+use crate::{convert_to_timestamp, Error, Validator, WaitStatus, WaitlistEntry, WaitlistStatus};
+
+impl WaitlistEntry {
+    /// the entry expires when the desired window starts
+    pub fn new_waiting(
+        uid: impl Into<String>,
+        rid: impl Into<String>,
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+    ) -> Self {
+        let start = convert_to_timestamp(start.with_timezone(&Utc));
+        Self {
+            id: 0,
+            user_id: uid.into(),
+            resource_id: rid.into(),
+            start_time: Some(start.clone()),
+            end_time: Some(convert_to_timestamp(end.with_timezone(&Utc))),
+            expires_at: Some(start),
+            status: WaitlistStatus::Waiting as i32,
+            reservation_id: None,
+        }
+    }
+}
+
+impl Validator for WaitlistEntry {
+    fn validate(&self) -> Result<(), Error> {
+        if self.user_id.is_empty() {
+            return Err(Error::InvalidUserId(self.user_id.clone()));
+        }
+
+        if self.resource_id.is_empty() {
+            return Err(Error::InvalidResourceId(self.resource_id.clone()));
+        }
+
+        match (&self.start_time, &self.end_time) {
+            (Some(start), Some(end)) if start.seconds < end.seconds => Ok(()),
+            _ => Err(Error::InvalidTime),
+        }
+    }
+}
+
+impl FromRow<'_, PgRow> for WaitlistEntry {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        let period: PgRange<DateTime<Utc>> = row.get("rperiod");
+        let period: NaviRange<DateTime<Utc>> = period.into();
+        let expires_at: DateTime<Utc> = row.get("expires_at");
+        let status: WaitStatus = row.get("wstatus");
+
+        Ok(Self {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            resource_id: row.get("resource_id"),
+            start_time: period.start.map(convert_to_timestamp),
+            end_time: period.end.map(convert_to_timestamp),
+            expires_at: Some(convert_to_timestamp(expires_at)),
+            status: WaitlistStatus::from(status) as i32,
+            reservation_id: row.get("reservation_id"),
+        })
+    }
+}
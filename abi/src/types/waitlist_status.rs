@@ -0,0 +1,46 @@
+use std::{fmt, str::FromStr};
+
+use crate::{Error, WaitStatus, WaitlistStatus};
+
+impl fmt::Display for WaitlistStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitlistStatus::Unknown => write!(f, "unknown"),
+            WaitlistStatus::Waiting => write!(f, "waiting"),
+            WaitlistStatus::Notified => write!(f, "notified"),
+            WaitlistStatus::Fulfilled => write!(f, "fulfilled"),
+            WaitlistStatus::Left => write!(f, "left"),
+            WaitlistStatus::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+impl FromStr for WaitlistStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unknown" => Ok(WaitlistStatus::Unknown),
+            "waiting" => Ok(WaitlistStatus::Waiting),
+            "notified" => Ok(WaitlistStatus::Notified),
+            "fulfilled" => Ok(WaitlistStatus::Fulfilled),
+            "left" => Ok(WaitlistStatus::Left),
+            "expired" => Ok(WaitlistStatus::Expired),
+            _ => Err(Error::InvalidStatusName(s.to_string())),
+        }
+    }
+}
+
+/// database equivalent of enum wstatus column
+impl From<WaitStatus> for WaitlistStatus {
+    fn from(status: WaitStatus) -> Self {
+        match status {
+            WaitStatus::Unknown => WaitlistStatus::Unknown,
+            WaitStatus::Waiting => WaitlistStatus::Waiting,
+            WaitStatus::Notified => WaitlistStatus::Notified,
+            WaitStatus::Fulfilled => WaitlistStatus::Fulfilled,
+            WaitStatus::Left => WaitlistStatus::Left,
+            WaitStatus::Expired => WaitlistStatus::Expired,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, FromRow, Row};
+
+use crate::{
+    convert_to_timestamp, DeadLetter, Error, Reservation, ReservationUpdateType, Validator, Webhook,
+};
+
+impl Webhook {
+    /// whether the change of the reservation is delivered to the webhook
+    pub fn matches(&self, op: i32, rsvp: &Reservation) -> bool {
+        let event = self.events.is_empty() || self.events.contains(&op);
+        let resource = match self.resource_id.strip_suffix('*') {
+            Some(prefix) => rsvp.resource_id.starts_with(prefix),
+            None => self.resource_id.is_empty() || self.resource_id == rsvp.resource_id,
+        };
+        event && resource
+    }
+}
+
+impl Validator for Webhook {
+    fn validate(&self) -> Result<(), Error> {
+        let url = self.url.parse::<http::Uri>().ok();
+        let scheme = url.as_ref().and_then(|url| url.scheme_str());
+        if !matches!(scheme, Some("http") | Some("https")) || url.unwrap().host().is_none() {
+            return Err(Error::InvalidWebhook(format!("url {}", self.url)));
+        }
+        if self.secret.is_empty() {
+            return Err(Error::InvalidWebhook("empty secret".to_string()));
+        }
+        if let Some(op) = self
+            .events
+            .iter()
+            .find(|op| ReservationUpdateType::from_i32(**op).is_none())
+        {
+            return Err(Error::InvalidWebhook(format!("event {op}")));
+        }
+        Ok(())
+    }
+}
+
+impl FromRow<'_, PgRow> for Webhook {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        let created_at: DateTime<Utc> = row.try_get("created_at")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            url: row.try_get("url")?,
+            secret: row.try_get("secret")?,
+            events: row.try_get("events")?,
+            resource_id: row.try_get("resource_id")?,
+            created_at: Some(convert_to_timestamp(created_at)),
+        })
+    }
+}
+
+impl FromRow<'_, PgRow> for DeadLetter {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        let failed_at: DateTime<Utc> = row.try_get("failed_at")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            webhook_id: row.try_get("webhook_id")?,
+            change_id: row.try_get("change_id")?,
+            payload: row.try_get("payload")?,
+            attempts: row.try_get("attempts")?,
+            last_error: row.try_get("last_error")?,
+            failed_at: Some(convert_to_timestamp(failed_at)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: Vec<ReservationUpdateType>, resource_id: &str) -> Webhook {
+        Webhook {
+            url: "https://example.com/hooks".to_string(),
+            secret: "secret".to_string(),
+            events: events.into_iter().map(|op| op as i32).collect(),
+            resource_id: resource_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn webhook_should_match_the_filters() {
+        let rsvp = Reservation {
+            resource_id: "meeting-room-1".to_string(),
+            ..Default::default()
+        };
+        let create = ReservationUpdateType::Create as i32;
+        let delete = ReservationUpdateType::Delete as i32;
+        assert!(webhook(vec![], "").matches(create, &rsvp));
+        assert!(
+            webhook(vec![ReservationUpdateType::Create], "meeting-room-*").matches(create, &rsvp)
+        );
+        assert!(!webhook(vec![ReservationUpdateType::Create], "").matches(delete, &rsvp));
+        assert!(!webhook(vec![], "meeting-room-2").matches(create, &rsvp));
+        assert!(!webhook(vec![], "vehicle-*").matches(create, &rsvp));
+    }
+
+    #[test]
+    fn webhook_should_be_validated() {
+        assert!(webhook(vec![], "").validate().is_ok());
+        let invalid = |webhook: Webhook| webhook.validate().is_err();
+        assert!(invalid(Webhook {
+            url: "ftp://example.com".to_string(),
+            ..webhook(vec![], "")
+        }));
+        assert!(invalid(Webhook {
+            url: "/hooks".to_string(),
+            ..webhook(vec![], "")
+        }));
+        assert!(invalid(Webhook {
+            secret: String::new(),
+            ..webhook(vec![], "")
+        }));
+        assert!(invalid(Webhook {
+            events: vec![42],
+            ..webhook(vec![], "")
+        }));
+    }
+}
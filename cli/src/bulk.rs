@@ -0,0 +1,289 @@
+use abi::{Reservation, ReservationStatus};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, SecondsFormat};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use prost_types::Timestamp;
+use serde_json::{Map, Value};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Write},
+};
+
+use crate::time::parse_time_or_local;
+
+/// fields of a reservation in the file, in the order of the exported columns
+const FIELDS: [&str; 7] = [
+    "id",
+    "user_id",
+    "resource_id",
+    "start",
+    "end",
+    "status",
+    "note",
+];
+const ID: usize = 0;
+const USER: usize = 1;
+const RESOURCE: usize = 2;
+const START: usize = 3;
+const END: usize = 4;
+const STATUS: usize = 5;
+const NOTE: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Csv,
+    /// one json object per line
+    Ndjson,
+}
+
+/// the column of each field, the field name unless it is mapped by `field=column`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns([String; 7]);
+
+impl Columns {
+    pub fn new(mappings: &[String]) -> Result<Self> {
+        let mut columns = FIELDS.map(String::from);
+        for mapping in mappings {
+            let (field, column) = mapping
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid column mapping, expect field=column: {mapping}"))?;
+            let i = FIELDS
+                .iter()
+                .position(|f| *f == field.trim())
+                .ok_or_else(|| {
+                    anyhow!("unknown field {field}, expect one of {}", FIELDS.join(", "))
+                })?;
+            columns[i] = column.trim().to_string();
+        }
+        Ok(Self(columns))
+    }
+}
+
+/// open the file, `-` is stdin
+pub fn open(path: &str) -> Result<Box<dyn Read>> {
+    Ok(match path {
+        "-" => Box::new(io::stdin()),
+        path => Box::new(File::open(path).with_context(|| format!("failed to open {path}"))?),
+    })
+}
+
+/// create the file, `-` is stdout
+pub fn create(path: &str) -> Result<Box<dyn Write>> {
+    Ok(match path {
+        "-" => Box::new(io::stdout()),
+        path => Box::new(File::create(path).with_context(|| format!("failed to create {path}"))?),
+    })
+}
+
+/// read the reservations of the file, the times without an offset are in the timezone
+pub fn read(
+    reader: impl Read,
+    format: Format,
+    columns: &Columns,
+    tz: Option<Tz>,
+) -> Result<Vec<Reservation>> {
+    let mut rsvps = vec![];
+    match format {
+        Format::Csv => {
+            let mut reader = csv::Reader::from_reader(reader);
+            let headers = reader.headers()?.clone();
+            let index = columns
+                .0
+                .clone()
+                .map(|column| headers.iter().position(|h| h.trim() == column));
+            for (i, record) in reader.records().enumerate() {
+                let record = record?;
+                let get = |field: usize| index[field].and_then(|i| record.get(i));
+                let rsvp = reservation(get, tz).with_context(|| format!("row {}", i + 1))?;
+                rsvps.push(rsvp);
+            }
+        }
+        Format::Ndjson => {
+            for line in BufReader::new(reader).lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let row = rsvps.len() + 1;
+                let object: Map<String, Value> =
+                    serde_json::from_str(&line).with_context(|| format!("row {row}"))?;
+                let values = columns.0.clone().map(|column| match object.get(&column) {
+                    Some(Value::String(s)) => Some(s.clone()),
+                    Some(Value::Null) | None => None,
+                    Some(value) => Some(value.to_string()),
+                });
+                let get = |field: usize| values[field].as_deref();
+                let rsvp = reservation(get, tz).with_context(|| format!("row {row}"))?;
+                rsvps.push(rsvp);
+            }
+        }
+    }
+    Ok(rsvps)
+}
+
+/// writes the reservations one by one, so the export is not kept in memory
+pub struct Writer<W: Write> {
+    inner: WriterInner<W>,
+    columns: Columns,
+}
+
+enum WriterInner<W: Write> {
+    Csv(Box<csv::Writer<W>>),
+    Ndjson(W),
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(writer: W, format: Format, columns: &Columns) -> Result<Self> {
+        let inner = match format {
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(writer);
+                writer.write_record(&columns.0)?;
+                WriterInner::Csv(Box::new(writer))
+            }
+            Format::Ndjson => WriterInner::Ndjson(writer),
+        };
+        Ok(Self {
+            inner,
+            columns: columns.clone(),
+        })
+    }
+
+    pub fn write(&mut self, rsvp: &Reservation, tz: Option<Tz>) -> Result<()> {
+        let status = ReservationStatus::from_i32(rsvp.status).unwrap_or(ReservationStatus::Unknown);
+        let values = [
+            rsvp.id.to_string(),
+            rsvp.user_id.clone(),
+            rsvp.resource_id.clone(),
+            format_time(rsvp.start_time.as_ref(), tz),
+            format_time(rsvp.end_time.as_ref(), tz),
+            status.to_string(),
+            rsvp.note.clone(),
+        ];
+        match &mut self.inner {
+            WriterInner::Csv(writer) => writer.write_record(&values)?,
+            WriterInner::Ndjson(writer) => {
+                let mut object = Map::new();
+                for (i, (column, value)) in self.columns.0.iter().zip(values).enumerate() {
+                    let value = match i {
+                        ID => Value::from(rsvp.id),
+                        _ => Value::String(value),
+                    };
+                    object.insert(column.clone(), value);
+                }
+                writeln!(writer, "{}", Value::Object(object))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        match &mut self.inner {
+            WriterInner::Csv(writer) => writer.flush()?,
+            WriterInner::Ndjson(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// the reservation of a row, the id is ignored since the server assigns it
+fn reservation<'a>(get: impl Fn(usize) -> Option<&'a str>, tz: Option<Tz>) -> Result<Reservation> {
+    let required = |field: usize| {
+        get(field)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow!("missing {}", FIELDS[field]))
+    };
+    let time = |field: usize| -> Result<Timestamp> { parse_time_or_local(required(field)?, tz) };
+    let status = match get(STATUS).map(str::trim).filter(|s| !s.is_empty()) {
+        Some(status) => status.to_lowercase().parse()?,
+        None => ReservationStatus::Pending,
+    };
+    Ok(Reservation {
+        user_id: required(USER)?.to_string(),
+        resource_id: required(RESOURCE)?.to_string(),
+        start_time: Some(time(START)?),
+        end_time: Some(time(END)?),
+        status: status as i32,
+        note: get(NOTE).unwrap_or_default().to_string(),
+        ..Default::default()
+    })
+}
+
+/// rfc3339 in the timezone, so the file could be imported again
+fn format_time(ts: Option<&Timestamp>, tz: Option<Tz>) -> String {
+    ts.map(|ts| {
+        let dt = abi::convert_to_utc_time(ts);
+        match tz {
+            Some(tz) => dt
+                .with_timezone(&tz)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            None => dt
+                .with_timezone(&Local)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> Option<Timestamp> {
+        Some(abi::convert_to_timestamp(s.parse().unwrap()))
+    }
+
+    #[test]
+    fn columns_should_be_mapped() {
+        let columns = Columns::new(&["user_id=Customer".to_string()]).unwrap();
+        assert_eq!("Customer", columns.0[USER]);
+        assert_eq!("resource_id", columns.0[RESOURCE]);
+        assert!(Columns::new(&["user=Customer".to_string()]).is_err());
+        assert!(Columns::new(&["Customer".to_string()]).is_err());
+    }
+
+    #[test]
+    fn csv_should_be_read_in_the_timezone() {
+        let csv = "Customer,resource_id,start,end,note\n\
+                   tosei,room-1,2030-01-01 10:00,2030-01-01T12:00:00Z,\"hi, there\"\n";
+        let columns = Columns::new(&["user_id=Customer".to_string()]).unwrap();
+        let rsvps = read(csv.as_bytes(), Format::Csv, &columns, Some(Tz::Asia__Tokyo)).unwrap();
+        assert_eq!(1, rsvps.len());
+        assert_eq!("tosei", rsvps[0].user_id);
+        assert_eq!(ts("2030-01-01T01:00:00Z"), rsvps[0].start_time);
+        assert_eq!(ts("2030-01-01T12:00:00Z"), rsvps[0].end_time);
+        assert_eq!(ReservationStatus::Pending as i32, rsvps[0].status);
+        assert_eq!("hi, there", rsvps[0].note);
+
+        let missing = "user_id,resource_id,start\ntosei,room-1,2030-01-01 10:00\n";
+        let err = read(missing.as_bytes(), Format::Csv, &columns, None).unwrap_err();
+        assert_eq!("row 1", err.to_string());
+    }
+
+    #[test]
+    fn written_ndjson_should_be_read_back() {
+        let mut rsvp = Reservation::new_pending(
+            "tosei",
+            "room-1",
+            "2030-01-01T10:00:00Z".parse().unwrap(),
+            "2030-01-01T12:00:00Z".parse().unwrap(),
+            "hi",
+        );
+        rsvp.id = 12;
+        let columns = Columns::new(&["resource_id=room".to_string()]).unwrap();
+        let mut out = vec![];
+        let mut writer = Writer::new(&mut out, Format::Ndjson, &columns).unwrap();
+        writer.write(&rsvp, Some(Tz::Asia__Tokyo)).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        let line = String::from_utf8(out.clone()).unwrap();
+        assert!(line.contains(r#""id":12"#));
+        assert!(line.contains(r#""room":"room-1""#));
+        assert!(line.contains(r#""start":"2030-01-01T19:00:00+09:00""#));
+
+        let rsvps = read(out.as_slice(), Format::Ndjson, &columns, None).unwrap();
+        rsvp.id = 0;
+        assert_eq!(vec![rsvp], rsvps);
+    }
+}
@@ -0,0 +1,453 @@
+mod bulk;
+mod output;
+mod time;
+
+use abi::{
+    reservation_service_client::ReservationServiceClient, AddRequest, CancelRequest, Config,
+    ConfirmRequest, ExportRequest, FilterRequest, GetRequest, ImportRequest, ListenRequest,
+    QueryRequest, Reservation, ReservationFilter, ReservationQuery, ReservationStatus, RuleConfig,
+    UpdateRequest,
+};
+use anyhow::Result;
+use chrono_tz::Tz;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use prost_types::Timestamp;
+use tonic::transport::Channel;
+
+use crate::{
+    bulk::{Columns, Format, Writer},
+    output::Printer,
+    time::{parse_time, parse_time_or_local},
+};
+
+/// command-line client for the reservation service
+#[derive(Debug, Parser)]
+#[command(name = "rorder", version)]
+struct Cli {
+    /// configuration file of the server, used to get the server address
+    #[arg(
+        short,
+        long,
+        env = "RORDER_CONFIG",
+        default_value = "./reservation.yml"
+    )]
+    config: String,
+    /// server address, e.g. http://localhost:50051. Overrides the configuration file
+    #[arg(short, long, env = "RORDER_ADDR")]
+    addr: Option<String>,
+    /// print the result as json
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// make a reservation
+    Add {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        resource: String,
+        /// start time in the timezone of the reservation, e.g. "2023-01-25 15:00"
+        #[arg(short, long)]
+        start: String,
+        /// end time in the timezone of the reservation, e.g. "2023-01-25 17:00"
+        #[arg(short, long)]
+        end: String,
+        #[arg(short, long, default_value = "")]
+        note: String,
+        /// timezone of the reservation and its times, e.g. Asia/Tokyo. The one of the resource
+        /// in the configuration file by default, or the local one without the file
+        #[arg(long)]
+        timezone: Option<Tz>,
+    },
+    /// confirm a pending reservation
+    Confirm { id: i64 },
+    /// update the note of a reservation
+    Update {
+        id: i64,
+        #[arg(short, long)]
+        note: String,
+    },
+    /// cancel a reservation
+    Cancel { id: i64 },
+    /// get reservation by id
+    Get { id: i64 },
+    /// query reservations within a period
+    Query {
+        #[command(flatten)]
+        scope: Scope,
+        #[arg(short, long, value_parser = parse_time)]
+        start: Timestamp,
+        #[arg(short, long, value_parser = parse_time, required_unless_present = "period")]
+        end: Option<Timestamp>,
+        /// the day or week (monday to sunday) of the start instead of the range to the end
+        #[arg(long, value_enum, conflicts_with = "end")]
+        period: Option<Period>,
+        /// timezone of the day or week, e.g. Asia/Tokyo. UTC by default
+        #[arg(long, requires = "period")]
+        timezone: Option<Tz>,
+        #[arg(long, default_value_t = 1)]
+        page: i32,
+        #[arg(long, default_value_t = 10)]
+        page_size: i32,
+    },
+    /// filter reservations, order by id
+    Filter {
+        #[command(flatten)]
+        scope: Scope,
+        /// id of the last reservation of the previous page
+        #[arg(long)]
+        cursor: Option<i64>,
+        #[arg(long, default_value_t = 10)]
+        page_size: i64,
+    },
+    /// print the reservations when they are changed
+    Listen,
+    /// make the reservations of a csv or ndjson file, committed in chunks
+    Import {
+        /// the file to import, `-` is stdin
+        file: String,
+        #[command(flatten)]
+        file_args: FileArgs,
+        /// check every row and report the conflicts without committing
+        #[arg(long)]
+        dry_run: bool,
+        /// rows committed in one transaction, 500 by default
+        #[arg(long, default_value_t = 0)]
+        chunk_size: i32,
+    },
+    /// write the reservations matching the filter to a csv or ndjson file
+    Export {
+        #[command(flatten)]
+        scope: Scope,
+        /// the file to write, `-` is stdout
+        #[arg(default_value = "-")]
+        file: String,
+        #[command(flatten)]
+        file_args: FileArgs,
+    },
+}
+
+/// common conditions of query and filter
+#[derive(Debug, Args)]
+struct Scope {
+    #[arg(short, long, default_value = "")]
+    user: String,
+    #[arg(short, long, default_value = "")]
+    resource: String,
+    /// pending, confirmed or cancelled
+    #[arg(long, default_value = "pending")]
+    status: ReservationStatus,
+    #[arg(long)]
+    desc: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Period {
+    Day,
+    Week,
+}
+
+impl Period {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+        }
+    }
+}
+
+/// layout of the import and export files
+#[derive(Debug, Args)]
+struct FileArgs {
+    #[arg(short, long, value_enum, default_value = "csv")]
+    format: Format,
+    /// column of a field if it is not the field name, e.g. `user_id=Customer`. Fields are id,
+    /// user_id, resource_id, start, end, status and note
+    #[arg(long = "column", value_name = "FIELD=COLUMN")]
+    columns: Vec<String>,
+    /// timezone of the times without an offset, e.g. Asia/Tokyo. The local timezone by default
+    #[arg(long)]
+    timezone: Option<Tz>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    // the file gives the timezones of the resources, it is optional if the address is given
+    let (addr, config) = match cli.addr {
+        Some(addr) => (addr, Config::from_file(&cli.config).ok()),
+        None => {
+            let config = Config::from_file(&cli.config)?;
+            (config.server.url(false), Some(config))
+        }
+    };
+    let mut client = ReservationServiceClient::connect(addr).await?;
+    let printer = Printer { json: cli.json };
+
+    let rules = config.map(|config| config.rules);
+    run(&mut client, &printer, rules.as_ref(), cli.command).await
+}
+
+async fn run(
+    client: &mut ReservationServiceClient<Channel>,
+    printer: &Printer,
+    rules: Option<&RuleConfig>,
+    command: Command,
+) -> Result<()> {
+    match command {
+        Command::Add {
+            user,
+            resource,
+            start,
+            end,
+            note,
+            timezone,
+        } => {
+            // the server makes the reservation in the timezone of the resource if none is given
+            let tz = timezone.or_else(|| rules.map(|rules| rules.for_resource(&resource).timezone));
+            let reservation = Reservation {
+                user_id: user,
+                resource_id: resource,
+                start_time: Some(parse_time_or_local(&start, tz)?),
+                end_time: Some(parse_time_or_local(&end, tz)?),
+                status: ReservationStatus::Pending as i32,
+                note,
+                timezone: timezone.map(|tz| tz.name().to_string()).unwrap_or_default(),
+                ..Default::default()
+            };
+            let request = AddRequest {
+                reservation: Some(reservation),
+            };
+            let rsvp = client.add(request).await?.into_inner().reservation;
+            printer.reservation(&rsvp.unwrap_or_default());
+        }
+        Command::Confirm { id } => {
+            let rsvp = client.confirm(ConfirmRequest { id }).await?.into_inner();
+            printer.reservation(&rsvp.reservation.unwrap_or_default());
+        }
+        Command::Update { id, note } => {
+            let rsvp = client
+                .update(UpdateRequest { id, note })
+                .await?
+                .into_inner();
+            printer.reservation(&rsvp.reservation.unwrap_or_default());
+        }
+        Command::Cancel { id } => {
+            let rsvp = client.cancel(CancelRequest { id }).await?.into_inner();
+            printer.reservation(&rsvp.reservation.unwrap_or_default());
+        }
+        Command::Get { id } => {
+            let rsvp = client.get(GetRequest { id }).await?.into_inner();
+            printer.reservation(&rsvp.reservation.unwrap_or_default());
+        }
+        Command::Query {
+            scope,
+            start,
+            end,
+            period,
+            timezone,
+            page,
+            page_size,
+        } => {
+            let query = ReservationQuery {
+                resource_id: scope.resource,
+                user_id: scope.user,
+                status: scope.status as i32,
+                start: Some(start),
+                end,
+                desc: scope.desc,
+                page,
+                page_size,
+                period: period.map(|p| p.as_str().to_string()).unwrap_or_default(),
+                timezone: timezone.map(|tz| tz.name().to_string()).unwrap_or_default(),
+            };
+            let request = QueryRequest { query: Some(query) };
+            let mut stream = client.query(request).await?.into_inner();
+            let mut rsvps = vec![];
+            while let Some(rsvp) = stream.message().await? {
+                rsvps.push(rsvp);
+            }
+            printer.reservations(&rsvps);
+        }
+        Command::Filter {
+            scope,
+            cursor,
+            page_size,
+        } => {
+            let filter = ReservationFilter {
+                resource_id: scope.resource,
+                user_id: scope.user,
+                status: scope.status as i32,
+                cursor,
+                page_size,
+                desc: scope.desc,
+            };
+            let request = FilterRequest {
+                filter: Some(filter),
+            };
+            let response = client.filter(request).await?.into_inner();
+            printer.filter(&response.reservations, response.pager.as_ref());
+        }
+        Command::Listen => {
+            let mut stream = client.listen(ListenRequest {}).await?.into_inner();
+            while let Some(rsvp) = stream.message().await? {
+                printer.event(&rsvp);
+            }
+        }
+        Command::Import {
+            file,
+            file_args,
+            dry_run,
+            chunk_size,
+        } => {
+            let columns = Columns::new(&file_args.columns)?;
+            let rsvps = bulk::read(
+                bulk::open(&file)?,
+                file_args.format,
+                &columns,
+                file_args.timezone,
+            )?;
+            let requests = rsvps.into_iter().map(move |rsvp| ImportRequest {
+                reservation: Some(rsvp),
+                dry_run,
+                chunk_size,
+            });
+            let mut stream = client
+                .import(futures::stream::iter(requests))
+                .await?
+                .into_inner();
+            let mut results = vec![];
+            while let Some(result) = stream.message().await? {
+                printer.import_result(&result);
+                results.push(result);
+            }
+            printer.import_summary(&results, dry_run);
+        }
+        Command::Export {
+            scope,
+            file,
+            file_args,
+        } => {
+            let columns = Columns::new(&file_args.columns)?;
+            let filter = ReservationFilter {
+                resource_id: scope.resource,
+                user_id: scope.user,
+                status: scope.status as i32,
+                desc: scope.desc,
+                ..Default::default()
+            };
+            let request = ExportRequest {
+                filter: Some(filter),
+            };
+            let mut stream = client.export(request).await?.into_inner();
+            let mut writer = Writer::new(bulk::create(&file)?, file_args.format, &columns)?;
+            while let Some(rsvp) = stream.message().await? {
+                writer.write(&rsvp, file_args.timezone)?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_should_be_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn filter_args_should_be_parsed() {
+        let cli = Cli::try_parse_from([
+            "rorder",
+            "--json",
+            "filter",
+            "-u",
+            "tosei",
+            "--status",
+            "confirmed",
+            "--cursor",
+            "10",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        match cli.command {
+            Command::Filter { scope, cursor, .. } => {
+                assert_eq!("tosei", scope.user);
+                assert_eq!(ReservationStatus::Confirmed, scope.status);
+                assert_eq!(Some(10), cursor);
+            }
+            _ => panic!("expect filter command"),
+        }
+    }
+
+    #[test]
+    fn query_args_should_be_parsed() {
+        let cli = Cli::try_parse_from([
+            "rorder",
+            "query",
+            "-s",
+            "2030-01-01T10:00:00+09:00",
+            "--period",
+            "week",
+            "--timezone",
+            "Asia/Tokyo",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Query {
+                end,
+                period,
+                timezone,
+                ..
+            } => {
+                assert_eq!(None, end);
+                assert_eq!(Some(Period::Week), period);
+                assert_eq!(Some(Tz::Asia__Tokyo), timezone);
+            }
+            _ => panic!("expect query command"),
+        }
+        // either the end or the period is required
+        assert!(Cli::try_parse_from(["rorder", "query", "-s", "2030-01-01"]).is_err());
+    }
+
+    #[test]
+    fn import_args_should_be_parsed() {
+        let cli = Cli::try_parse_from([
+            "rorder",
+            "import",
+            "rows.ndjson",
+            "--format",
+            "ndjson",
+            "--column",
+            "user_id=Customer",
+            "--timezone",
+            "Asia/Tokyo",
+            "--dry-run",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Import {
+                file,
+                file_args,
+                dry_run,
+                ..
+            } => {
+                assert_eq!("rows.ndjson", file);
+                assert_eq!(Format::Ndjson, file_args.format);
+                assert_eq!(vec!["user_id=Customer"], file_args.columns);
+                assert_eq!(Some(Tz::Asia__Tokyo), file_args.timezone);
+                assert!(dry_run);
+            }
+            _ => panic!("expect import command"),
+        }
+    }
+}
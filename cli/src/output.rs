@@ -0,0 +1,147 @@
+use abi::{FilterPager, ImportResult, Reservation, ReservationStatus};
+use serde::Serialize;
+
+use crate::time::format_time;
+
+const HEADERS: [&str; 7] = ["ID", "USER", "RESOURCE", "START", "END", "STATUS", "NOTE"];
+
+/// print the value as json or as a human-readable table
+pub struct Printer {
+    pub json: bool,
+}
+
+impl Printer {
+    pub fn reservation(&self, rsvp: &Reservation) {
+        if self.json {
+            print_json(rsvp);
+        } else {
+            print!("{}", table(std::slice::from_ref(rsvp)));
+        }
+    }
+
+    pub fn reservations(&self, rsvps: &[Reservation]) {
+        if self.json {
+            print_json(&rsvps);
+        } else {
+            print!("{}", table(rsvps));
+        }
+    }
+
+    pub fn filter(&self, rsvps: &[Reservation], pager: Option<&FilterPager>) {
+        if self.json {
+            print_json(&serde_json::json!({ "reservations": rsvps, "pager": pager }));
+            return;
+        }
+        print!("{}", table(rsvps));
+        if let Some(next) = pager.and_then(|p| p.next) {
+            println!("next cursor: {next}");
+        }
+    }
+
+    /// reservations of the listen stream, one line per reservation in json mode
+    pub fn event(&self, rsvp: &Reservation) {
+        if self.json {
+            println!("{}", serde_json::to_string(rsvp).unwrap());
+        } else {
+            println!("{}", row(rsvp).join("  "));
+        }
+    }
+
+    /// result of an imported row, one line per row in json mode
+    pub fn import_result(&self, result: &ImportResult) {
+        if self.json {
+            let value = serde_json::json!({
+                "row": result.row,
+                "reservation": result.reservation,
+                "error": result.error,
+                "conflicting_id": result.conflict.as_ref().map(|c| c.conflicting_id),
+            });
+            println!("{value}");
+        } else if result.error.is_empty() {
+            let rsvp = result.reservation.clone().unwrap_or_default();
+            println!("row {}: {}", result.row, row(&rsvp).join("  "));
+        } else {
+            println!("row {}: {}", result.row, result.error);
+        }
+    }
+
+    pub fn import_summary(&self, results: &[ImportResult], dry_run: bool) {
+        if self.json {
+            return;
+        }
+        let failed = results.iter().filter(|r| !r.error.is_empty()).count();
+        let verb = if dry_run {
+            "would be imported"
+        } else {
+            "imported"
+        };
+        println!("{} {verb}, {failed} failed", results.len() - failed);
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap());
+}
+
+fn row(rsvp: &Reservation) -> [String; 7] {
+    let status = ReservationStatus::from_i32(rsvp.status).unwrap_or(ReservationStatus::Unknown);
+    [
+        rsvp.id.to_string(),
+        rsvp.user_id.clone(),
+        rsvp.resource_id.clone(),
+        format_time(rsvp.start_time.as_ref()),
+        format_time(rsvp.end_time.as_ref()),
+        status.to_string(),
+        rsvp.note.clone(),
+    ]
+}
+
+/// columns aligned by the widest cell
+fn table(rsvps: &[Reservation]) -> String {
+    let rows: Vec<[String; 7]> = rsvps.iter().map(row).collect();
+    let mut widths = HEADERS.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut push_line = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    };
+    push_line(&HEADERS);
+    for row in &rows {
+        push_line(&row.each_ref().map(|s| s.as_str()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_should_be_aligned() {
+        let mut rsvp = Reservation::new_pending(
+            "tosei",
+            "ocean room-745",
+            "2022-11-01T15:00:00+0800".parse().unwrap(),
+            "2022-11-07T12:00:00+0800".parse().unwrap(),
+            "hi",
+        );
+        rsvp.id = 12;
+        let out = table(&[rsvp]);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(2, lines.len());
+        assert!(lines[0].starts_with("ID  USER   RESOURCE        START"));
+        assert!(lines[1].starts_with("12  tosei  ocean room-745  "));
+        assert!(lines[1].ends_with("pending  hi"));
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use prost_types::Timestamp;
+
+/// parse the time in the local timezone unless an offset is given
+///
+/// accepts rfc3339 (2023-01-25T15:00:00+08:00), `2023-01-25 15:00[:00]` and `2023-01-25`
+pub fn parse_time(s: &str) -> Result<Timestamp> {
+    parse_time_in(s, &Local)
+}
+
+/// parse the time in the timezone, or in the local one if it is not given
+pub(crate) fn parse_time_or_local(s: &str, tz: Option<chrono_tz::Tz>) -> Result<Timestamp> {
+    match tz {
+        Some(tz) => parse_time_in(s, &tz),
+        None => parse_time(s),
+    }
+}
+
+pub(crate) fn parse_time_in<Tz: TimeZone>(s: &str, tz: &Tz) -> Result<Timestamp> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(abi::convert_to_timestamp(dt.with_timezone(&Utc)));
+    }
+
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| anyhow!("invalid time: {s}"))?;
+
+    let dt = tz
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| anyhow!("time does not exist in the timezone: {s}"))?;
+    Ok(abi::convert_to_timestamp(dt.with_timezone(&Utc)))
+}
+
+/// format the timestamp in the local timezone
+pub fn format_time(ts: Option<&Timestamp>) -> String {
+    ts.map(|ts| {
+        abi::convert_to_utc_time(ts)
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn parse_time_should_use_the_given_timezone() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let expected = abi::convert_to_timestamp("2023-01-25T06:00:00Z".parse().unwrap());
+        assert_eq!(expected, parse_time_in("2023-01-25 15:00", &tokyo).unwrap());
+        assert_eq!(
+            expected,
+            parse_time_in("2023-01-25 15:00:00", &tokyo).unwrap()
+        );
+        assert_eq!(
+            expected,
+            parse_time_in("2023-01-25T15:00:00+09:00", &Utc).unwrap()
+        );
+        assert!(parse_time_in("next monday", &tokyo).is_err());
+    }
+
+    #[test]
+    fn parse_time_should_fall_back_to_the_local_timezone() {
+        let tokyo = Some(chrono_tz::Asia::Tokyo);
+        let expected = abi::convert_to_timestamp("2023-01-25T06:00:00Z".parse().unwrap());
+        assert_eq!(
+            expected,
+            parse_time_or_local("2023-01-25 15:00", tokyo).unwrap()
+        );
+        assert_eq!(
+            parse_time("2023-01-25 15:00").unwrap(),
+            parse_time_or_local("2023-01-25 15:00", None).unwrap()
+        );
+    }
+}
@@ -0,0 +1,998 @@
+mod retry;
+
+use abi::{
+    convert_to_timestamp, reservation_service_client::ReservationServiceClient, AddRequest,
+    ApproveRequest, AuditEntry, CancelRequest, CheckInRequest, CheckOutRequest, Config,
+    ConfirmRequest, DeadLetter, DeleteRequest, Error, ExportCalendarRequest, ExtendRequest,
+    FilterPager, FilterRequest, GetRequest, HistoryRequest, HoldRequest, HoldResponse,
+    ImportCalendarRequest, ImportedEvent, JoinWaitlistRequest, LeaveWaitlistRequest,
+    ListDeadLettersRequest, ListWaitlistRequest, ListWebhooksRequest, ListenRequest,
+    ListenResponse, NoShowsRequest, PromoteHoldRequest, PurgeRequest, QueryAuditRequest,
+    QueryRequest, QuotaRequest, QuotaUsage, RegisterWebhookRequest, RejectRequest,
+    ReleaseEarlyRequest, ReplayWebhookRequest, Reservation, ReservationFilter, ReservationQuery,
+    ReservationStatus, UnregisterWebhookRequest, UpdateRequest, WaitlistEntry, Webhook,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
+use std::time::Duration;
+use tonic::{
+    codegen::InterceptedService,
+    metadata::{Ascii, MetadataValue},
+    service::Interceptor,
+    transport::{Channel, Endpoint},
+    Request, Status,
+};
+use uuid::Uuid;
+
+pub use retry::*;
+
+pub type ReservationId = i64;
+
+/// the metadata of the request id, the server does not repeat the change of a retried request
+const REQUEST_ID_METADATA: &str = "x-request-id";
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// addresses of the servers, e.g. http://localhost:50051
+    pub endpoints: Vec<String>,
+    /// connections opened to every endpoint, requests are balanced among them
+    pub pool_size: usize,
+    pub connect_timeout: Duration,
+    /// timeout of every unary rpc
+    pub timeout: Duration,
+    pub retry: RetryPolicy,
+    /// the bearer token of the user, required by the servers which verify the tokens
+    pub token: Option<String>,
+}
+
+/// query reservations within the period, chrono version of `ReservationQuery`
+#[derive(Debug, Clone)]
+pub struct RsvpQuery {
+    pub resource_id: String,
+    pub user_id: String,
+    pub status: ReservationStatus,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub desc: bool,
+    pub page: i32,
+    pub page_size: i32,
+    /// "day" or "week" of the start in the timezone, the end is ignored if set
+    pub period: String,
+    /// IANA name of the timezone of the period, UTC if empty
+    pub timezone: String,
+}
+
+/// high-level client of the reservation service
+#[derive(Debug, Clone)]
+pub struct RsvpClient {
+    inner: ReservationServiceClient<InterceptedService<Channel, BearerToken>>,
+    retry: RetryPolicy,
+}
+
+/// add the authorization metadata of the token to every request
+#[derive(Debug, Clone, Default)]
+pub struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl ClientConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoints: vec![endpoint.into()],
+            pool_size: 1,
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(30),
+            retry: RetryPolicy::default(),
+            token: None,
+        }
+    }
+
+    /// connect to the server of the configuration file
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.server.url(false))
+    }
+}
+
+impl RsvpQuery {
+    pub fn new<Tz: TimeZone>(start: DateTime<Tz>, end: DateTime<Tz>) -> Self {
+        Self {
+            resource_id: String::new(),
+            user_id: String::new(),
+            status: ReservationStatus::Pending,
+            start: start.with_timezone(&Utc),
+            end: end.with_timezone(&Utc),
+            desc: false,
+            page: 1,
+            page_size: 10,
+            period: String::new(),
+            timezone: String::new(),
+        }
+    }
+
+    /// query the day or week of the start in the timezone, e.g. `("week", "Asia/Tokyo")`
+    pub fn within(mut self, period: impl Into<String>, timezone: impl Into<String>) -> Self {
+        self.period = period.into();
+        self.timezone = timezone.into();
+        self
+    }
+}
+
+impl From<RsvpQuery> for ReservationQuery {
+    fn from(query: RsvpQuery) -> Self {
+        Self {
+            resource_id: query.resource_id,
+            user_id: query.user_id,
+            status: query.status as i32,
+            start: Some(convert_to_timestamp(query.start)),
+            end: Some(convert_to_timestamp(query.end)),
+            desc: query.desc,
+            page: query.page,
+            page_size: query.page_size,
+            period: query.period,
+            timezone: query.timezone,
+        }
+    }
+}
+
+impl BearerToken {
+    pub fn new(token: &str) -> Result<Self, Error> {
+        let value = format!("Bearer {token}")
+            .parse()
+            .map_err(|_| Error::RpcError(Box::new(Status::invalid_argument("invalid token"))))?;
+        Ok(Self(Some(value)))
+    }
+}
+
+impl Interceptor for BearerToken {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(token) = &self.0 {
+            request
+                .metadata_mut()
+                .insert("authorization", token.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// a new request id for every call, the same in all the attempts of the call
+fn new_request_id() -> MetadataValue<Ascii> {
+    Uuid::new_v4().to_string().parse().unwrap()
+}
+
+fn with_request_id<T>(message: T, request_id: &MetadataValue<Ascii>) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert(REQUEST_ID_METADATA, request_id.clone());
+    request
+}
+
+impl RsvpClient {
+    /// the connections are opened lazily and reconnected when they are broken
+    pub fn connect(config: ClientConfig) -> Result<Self, Error> {
+        let mut endpoints = vec![];
+        for endpoint in &config.endpoints {
+            let endpoint = Endpoint::from_shared(endpoint.clone())
+                .map_err(|e| Error::RpcError(Box::new(Status::invalid_argument(e.to_string()))))?
+                .connect_timeout(config.connect_timeout)
+                .timeout(config.timeout);
+            endpoints.extend(std::iter::repeat_n(endpoint, config.pool_size.max(1)));
+        }
+        let channel = Channel::balance_list(endpoints.into_iter());
+        let token = match config.token {
+            Some(token) => BearerToken::new(&token)?,
+            None => BearerToken::default(),
+        };
+        Ok(Self::with_token(channel, config.retry, token))
+    }
+
+    pub fn new(channel: Channel, retry: RetryPolicy) -> Self {
+        Self::with_token(channel, retry, BearerToken::default())
+    }
+
+    pub fn with_token(channel: Channel, retry: RetryPolicy, token: BearerToken) -> Self {
+        Self {
+            inner: ReservationServiceClient::with_interceptor(channel, token),
+            retry,
+        }
+    }
+
+    /// create a pending reservation
+    pub async fn create_order<Tz: TimeZone>(
+        &self,
+        user_id: impl Into<String>,
+        resource_id: impl Into<String>,
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        note: impl Into<String>,
+    ) -> Result<Reservation, Error> {
+        let rsvp = Reservation {
+            user_id: user_id.into(),
+            resource_id: resource_id.into(),
+            start_time: Some(convert_to_timestamp(start.with_timezone(&Utc))),
+            end_time: Some(convert_to_timestamp(end.with_timezone(&Utc))),
+            status: ReservationStatus::Pending as i32,
+            note: note.into(),
+            ..Default::default()
+        };
+        let request_id = new_request_id();
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = with_request_id(
+                    AddRequest {
+                        reservation: Some(rsvp.clone()),
+                    },
+                    &request_id,
+                );
+                async move { client.add(request).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// change the status of a reservation(if current status is pending, change it to confirmed)
+    pub async fn change_status(&self, id: ReservationId) -> Result<Reservation, Error> {
+        // confirm twice returns not found, the retry sends the same request id, so the server
+        // returns the confirmed one
+        let request_id = new_request_id();
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = with_request_id(ConfirmRequest { id }, &request_id);
+                async move { client.confirm(request).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// update the note of a reservation
+    pub async fn update_note(
+        &self,
+        id: ReservationId,
+        note: impl Into<String>,
+    ) -> Result<Reservation, Error> {
+        let note = note.into();
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = UpdateRequest {
+                    id,
+                    note: note.clone(),
+                };
+                async move { client.update(request).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// cancel reservation
+    pub async fn cancel_reservation(&self, id: ReservationId) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.cancel(CancelRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// get reservation by id
+    pub async fn get_reservation(&self, id: ReservationId) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.get(GetRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// query reservations within the period
+    pub async fn query_reservations(
+        &self,
+        query: RsvpQuery,
+    ) -> Result<BoxStream<'static, Result<Reservation, Error>>, Error> {
+        let query: ReservationQuery = query.into();
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = QueryRequest {
+                    query: Some(query.clone()),
+                };
+                async move { client.query(request).await }
+            })
+            .await?;
+        Ok(response.into_inner().map_err(Error::from).boxed())
+    }
+
+    /// a page of reservations, order by id
+    pub async fn filter_page(
+        &self,
+        filter: ReservationFilter,
+    ) -> Result<(FilterPager, Vec<Reservation>), Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = FilterRequest {
+                    filter: Some(filter.clone()),
+                };
+                async move { client.filter(request).await }
+            })
+            .await?
+            .into_inner();
+        Ok((response.pager.unwrap_or_default(), response.reservations))
+    }
+
+    /// all reservations after the cursor of the filter, the pages are fetched when they are polled
+    pub fn filter_reservations(
+        &self,
+        filter: ReservationFilter,
+    ) -> BoxStream<'static, Result<Reservation, Error>> {
+        let client = self.clone();
+        stream::try_unfold(Some(filter), move |filter| {
+            let client = client.clone();
+            async move {
+                let filter = match filter {
+                    Some(filter) => filter,
+                    None => return Ok::<_, Error>(None),
+                };
+                let (pager, rsvps) = client.filter_page(filter.clone()).await?;
+                // the cursor of the next page is the last id of this page
+                let next = pager.next.map(|cursor| ReservationFilter {
+                    cursor: Some(cursor),
+                    ..filter
+                });
+                Ok(Some((stream::iter(rsvps.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// listen to newly added/confirmed/cancelled reservations
+    pub async fn listen(&self) -> Result<BoxStream<'static, Result<Reservation, Error>>, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.listen(ListenRequest {}).await }
+            })
+            .await?;
+        Ok(response.into_inner().map_err(Error::from).boxed())
+    }
+
+    /// listen to the changes of the reservations with their update type
+    pub async fn listen_events(
+        &self,
+    ) -> Result<BoxStream<'static, Result<ListenResponse, Error>>, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.listen_events(ListenRequest {}).await }
+            })
+            .await?;
+        Ok(response.into_inner().map_err(Error::from).boxed())
+    }
+
+    /// approve the current step of a pending reservation of a restricted resource as the user
+    /// of the bearer token
+    pub async fn approve(
+        &self,
+        id: ReservationId,
+        reason: impl Into<String>,
+    ) -> Result<Reservation, Error> {
+        let request = ApproveRequest {
+            id,
+            reason: reason.into(),
+        };
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let request = request.clone();
+                async move { client.approve(request).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// reject a pending reservation of a restricted resource, its window is freed
+    pub async fn reject(
+        &self,
+        id: ReservationId,
+        reason: impl Into<String>,
+    ) -> Result<Reservation, Error> {
+        let request = RejectRequest {
+            id,
+            reason: reason.into(),
+        };
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let request = request.clone();
+                async move { client.reject(request).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// lock the window of the reservation, ttl 0 means the ttl configured in the server
+    pub async fn hold(&self, rsvp: Reservation, ttl: Duration) -> Result<HoldResponse, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let request = HoldRequest {
+                    reservation: Some(rsvp.clone()),
+                    ttl_seconds: ttl.as_secs() as u32,
+                };
+                async move { client.hold(request).await }
+            })
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// turn the hold into a pending reservation, or a confirmed one
+    pub async fn promote_hold(
+        &self,
+        id: ReservationId,
+        confirm: bool,
+    ) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move {
+                    client
+                        .promote_hold(PromoteHoldRequest { id, confirm })
+                        .await
+                }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// queue for the window of a resource which is already booked
+    pub async fn join_waitlist(&self, entry: WaitlistEntry) -> Result<WaitlistEntry, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let request = JoinWaitlistRequest {
+                    entry: Some(entry.clone()),
+                };
+                async move { client.join_waitlist(request).await }
+            })
+            .await?;
+        response.into_inner().entry.ok_or(Error::Unknown)
+    }
+
+    /// leave the waitlist
+    pub async fn leave_waitlist(&self, id: i64) -> Result<WaitlistEntry, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move { client.leave_waitlist(LeaveWaitlistRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().entry.ok_or(Error::Unknown)
+    }
+
+    /// waiting and notified entries in queue order, empty ids match all
+    pub async fn list_waitlist(
+        &self,
+        resource_id: impl Into<String>,
+        user_id: impl Into<String>,
+    ) -> Result<Vec<WaitlistEntry>, Error> {
+        let request = ListWaitlistRequest {
+            resource_id: resource_id.into(),
+            user_id: user_id.into(),
+        };
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = request.clone();
+                async move { client.list_waitlist(request).await }
+            })
+            .await?;
+        Ok(response.into_inner().entries)
+    }
+
+    /// hide the reservation from get, query and filter, an active one is cancelled
+    pub async fn delete_reservation(&self, id: ReservationId) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.delete(DeleteRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// remove the reservation permanently, only the admins could purge
+    pub async fn purge_reservation(&self, id: ReservationId) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move { client.purge(PurgeRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// the reservations of the query as an iCalendar, the query must have a user or a resource
+    pub async fn export_calendar(&self, query: ReservationQuery) -> Result<String, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let query = Some(query.clone());
+                async move {
+                    client
+                        .export_calendar(ExportCalendarRequest { query })
+                        .await
+                }
+            })
+            .await?;
+        Ok(response.into_inner().calendar)
+    }
+
+    /// make a reservation of each event of the calendar for the user, on the resource or the
+    /// LOCATION of the events if it is empty
+    pub async fn import_calendar(
+        &self,
+        user_id: &str,
+        resource_id: &str,
+        calendar: &str,
+    ) -> Result<Vec<ImportedEvent>, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let request = ImportCalendarRequest {
+                    user_id: user_id.to_string(),
+                    resource_id: resource_id.to_string(),
+                    calendar: calendar.to_string(),
+                };
+                async move { client.import_calendar(request).await }
+            })
+            .await?;
+        Ok(response.into_inner().events)
+    }
+
+    /// post the reservation changes matching the filters of the webhook to its url
+    pub async fn register_webhook(&self, webhook: Webhook) -> Result<Webhook, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let webhook = Some(webhook.clone());
+                async move {
+                    client
+                        .register_webhook(RegisterWebhookRequest { webhook })
+                        .await
+                }
+            })
+            .await?;
+        response.into_inner().webhook.ok_or(Error::Unknown)
+    }
+
+    /// stop posting to the webhook
+    pub async fn unregister_webhook(&self, id: i64) -> Result<Webhook, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move {
+                    client
+                        .unregister_webhook(UnregisterWebhookRequest { id })
+                        .await
+                }
+            })
+            .await?;
+        response.into_inner().webhook.ok_or(Error::Unknown)
+    }
+
+    /// the registered webhooks, without their secrets
+    pub async fn list_webhooks(&self) -> Result<Vec<Webhook>, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.list_webhooks(ListWebhooksRequest {}).await }
+            })
+            .await?;
+        Ok(response.into_inner().webhooks)
+    }
+
+    /// the deliveries of the webhook which failed every attempt
+    pub async fn list_dead_letters(&self, webhook_id: i64) -> Result<Vec<DeadLetter>, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move {
+                    client
+                        .list_dead_letters(ListDeadLettersRequest { webhook_id })
+                        .await
+                }
+            })
+            .await?;
+        Ok(response.into_inner().dead_letters)
+    }
+
+    /// deliver the dead letters of the webhook again, all of them if `ids` is empty
+    pub async fn replay_webhook(&self, webhook_id: i64, ids: Vec<i64>) -> Result<i64, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let ids = ids.clone();
+                async move {
+                    client
+                        .replay_webhook(ReplayWebhookRequest { webhook_id, ids })
+                        .await
+                }
+            })
+            .await?;
+        Ok(response.into_inner().replayed)
+    }
+
+    /// the changes of the reservation in order, with the actors and the snapshots
+    pub async fn history(&self, id: ReservationId) -> Result<Vec<AuditEntry>, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.history(HistoryRequest { id }).await }
+            })
+            .await?;
+        Ok(response.into_inner().entries)
+    }
+
+    /// a page of the audit log, returns the cursor of the next page or 0 if it is the last
+    pub async fn query_audit(
+        &self,
+        query: QueryAuditRequest,
+    ) -> Result<(Vec<AuditEntry>, i64), Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = query.clone();
+                async move { client.query_audit(request).await }
+            })
+            .await?
+            .into_inner();
+        Ok((response.entries, response.next_cursor))
+    }
+
+    /// the usage and the remaining quotas of the user
+    pub async fn quota(&self, user_id: impl Into<String>) -> Result<Vec<QuotaUsage>, Error> {
+        let request = QuotaRequest {
+            user_id: user_id.into(),
+        };
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = request.clone();
+                async move { client.quota(request).await }
+            })
+            .await?;
+        Ok(response.into_inner().quotas)
+    }
+
+    /// check in the confirmed reservation, shortly before its start at the earliest
+    pub async fn check_in(&self, id: ReservationId) -> Result<Reservation, Error> {
+        // a second check in is refused, so it is not idempotent
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move { client.check_in(CheckInRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// check out the checked in reservation
+    pub async fn check_out(&self, id: ReservationId) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move { client.check_out(CheckOutRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// the count of the no-shows of the user, the ones started since the time if it is given
+    pub async fn no_shows(
+        &self,
+        user_id: impl Into<String>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<i64, Error> {
+        let request = NoShowsRequest {
+            user_id: user_id.into(),
+            since: since.map(convert_to_timestamp),
+        };
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                let request = request.clone();
+                async move { client.no_shows(request).await }
+            })
+            .await?;
+        Ok(response.into_inner().count)
+    }
+
+    /// move the end of the reservation later, a conflict has the latest end it could take
+    pub async fn extend<Tz: TimeZone>(
+        &self,
+        id: ReservationId,
+        end: DateTime<Tz>,
+    ) -> Result<Reservation, Error> {
+        let end_time = Some(convert_to_timestamp(end.with_timezone(&Utc)));
+        // the same extension twice is refused, so it is not idempotent
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                let request = ExtendRequest {
+                    id,
+                    end_time: end_time.clone(),
+                };
+                async move { client.extend(request).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// end the ongoing reservation now, the rest of its window could be booked again
+    pub async fn release_early(&self, id: ReservationId) -> Result<Reservation, Error> {
+        let response = self
+            .retry
+            .run(false, || {
+                let mut client = self.inner.clone();
+                async move { client.release_early(ReleaseEarlyRequest { id }).await }
+            })
+            .await?;
+        response.into_inner().reservation.ok_or(Error::Unknown)
+    }
+
+    /// listen to the changed waitlist entries
+    pub async fn listen_waitlist(
+        &self,
+    ) -> Result<BoxStream<'static, Result<WaitlistEntry, Error>>, Error> {
+        let response = self
+            .retry
+            .run(true, || {
+                let mut client = self.inner.clone();
+                async move { client.listen_waitlist(ListenRequest {}).await }
+            })
+            .await?;
+        Ok(response.into_inner().map_err(Error::from).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use abi::{
+        reservation_service_server::ReservationServiceServer, ReservationConflictInfo,
+        ReservationFilterBuilder,
+    };
+    use chrono::FixedOffset;
+    use roder_service::{AuditLayer, RsvpService, TestConfig};
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::{body::BoxBody, codegen::http, transport::Server};
+    use tower::util::MapResponseLayer;
+
+    async fn start_server() -> (TestConfig, RsvpClient) {
+        let config = TestConfig::new("../service/fixtures/config.yml");
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(ReservationServiceServer::new(svc))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let mut client_config = ClientConfig::new(format!("http://{addr}"));
+        client_config.pool_size = 2;
+        (config, RsvpClient::connect(client_config).unwrap())
+    }
+
+    fn time(s: &str) -> DateTime<FixedOffset> {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn lost_response_should_be_retried_without_changing_twice() {
+        let config = TestConfig::new("../service/fixtures/config.yml");
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // the change of every first attempt is applied, but the client gets unavailable
+        let attempts = Arc::new(AtomicU32::new(0));
+        let lose_first = MapResponseLayer::new(move |mut res: http::Response<BoxBody>| {
+            if attempts.fetch_add(1, Ordering::SeqCst).is_multiple_of(2) {
+                res.headers_mut().insert("grpc-status", 14.into());
+            }
+            res
+        });
+        tokio::spawn(
+            Server::builder()
+                .layer(AuditLayer)
+                .layer(lose_first)
+                .add_service(ReservationServiceServer::new(svc))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        let mut client_config = ClientConfig::new(format!("http://{addr}"));
+        client_config.retry.base_delay = Duration::from_millis(1);
+        let client = RsvpClient::connect(client_config).unwrap();
+
+        let rsvp = client
+            .create_order(
+                "tosei",
+                "ocean-view-room-713",
+                time("2022-12-25T15:00:00-0700"),
+                time("2022-12-28T12:00:00-0700"),
+                "hello",
+            )
+            .await
+            .unwrap();
+        let confirmed = client.change_status(rsvp.id).await.unwrap();
+        assert_eq!(ReservationStatus::Confirmed as i32, confirmed.status);
+
+        let history = client.history(rsvp.id).await.unwrap();
+        assert_eq!(2, history.len());
+        // every call has its own request id
+        assert!(!history[0].request_id.is_empty());
+        assert_ne!(history[0].request_id, history[1].request_id);
+    }
+
+    #[tokio::test]
+    async fn client_should_send_the_bearer_token() {
+        let mut config = TestConfig::new("../service/fixtures/config.yml");
+        config.config.tenancy.jwt_secret = Some("secret".to_string());
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .layer(svc.tenant_layer())
+                .add_service(ReservationServiceServer::new(svc))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let claims = serde_json::json!({"tenant_id": "default", "sub": "admin", "exp": u32::MAX});
+        let key = jsonwebtoken::EncodingKey::from_secret(b"secret");
+        let token = jsonwebtoken::encode(&Default::default(), &claims, &key).unwrap();
+        let mut client_config = ClientConfig::new(format!("http://{addr}"));
+        client_config.retry = RetryPolicy::none();
+        let anonymous = RsvpClient::connect(client_config.clone()).unwrap();
+        let err = anonymous
+            .query_audit(QueryAuditRequest::default())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::RpcError(status) if status.code() == tonic::Code::Unauthenticated)
+        );
+
+        client_config.token = Some(token);
+        let admin = RsvpClient::connect(client_config).unwrap();
+        let (entries, _) = admin
+            .query_audit(QueryAuditRequest::default())
+            .await
+            .unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn client_create_and_get_should_be_work() {
+        let (_config, client) = start_server().await;
+        let rsvp = client
+            .create_order(
+                "tosei",
+                "ocean room-745",
+                time("2022-11-01T15:00:00+0800"),
+                time("2022-11-07T12:00:00+0800"),
+                "please check the room for me",
+            )
+            .await
+            .unwrap();
+        assert!(rsvp.id != 0);
+        assert_eq!(rsvp, client.get_reservation(rsvp.id).await.unwrap());
+
+        let err = client
+            .create_order(
+                "wxy",
+                "ocean room-745",
+                time("2022-11-04T15:00:00+0800"),
+                time("2022-11-08T12:00:00+0800"),
+                "love this room",
+            )
+            .await
+            .unwrap_err();
+        match err {
+            Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict)) => {
+                assert_eq!("ocean room-745", conflict.old.rid);
+                assert_eq!(time("2022-11-01T15:00:00+0800"), conflict.old.start);
+                assert_eq!(rsvp.id, conflict.conflicting_id);
+                assert_eq!("tosei", conflict.conflicting_user_id);
+            }
+            _ => panic!("expect conflict, got {err:?}"),
+        }
+
+        assert_eq!(
+            Error::NotFound,
+            client.get_reservation(10000).await.unwrap_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn client_filter_should_fetch_all_pages() {
+        let (_config, client) = start_server().await;
+        let start = time("2023-01-01T10:00:00+0800");
+        for i in 0..12 {
+            let start = start + chrono::Duration::days(i);
+            let end = start + chrono::Duration::hours(1);
+            client
+                .create_order("tosei", "room-1", start, end, "")
+                .await
+                .unwrap();
+        }
+
+        let filter = ReservationFilterBuilder::default()
+            .user_id("tosei")
+            .status(ReservationStatus::Pending)
+            .page_size(10)
+            .build()
+            .unwrap();
+        let rsvps: Vec<Reservation> = client
+            .filter_reservations(filter)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(12, rsvps.len());
+        assert!(rsvps.windows(2).all(|w| w[0].id < w[1].id));
+
+        let query = RsvpQuery {
+            user_id: "tosei".to_string(),
+            ..RsvpQuery::new(start, start + chrono::Duration::days(2))
+        };
+        let rsvps: Vec<Reservation> = client
+            .query_reservations(query)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(2, rsvps.len());
+    }
+}
@@ -0,0 +1,151 @@
+use futures::Future;
+use std::time::Duration;
+use tonic::{Code, Status};
+use tracing::debug;
+
+/// exponential backoff for the failed rpc
+///
+/// the rpc which is not idempotent is only retried when it is rejected by the
+/// rate limiter, because it is never executed by the server in that case. The creation
+/// and the confirmation send a request id which the server dedupes on, so they are
+/// retried as the idempotent ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// call `f` until it succeeds, the error is not retryable or the retries are used up
+    pub async fn run<T, F, Fut>(&self, idempotent: bool, mut f: F) -> Result<T, Status>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Ok(v) => return Ok(v),
+                Err(status) if attempt < self.max_retries && should_retry(&status, idempotent) => {
+                    let delay = retry_after(&status).unwrap_or_else(|| self.backoff(attempt));
+                    debug!("retry in {delay:?} after error: {status}");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(status) => return Err(status),
+            }
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+fn should_retry(status: &Status, idempotent: bool) -> bool {
+    match status.code() {
+        // rejected by the rate limiter before the rpc is executed, an exceeded quota of the
+        // user has no retry-after and would not be freed by retrying
+        Code::ResourceExhausted => retry_after(status).is_some(),
+        Code::Unavailable | Code::DeadlineExceeded | Code::Aborted => idempotent,
+        _ => false,
+    }
+}
+
+/// the retry-after metadata in seconds which is set by the rate limiter
+fn retry_after(status: &Status) -> Option<Duration> {
+    let seconds = status.metadata().get("retry-after")?.to_str().ok()?;
+    seconds.parse().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        }
+    }
+
+    async fn fail_twice(calls: &AtomicU32, code: Code) -> Result<u32, Status> {
+        let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if n <= 2 {
+            let mut status = Status::new(code, "try again");
+            if code == Code::ResourceExhausted {
+                status.metadata_mut().insert("retry-after", 0.into());
+            }
+            Err(status)
+        } else {
+            Ok(n)
+        }
+    }
+
+    #[tokio::test]
+    async fn idempotent_rpc_should_be_retried() {
+        let calls = AtomicU32::new(0);
+        let ret = policy()
+            .run(true, || fail_twice(&calls, Code::Unavailable))
+            .await;
+        assert_eq!(3, ret.unwrap());
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_rpc_should_not_be_retried_when_unavailable() {
+        let calls = AtomicU32::new(0);
+        let ret = policy()
+            .run(false, || fail_twice(&calls, Code::Unavailable))
+            .await;
+        assert_eq!(Code::Unavailable, ret.unwrap_err().code());
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+
+        // rate limited request is never executed, so it is safe to retry
+        let calls = AtomicU32::new(0);
+        let ret = policy()
+            .run(false, || fail_twice(&calls, Code::ResourceExhausted))
+            .await;
+        assert_eq!(3, ret.unwrap());
+
+        // the exceeded quota has no retry-after
+        let calls = AtomicU32::new(0);
+        let ret = policy()
+            .run(true, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(Status::resource_exhausted("quota exceeded"))
+            })
+            .await;
+        assert_eq!(Code::ResourceExhausted, ret.unwrap_err().code());
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn backoff_should_be_capped() {
+        let policy = policy();
+        assert_eq!(Duration::from_millis(1), policy.backoff(0));
+        assert_eq!(Duration::from_millis(4), policy.backoff(2));
+        assert_eq!(Duration::from_millis(10), policy.backoff(10));
+    }
+}
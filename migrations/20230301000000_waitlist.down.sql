@@ -0,0 +1,11 @@
+DROP TRIGGER waitlist_trigger ON rsvt.waitlist;
+DROP FUNCTION rsvt.waitlist_trigger();
+DROP TABLE rsvt.waitlist_changes CASCADE;
+DROP TABLE rsvt.waitlist CASCADE;
+DROP TYPE rsvt.waitlist_status;
+
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    rperiod WITH &&
+);
@@ -0,0 +1,50 @@
+-- cancelled reservations (stored as blocked) no longer occupy the resource
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    rperiod WITH &&
+) WHERE (rstatus <> 'blocked');
+
+CREATE TYPE rsvt.waitlist_status AS ENUM ('unknown', 'waiting', 'notified', 'fulfilled', 'left', 'expired');
+
+-- users queue for a resource which is fully booked in the desired window
+CREATE TABLE rsvt.waitlist (
+    id bigserial NOT NULL,
+    user_id VARCHAR(64) NOT NULL,
+    resource_id VARCHAR(64) NOT NULL,
+    rperiod TSTZRANGE NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    wstatus rsvt.waitlist_status NOT NULL DEFAULT 'waiting',
+    -- the pending reservation which the entry is converted into
+    reservation_id BIGINT,
+
+    CONSTRAINT waitlist_pkey PRIMARY KEY (id)
+);
+
+CREATE INDEX waitlist_resource_id_idx ON rsvt.waitlist (resource_id) WHERE wstatus = 'waiting';
+
+-- waitlist change queue
+CREATE TABLE rsvt.waitlist_changes (
+    id SERIAL NOT NULL,
+    waitlist_id BIGINT NOT NULL,
+    op rsvt.reservation_update_type NOT NULL,
+    CONSTRAINT waitlist_changes_pkey PRIMARY KEY (id)
+);
+
+CREATE OR REPLACE FUNCTION rsvt.waitlist_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.waitlist_changes (waitlist_id, op) VALUES (NEW.id, 'create');
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.wstatus <> NEW.wstatus THEN
+            INSERT INTO rsvt.waitlist_changes (waitlist_id, op) VALUES (NEW.id, 'update');
+        END IF;
+    END IF;
+    NOTIFY waitlist_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER waitlist_trigger
+    AFTER INSERT OR UPDATE ON rsvt.waitlist
+    FOR EACH ROW EXECUTE PROCEDURE rsvt.waitlist_trigger();
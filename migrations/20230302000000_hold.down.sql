@@ -0,0 +1,4 @@
+DROP TABLE rsvt.holds CASCADE;
+
+-- the enum value can not be dropped, release the holds instead
+UPDATE rsvt.reservations SET rstatus = 'blocked' WHERE rstatus = 'held';
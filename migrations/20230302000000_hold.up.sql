@@ -0,0 +1,14 @@
+-- a held reservation locks the window for a checkout flow until the hold expires
+ALTER TYPE rsvt.reservation_status ADD VALUE 'held';
+
+-- expiry of the held reservations, removed when the hold is promoted
+CREATE TABLE rsvt.holds (
+    reservation_id BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+
+    CONSTRAINT holds_pkey PRIMARY KEY (reservation_id),
+    CONSTRAINT holds_reservation_id_fkey FOREIGN KEY (reservation_id)
+        REFERENCES rsvt.reservations (id) ON DELETE CASCADE
+);
+
+CREATE INDEX holds_expires_at_idx ON rsvt.holds (expires_at);
@@ -0,0 +1,7 @@
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    rperiod WITH &&
+) WHERE (rstatus <> 'blocked');
+
+ALTER TABLE rsvt.reservations DROP COLUMN bperiod;
@@ -0,0 +1,11 @@
+-- the booked period padded with the buffers of the resource, e.g. the cleaning time of a room
+ALTER TABLE rsvt.reservations ADD COLUMN bperiod TSTZRANGE;
+UPDATE rsvt.reservations SET bperiod = rperiod;
+ALTER TABLE rsvt.reservations ALTER COLUMN bperiod SET NOT NULL;
+
+-- the padded periods must not overlap, the visible rperiod stays unpadded
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    bperiod WITH &&
+) WHERE (rstatus <> 'blocked');
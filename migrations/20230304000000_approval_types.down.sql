@@ -0,0 +1 @@
+-- the enum values can not be dropped, the approval migration moves the rows off them
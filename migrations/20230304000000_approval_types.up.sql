@@ -0,0 +1,4 @@
+-- a rejected reservation is terminal and frees its window
+ALTER TYPE rsvt.reservation_status ADD VALUE 'rejected';
+-- the reservation waits for the approvers of the current step
+ALTER TYPE rsvt.reservation_update_type ADD VALUE 'approval_requested';
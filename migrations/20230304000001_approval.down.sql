@@ -0,0 +1,11 @@
+UPDATE rsvt.reservations SET rstatus = 'blocked' WHERE rstatus = 'rejected';
+DELETE FROM rsvt.reservation_changes WHERE op = 'approval_requested';
+
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    bperiod WITH &&
+) WHERE (rstatus <> 'blocked');
+
+DROP TABLE rsvt.approvals CASCADE;
+DROP TYPE rsvt.approval_decision;
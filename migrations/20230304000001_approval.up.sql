@@ -0,0 +1,24 @@
+CREATE TYPE rsvt.approval_decision AS ENUM ('approved', 'rejected');
+
+-- the decisions of the approvers, by the step of the approval policy of the resource
+CREATE TABLE rsvt.approvals (
+    id BIGSERIAL NOT NULL,
+    reservation_id BIGINT NOT NULL,
+    step INT NOT NULL,
+    approver VARCHAR(64) NOT NULL,
+    decision rsvt.approval_decision NOT NULL,
+    reason TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT approvals_pkey PRIMARY KEY (id),
+    CONSTRAINT approvals_reservation_id_fkey FOREIGN KEY (reservation_id)
+        REFERENCES rsvt.reservations (id) ON DELETE CASCADE,
+    CONSTRAINT approvals_step_approver_key UNIQUE (reservation_id, step, approver)
+);
+
+-- the rejected reservations do not hold their windows
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    bperiod WITH &&
+) WHERE (rstatus NOT IN ('blocked', 'rejected'));
@@ -0,0 +1,3 @@
+DROP TRIGGER reservations_audit ON rsvt.reservations;
+DROP FUNCTION rsvt.reservations_audit();
+DROP TABLE rsvt.reservation_audit CASCADE;
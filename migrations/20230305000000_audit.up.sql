@@ -0,0 +1,44 @@
+-- every mutation of the reservations with the actor and the snapshots of the row
+CREATE TABLE rsvt.reservation_audit (
+    id BIGSERIAL NOT NULL,
+    -- not a foreign key, the history is kept after the reservation is deleted
+    reservation_id BIGINT NOT NULL,
+    op rsvt.reservation_update_type NOT NULL,
+    actor VARCHAR(64) NOT NULL,
+    request_id VARCHAR(64) NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    before JSONB,
+    after JSONB,
+
+    CONSTRAINT reservation_audit_pkey PRIMARY KEY (id)
+);
+
+CREATE INDEX reservation_audit_reservation_id_idx ON rsvt.reservation_audit (reservation_id, id);
+CREATE INDEX reservation_audit_actor_changed_at_idx ON rsvt.reservation_audit (actor, changed_at);
+
+-- the actor and the request id are set by the manager with set_config in the transaction,
+-- the changes made without them are recorded as the system ones
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, after)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW));
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before, after)
+            VALUES (NEW.id, 'update', v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW));
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reservations_audit
+    AFTER INSERT OR UPDATE OR DELETE ON rsvt.reservations
+    FOR EACH ROW EXECUTE PROCEDURE rsvt.reservations_audit();
@@ -0,0 +1,140 @@
+CREATE OR REPLACE FUNCTION rsvt.reservations_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'create');
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.rstatus <> NEW.rstatus THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'update');
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (OLD.id, 'delete');
+    END IF;
+    NOTIFY reservation_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, after)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW));
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before, after)
+            VALUES (NEW.id, 'update', v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW));
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.query(
+    uid text,
+    rid text,
+    during TSTZRANGE,
+    status rsvt.reservation_status,
+    page integer default 1,
+    is_desc bool default false,
+    page_size integer default 10
+) RETURNS TABLE (LIKE rsvt.reservations) AS $$ -- RETURNS TABLE (LIKE rsvt.reservations) 返回表
+DECLARE
+    _sql text;
+    BEGIN
+        -- if page_size is not between 10 and 100, set it to 10
+        IF page_size < 10 OR page_size > 100 THEN
+            page_size := 10;
+        END IF;
+        IF page < 1 THEN
+            page := 1;
+        END IF;
+        -- format the qurey based on parameters
+        _sql := format(
+            'select * from rsvt.reservations where %L @> rperiod and rstatus = %L and %s order by lower(rperiod) %s
+            limit %s offset %s',
+            during,
+            status,
+            CASE
+                WHEN uid IS NULL AND rid IS NULL THEN 'TRUE'
+                WHEN uid IS NULL THEN 'resource_id = ' || quote_literal(rid)
+                WHEN rid IS NULL THEN 'user_id = ' || quote_literal(uid)
+                ELSE 'resource_id =' || quote_literal(rid) || ' AND user_id = ' || quote_literal(uid)
+            END,
+            CASE
+                WHEN is_desc THEN 'DESC'
+                ELSE 'ASC'
+            END,
+            page_size,
+            (page - 1) * page_size
+        );
+
+        -- log the sql
+        RAISE NOTICE '%', _sql;
+
+        -- execute the query
+        RETURN QUERY EXECUTE _sql;
+
+    END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.filter(
+    uid text,
+    rid text,
+    status rsvt.reservation_status,
+    cursor bigint default null,
+    is_desc bool default false,
+    page_size bigint default 10
+) RETURNS TABLE (LIKE rsvt.reservations) AS $$ -- RETURNS TABLE (LIKE rsvt.reservations) 返回表
+DECLARE
+    _sql text;
+    BEGIN
+        -- if cursor is null, set it to 0 if is_desc is false, or to max int if is_desc is true
+        IF cursor IS NULL or cursor < 0 THEN
+            IF is_desc THEN
+                cursor := 2147483647;
+            ELSE
+                cursor := 0;
+            END IF;
+        END IF;
+        -- if page_size is not between 10 and 100, set it to 10
+        IF page_size < 10 OR page_size > 100 THEN
+            page_size := 10;
+        END IF;
+        -- format the qurey based on parameters
+        _sql := format(
+            'select * from rsvt.reservations where %s and rstatus = %L and %s order by id %s limit %L::integer',
+            CASE
+                WHEN is_desc THEN 'id < ' || cursor
+                ELSE 'id > ' || cursor
+            END,
+            status,
+            CASE
+                WHEN uid IS NULL AND rid IS NULL THEN 'TRUE'
+                WHEN uid IS NULL THEN 'resource_id = ' || quote_literal(rid)
+                WHEN rid IS NULL THEN 'user_id = ' || quote_literal(uid)
+                ELSE 'resource_id =' || quote_literal(rid) || ' AND user_id = ' || quote_literal(uid)
+            END,
+            CASE
+                WHEN is_desc THEN 'DESC'
+                ELSE 'ASC'
+            END,
+            page_size
+        );
+
+        -- log the sql
+        RAISE NOTICE '%', _sql;
+
+        -- execute the query
+        RETURN QUERY EXECUTE _sql;
+
+    END;
+$$ LANGUAGE plpgsql;
+
+DROP TABLE rsvt.reservations_archive;
+ALTER TABLE rsvt.reservations DROP COLUMN deleted_at;
@@ -0,0 +1,154 @@
+-- a soft deleted reservation is hidden from get, query and filter but kept for the audit
+ALTER TABLE rsvt.reservations ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- the reservations moved out by the retention job, with the time they are archived
+CREATE TABLE rsvt.reservations_archive (LIKE rsvt.reservations);
+ALTER TABLE rsvt.reservations_archive ADD COLUMN archived_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE rsvt.reservations_archive ADD CONSTRAINT reservations_archive_pkey PRIMARY KEY (id);
+
+-- the soft delete is sent to the listeners as a delete
+CREATE OR REPLACE FUNCTION rsvt.reservations_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'create');
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'delete');
+        ELSIF OLD.rstatus <> NEW.rstatus THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'update');
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (OLD.id, 'delete');
+    END IF;
+    NOTIFY reservation_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, after)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW));
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before, after)
+            VALUES (
+                NEW.id,
+                CASE WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                    THEN 'delete' ELSE 'update' END::rsvt.reservation_update_type,
+                v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW)
+            );
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- the soft deleted reservations are not queried
+CREATE OR REPLACE FUNCTION rsvt.query(
+    uid text,
+    rid text,
+    during TSTZRANGE,
+    status rsvt.reservation_status,
+    page integer default 1,
+    is_desc bool default false,
+    page_size integer default 10
+) RETURNS TABLE (LIKE rsvt.reservations) AS $$ -- RETURNS TABLE (LIKE rsvt.reservations) 返回表
+DECLARE
+    _sql text;
+    BEGIN
+        -- if page_size is not between 10 and 100, set it to 10
+        IF page_size < 10 OR page_size > 100 THEN
+            page_size := 10;
+        END IF;
+        IF page < 1 THEN
+            page := 1;
+        END IF;
+        -- format the qurey based on parameters
+        _sql := format(
+            'select * from rsvt.reservations where deleted_at IS NULL and %L @> rperiod and rstatus = %L and %s order by lower(rperiod) %s
+            limit %s offset %s',
+            during,
+            status,
+            CASE
+                WHEN uid IS NULL AND rid IS NULL THEN 'TRUE'
+                WHEN uid IS NULL THEN 'resource_id = ' || quote_literal(rid)
+                WHEN rid IS NULL THEN 'user_id = ' || quote_literal(uid)
+                ELSE 'resource_id =' || quote_literal(rid) || ' AND user_id = ' || quote_literal(uid)
+            END,
+            CASE
+                WHEN is_desc THEN 'DESC'
+                ELSE 'ASC'
+            END,
+            page_size,
+            (page - 1) * page_size
+        );
+
+        -- log the sql
+        RAISE NOTICE '%', _sql;
+
+        -- execute the query
+        RETURN QUERY EXECUTE _sql;
+
+    END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.filter(
+    uid text,
+    rid text,
+    status rsvt.reservation_status,
+    cursor bigint default null,
+    is_desc bool default false,
+    page_size bigint default 10
+) RETURNS TABLE (LIKE rsvt.reservations) AS $$ -- RETURNS TABLE (LIKE rsvt.reservations) 返回表
+DECLARE
+    _sql text;
+    BEGIN
+        -- if cursor is null, set it to 0 if is_desc is false, or to max int if is_desc is true
+        IF cursor IS NULL or cursor < 0 THEN
+            IF is_desc THEN
+                cursor := 2147483647;
+            ELSE
+                cursor := 0;
+            END IF;
+        END IF;
+        -- if page_size is not between 10 and 100, set it to 10
+        IF page_size < 10 OR page_size > 100 THEN
+            page_size := 10;
+        END IF;
+        -- format the qurey based on parameters
+        _sql := format(
+            'select * from rsvt.reservations where deleted_at IS NULL and %s and rstatus = %L and %s order by id %s limit %L::integer',
+            CASE
+                WHEN is_desc THEN 'id < ' || cursor
+                ELSE 'id > ' || cursor
+            END,
+            status,
+            CASE
+                WHEN uid IS NULL AND rid IS NULL THEN 'TRUE'
+                WHEN uid IS NULL THEN 'resource_id = ' || quote_literal(rid)
+                WHEN rid IS NULL THEN 'user_id = ' || quote_literal(uid)
+                ELSE 'resource_id =' || quote_literal(rid) || ' AND user_id = ' || quote_literal(uid)
+            END,
+            CASE
+                WHEN is_desc THEN 'DESC'
+                ELSE 'ASC'
+            END,
+            page_size
+        );
+
+        -- log the sql
+        RAISE NOTICE '%', _sql;
+
+        -- execute the query
+        RETURN QUERY EXECUTE _sql;
+
+    END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,3 @@
+DROP TABLE rsvt.webhook_dead_letters;
+DROP TABLE rsvt.webhook_deliveries;
+DROP TABLE rsvt.webhooks;
@@ -0,0 +1,49 @@
+-- the subscribers of the changes, the changes after the cursor are not fanned out yet
+CREATE TABLE rsvt.webhooks (
+    id BIGSERIAL NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    -- the update types to deliver, all if empty
+    events INT[] NOT NULL DEFAULT '{}',
+    -- the resource id to deliver, an id ending with * matches the prefix, all if empty
+    resource_id VARCHAR(64) NOT NULL DEFAULT '',
+    cursor BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT webhooks_pkey PRIMARY KEY (id)
+);
+
+-- the outbox of the webhooks, a delivery is removed when the subscriber accepts it
+CREATE TABLE rsvt.webhook_deliveries (
+    id BIGSERIAL NOT NULL,
+    webhook_id BIGINT NOT NULL,
+    change_id BIGINT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_error TEXT NOT NULL DEFAULT '',
+
+    CONSTRAINT webhook_deliveries_pkey PRIMARY KEY (id),
+    CONSTRAINT webhook_deliveries_webhook_id_fkey FOREIGN KEY (webhook_id)
+        REFERENCES rsvt.webhooks (id) ON DELETE CASCADE,
+    CONSTRAINT webhook_deliveries_change_key UNIQUE (webhook_id, change_id)
+);
+
+CREATE INDEX webhook_deliveries_next_attempt_at_idx ON rsvt.webhook_deliveries (next_attempt_at);
+
+-- the deliveries which failed every attempt, moved back to the outbox by a replay
+CREATE TABLE rsvt.webhook_dead_letters (
+    id BIGSERIAL NOT NULL,
+    webhook_id BIGINT NOT NULL,
+    change_id BIGINT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INT NOT NULL,
+    last_error TEXT NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT webhook_dead_letters_pkey PRIMARY KEY (id),
+    CONSTRAINT webhook_dead_letters_webhook_id_fkey FOREIGN KEY (webhook_id)
+        REFERENCES rsvt.webhooks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX webhook_dead_letters_webhook_id_idx ON rsvt.webhook_dead_letters (webhook_id);
@@ -0,0 +1,100 @@
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'reservations', 'reservations_archive', 'reservation_changes', 'reservation_audit',
+        'waitlist', 'waitlist_changes', 'holds', 'approvals',
+        'webhooks', 'webhook_deliveries', 'webhook_dead_letters'
+    ] LOOP
+        EXECUTE format('DROP POLICY %I ON rsvt.%I', t || '_tenant', t);
+        EXECUTE format('ALTER TABLE rsvt.%I DISABLE ROW LEVEL SECURITY', t);
+    END LOOP;
+END $$;
+
+CREATE OR REPLACE FUNCTION rsvt.waitlist_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.waitlist_changes (waitlist_id, op) VALUES (NEW.id, 'create');
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.wstatus <> NEW.wstatus THEN
+            INSERT INTO rsvt.waitlist_changes (waitlist_id, op) VALUES (NEW.id, 'update');
+        END IF;
+    END IF;
+    NOTIFY waitlist_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, after)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW));
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before, after)
+            VALUES (
+                NEW.id,
+                CASE WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                    THEN 'delete' ELSE 'update' END::rsvt.reservation_update_type,
+                v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW)
+            );
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD));
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.reservations_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'create');
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'delete');
+        ELSIF OLD.rstatus <> NEW.rstatus THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (NEW.id, 'update');
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op) VALUES (OLD.id, 'delete');
+    END IF;
+    NOTIFY reservation_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP INDEX rsvt.reservations_resource_id_idx;
+CREATE INDEX reservations_resource_id_idx ON rsvt.reservations (resource_id);
+
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    resource_id WITH =,
+    bperiod WITH &&
+) WHERE (rstatus NOT IN ('blocked', 'rejected'));
+
+ALTER TABLE rsvt.webhook_dead_letters DROP COLUMN tenant_id;
+ALTER TABLE rsvt.webhook_deliveries DROP COLUMN tenant_id;
+ALTER TABLE rsvt.webhooks DROP COLUMN tenant_id;
+ALTER TABLE rsvt.approvals DROP COLUMN tenant_id;
+ALTER TABLE rsvt.holds DROP COLUMN tenant_id;
+ALTER TABLE rsvt.waitlist_changes DROP COLUMN tenant_id;
+ALTER TABLE rsvt.waitlist DROP COLUMN tenant_id;
+ALTER TABLE rsvt.reservation_audit DROP COLUMN tenant_id;
+ALTER TABLE rsvt.reservation_changes DROP COLUMN tenant_id;
+ALTER TABLE rsvt.reservations_archive DROP COLUMN tenant_id;
+ALTER TABLE rsvt.reservations DROP COLUMN tenant_id;
+
+DROP FUNCTION rsvt.current_tenant();
+
+ALTER DEFAULT PRIVILEGES IN SCHEMA rsvt REVOKE USAGE, SELECT ON SEQUENCES FROM rsvt_tenant;
+ALTER DEFAULT PRIVILEGES IN SCHEMA rsvt REVOKE SELECT, INSERT, UPDATE, DELETE ON TABLES FROM rsvt_tenant;
+REVOKE USAGE, SELECT ON ALL SEQUENCES IN SCHEMA rsvt FROM rsvt_tenant;
+REVOKE SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA rsvt FROM rsvt_tenant;
+REVOKE USAGE ON SCHEMA rsvt FROM rsvt_tenant;
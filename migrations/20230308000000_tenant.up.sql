@@ -0,0 +1,134 @@
+-- the manager switches to this role with the tenant of the request, so the row level security
+-- applies to it even if the service logs in as the owner of the tables. The role is shared by
+-- the databases of the cluster, it is not dropped by the down migration
+DO $$
+BEGIN
+    CREATE ROLE rsvt_tenant NOLOGIN;
+EXCEPTION WHEN duplicate_object OR unique_violation THEN
+    NULL;
+END $$;
+
+DO $$
+BEGIN
+    EXECUTE format('GRANT rsvt_tenant TO %I', current_user);
+EXCEPTION WHEN duplicate_object OR unique_violation THEN
+    NULL;
+END $$;
+
+GRANT USAGE ON SCHEMA rsvt TO rsvt_tenant;
+GRANT SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA rsvt TO rsvt_tenant;
+GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA rsvt TO rsvt_tenant;
+ALTER DEFAULT PRIVILEGES IN SCHEMA rsvt GRANT SELECT, INSERT, UPDATE, DELETE ON TABLES TO rsvt_tenant;
+ALTER DEFAULT PRIVILEGES IN SCHEMA rsvt GRANT USAGE, SELECT ON SEQUENCES TO rsvt_tenant;
+
+-- the tenant set by the manager in the transaction, the existing rows belong to the default one
+CREATE OR REPLACE FUNCTION rsvt.current_tenant() RETURNS TEXT AS $$
+    SELECT COALESCE(NULLIF(current_setting('rsvt.tenant_id', true), ''), 'default')
+$$ LANGUAGE sql STABLE;
+
+ALTER TABLE rsvt.reservations ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.reservations_archive ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT 'default';
+ALTER TABLE rsvt.reservation_changes ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.reservation_audit ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.waitlist ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.waitlist_changes ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.holds ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.approvals ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.webhooks ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.webhook_deliveries ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+ALTER TABLE rsvt.webhook_dead_letters ADD COLUMN tenant_id VARCHAR(64) NOT NULL DEFAULT rsvt.current_tenant();
+
+-- the same resource id of different tenants is a different resource
+ALTER TABLE rsvt.reservations DROP CONSTRAINT reservations_conflict;
+ALTER TABLE rsvt.reservations ADD CONSTRAINT reservations_conflict EXCLUDE USING gist (
+    tenant_id WITH =,
+    resource_id WITH =,
+    bperiod WITH &&
+) WHERE (rstatus NOT IN ('blocked', 'rejected'));
+
+DROP INDEX rsvt.reservations_resource_id_idx;
+CREATE INDEX reservations_resource_id_idx ON rsvt.reservations (tenant_id, resource_id);
+
+-- the derived rows take the tenant of the reservation, the background jobs set no tenant
+CREATE OR REPLACE FUNCTION rsvt.reservations_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+        VALUES (NEW.id, 'create', NEW.tenant_id);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+            VALUES (NEW.id, 'delete', NEW.tenant_id);
+        ELSIF OLD.rstatus <> NEW.rstatus THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+            VALUES (NEW.id, 'update', NEW.tenant_id);
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+        VALUES (OLD.id, 'delete', OLD.tenant_id);
+    END IF;
+    NOTIFY reservation_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, after, tenant_id)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW), NEW.tenant_id);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit
+                (reservation_id, op, actor, request_id, before, after, tenant_id)
+            VALUES (
+                NEW.id,
+                CASE WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                    THEN 'delete' ELSE 'update' END::rsvt.reservation_update_type,
+                v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW), NEW.tenant_id
+            );
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before, tenant_id)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD), OLD.tenant_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION rsvt.waitlist_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.waitlist_changes (waitlist_id, op, tenant_id)
+        VALUES (NEW.id, 'create', NEW.tenant_id);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.wstatus <> NEW.wstatus THEN
+            INSERT INTO rsvt.waitlist_changes (waitlist_id, op, tenant_id)
+            VALUES (NEW.id, 'update', NEW.tenant_id);
+        END IF;
+    END IF;
+    NOTIFY waitlist_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- a tenant sees and writes its own rows only, the owner is not restricted
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'reservations', 'reservations_archive', 'reservation_changes', 'reservation_audit',
+        'waitlist', 'waitlist_changes', 'holds', 'approvals',
+        'webhooks', 'webhook_deliveries', 'webhook_dead_letters'
+    ] LOOP
+        EXECUTE format('ALTER TABLE rsvt.%I ENABLE ROW LEVEL SECURITY', t);
+        EXECUTE format(
+            'CREATE POLICY %I ON rsvt.%I USING (tenant_id = rsvt.current_tenant())',
+            t || '_tenant', t
+        );
+    END LOOP;
+END $$;
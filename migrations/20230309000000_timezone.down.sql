@@ -0,0 +1,2 @@
+ALTER TABLE rsvt.reservations_archive DROP COLUMN timezone;
+ALTER TABLE rsvt.reservations DROP COLUMN timezone;
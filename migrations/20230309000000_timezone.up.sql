@@ -0,0 +1,3 @@
+-- the IANA timezone of a reservation, its local times are shown in it
+ALTER TABLE rsvt.reservations ADD COLUMN timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
+ALTER TABLE rsvt.reservations_archive ADD COLUMN timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
@@ -0,0 +1 @@
+-- the enum values can not be dropped, the check-in migration moves the rows off them
@@ -0,0 +1,2 @@
+-- a confirmed reservation which is not checked in within the grace period
+ALTER TYPE rsvt.reservation_status ADD VALUE 'no_show';
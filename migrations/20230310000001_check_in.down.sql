@@ -0,0 +1,10 @@
+DROP INDEX rsvt.reservations_no_show_idx;
+DROP INDEX rsvt.reservations_check_in_idx;
+
+UPDATE rsvt.reservations SET rstatus = 'blocked' WHERE rstatus = 'no_show';
+UPDATE rsvt.reservations_archive SET rstatus = 'blocked' WHERE rstatus = 'no_show';
+
+ALTER TABLE rsvt.reservations_archive DROP COLUMN checked_out_at;
+ALTER TABLE rsvt.reservations_archive DROP COLUMN checked_in_at;
+ALTER TABLE rsvt.reservations DROP COLUMN checked_out_at;
+ALTER TABLE rsvt.reservations DROP COLUMN checked_in_at;
@@ -0,0 +1,12 @@
+-- when the resource is actually taken and left
+ALTER TABLE rsvt.reservations ADD COLUMN checked_in_at TIMESTAMPTZ;
+ALTER TABLE rsvt.reservations ADD COLUMN checked_out_at TIMESTAMPTZ;
+ALTER TABLE rsvt.reservations_archive ADD COLUMN checked_in_at TIMESTAMPTZ;
+ALTER TABLE rsvt.reservations_archive ADD COLUMN checked_out_at TIMESTAMPTZ;
+
+-- the confirmed reservations swept by the no-show job
+CREATE INDEX reservations_check_in_idx ON rsvt.reservations (lower(rperiod))
+    WHERE rstatus = 'confirmed' AND checked_in_at IS NULL;
+-- the no-shows counted by user
+CREATE INDEX reservations_no_show_idx ON rsvt.reservations (user_id)
+    WHERE rstatus = 'no_show';
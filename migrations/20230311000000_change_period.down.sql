@@ -0,0 +1,21 @@
+CREATE OR REPLACE FUNCTION rsvt.reservations_trigger() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+        VALUES (NEW.id, 'create', NEW.tenant_id);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+            VALUES (NEW.id, 'delete', NEW.tenant_id);
+        ELSIF OLD.rstatus <> NEW.rstatus THEN
+            INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+            VALUES (NEW.id, 'update', NEW.tenant_id);
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_changes (reservation_id, op, tenant_id)
+        VALUES (OLD.id, 'delete', OLD.tenant_id);
+    END IF;
+    NOTIFY reservation_update;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
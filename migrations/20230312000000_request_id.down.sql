@@ -0,0 +1 @@
+DROP INDEX rsvt.reservation_audit_request_id_idx;
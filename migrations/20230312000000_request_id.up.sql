@@ -0,0 +1,3 @@
+-- the retried requests are found by their request id, the requests without it are skipped
+CREATE INDEX reservation_audit_request_id_idx ON rsvt.reservation_audit (request_id)
+    WHERE request_id <> '';
@@ -0,0 +1,3 @@
+ALTER TABLE rsvt.webhooks DROP COLUMN cursor_txid;
+DROP INDEX rsvt.reservation_changes_txid_id_idx;
+ALTER TABLE rsvt.reservation_changes DROP COLUMN txid;
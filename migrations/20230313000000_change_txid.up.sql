@@ -0,0 +1,8 @@
+-- the transaction of a change, a change is read once its transaction and every older one
+-- ended, so the change of a transaction which commits after a newer one is not skipped
+ALTER TABLE rsvt.reservation_changes ADD COLUMN txid xid8 NOT NULL DEFAULT pg_current_xact_id();
+CREATE INDEX reservation_changes_txid_id_idx ON rsvt.reservation_changes (txid, id);
+
+-- the webhooks are fanned out the changes after the cursor of (txid, change id), the changes
+-- before carry the transaction of the migration
+ALTER TABLE rsvt.webhooks ADD COLUMN cursor_txid xid8 NOT NULL DEFAULT pg_current_xact_id();
@@ -0,0 +1,2 @@
+DROP INDEX rsvt.waitlist_changes_txid_id_idx;
+ALTER TABLE rsvt.waitlist_changes DROP COLUMN txid;
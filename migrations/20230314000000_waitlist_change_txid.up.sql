@@ -0,0 +1,3 @@
+-- the transaction of a waitlist change, read once it and every older transaction ended
+ALTER TABLE rsvt.waitlist_changes ADD COLUMN txid xid8 NOT NULL DEFAULT pg_current_xact_id();
+CREATE INDEX waitlist_changes_txid_id_idx ON rsvt.waitlist_changes (txid, id);
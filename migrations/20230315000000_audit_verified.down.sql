@@ -0,0 +1,28 @@
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, after, tenant_id)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW), NEW.tenant_id);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit
+                (reservation_id, op, actor, request_id, before, after, tenant_id)
+            VALUES (
+                NEW.id,
+                CASE WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                    THEN 'delete' ELSE 'update' END::rsvt.reservation_update_type,
+                v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW), NEW.tenant_id
+            );
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit (reservation_id, op, actor, request_id, before, tenant_id)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD), OLD.tenant_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE rsvt.reservation_audit DROP COLUMN verified;
@@ -0,0 +1,32 @@
+-- the retried requests of a verified user are not matched by the user id of an unverified client
+ALTER TABLE rsvt.reservation_audit ADD COLUMN verified BOOLEAN NOT NULL DEFAULT false;
+
+CREATE OR REPLACE FUNCTION rsvt.reservations_audit() RETURNS TRIGGER AS $$
+DECLARE
+    v_actor TEXT := COALESCE(NULLIF(current_setting('rsvt.actor', true), ''), 'system');
+    v_request_id TEXT := COALESCE(current_setting('rsvt.request_id', true), '');
+    v_verified BOOLEAN := COALESCE(NULLIF(current_setting('rsvt.verified', true), ''), 'false');
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO rsvt.reservation_audit
+            (reservation_id, op, actor, request_id, after, tenant_id, verified)
+        VALUES (NEW.id, 'create', v_actor, v_request_id, to_jsonb(NEW), NEW.tenant_id, v_verified);
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF OLD IS DISTINCT FROM NEW THEN
+            INSERT INTO rsvt.reservation_audit
+                (reservation_id, op, actor, request_id, before, after, tenant_id, verified)
+            VALUES (
+                NEW.id,
+                CASE WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                    THEN 'delete' ELSE 'update' END::rsvt.reservation_update_type,
+                v_actor, v_request_id, to_jsonb(OLD), to_jsonb(NEW), NEW.tenant_id, v_verified
+            );
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        INSERT INTO rsvt.reservation_audit
+            (reservation_id, op, actor, request_id, before, tenant_id, verified)
+        VALUES (OLD.id, 'delete', v_actor, v_request_id, to_jsonb(OLD), OLD.tenant_id, v_verified);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
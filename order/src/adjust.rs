@@ -0,0 +1,288 @@
+use crate::{
+    manager::{find_reservation, sql_span},
+    quota::check_quota,
+    Adjust, OrderManager, ReservationId,
+};
+use abi::{
+    convert_to_timestamp, convert_to_utc_time, BookingRules, Error, QuotaConfig,
+    ReservationConflict, ReservationConflictInfo, ReservationStatus,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{postgres::types::PgRange, Connection, PgConnection};
+use tracing::Instrument;
+
+#[async_trait]
+impl Adjust for OrderManager {
+    async fn extend(
+        &self,
+        id: ReservationId,
+        end: DateTime<Utc>,
+    ) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        let rsvp = lock_active(&mut tx, id).await?;
+        let now = Utc::now();
+        let current_end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+        if current_end <= now {
+            return Err(Error::InvalidChange(format!("reservation {id} is ended")));
+        }
+        if end <= current_end {
+            return Err(Error::InvalidTime);
+        }
+
+        let mut wanted = rsvp.clone();
+        wanted.end_time = Some(convert_to_timestamp(end));
+        // the lead time and the horizon are of the booking, the extension keeps its start
+        let rules = BookingRules {
+            min_lead_time: None,
+            max_horizon: None,
+            ..self.rules().for_resource(&rsvp.resource_id).clone()
+        };
+        wanted.validate_rules(&rules, now)?;
+        // the reservation is counted already, only the added time is checked
+        let quotas = QuotaConfig {
+            max_active: None,
+            ..self.quotas().clone()
+        };
+        let mut added = wanted.clone();
+        added.start_time = rsvp.end_time.clone();
+        check_quota(&mut tx, &quotas, &added).await?;
+
+        let after = Duration::seconds(self.buffers().for_resource(&rsvp.resource_id).after as i64);
+        let sql = "UPDATE rsvt.reservations
+            SET rperiod = tstzrange(lower(rperiod), $2), bperiod = tstzrange(lower(bperiod), $3)
+            WHERE id = $1 RETURNING *";
+        // the savepoint keeps the transaction usable after the exclusion violation
+        let mut savepoint = tx.begin().await?;
+        let ret = sqlx::query_as(sql)
+            .bind(id)
+            .bind(end)
+            .bind(end + after)
+            .fetch_one(&mut savepoint)
+            .instrument(sql_span(sql))
+            .await;
+        let extended = match ret.map_err(Error::from) {
+            Ok(extended) => extended,
+            Err(Error::ConfilictReservation(info)) => {
+                savepoint.rollback().await?;
+                let blocked = (current_end + after)..(end + after);
+                let info = match find_blocking(&mut tx, &wanted, blocked).await? {
+                    Some((mut conflict, blocked_from)) => {
+                        conflict.max_end = Some(blocked_from - after);
+                        ReservationConflictInfo::Parsed(Box::new(conflict))
+                    }
+                    None => info,
+                };
+                let e = Error::ConfilictReservation(info);
+                return Err(self.count_conflict(&rsvp.resource_id, e));
+            }
+            Err(e) => return Err(e),
+        };
+        savepoint.commit().await?;
+        tx.commit().await?;
+        Ok(extended)
+    }
+
+    async fn release_early(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        let rsvp = lock_active(&mut tx, id).await?;
+        let now = Utc::now();
+        let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+        if start >= now {
+            return Err(Error::InvalidChange(format!(
+                "reservation {id} is not started"
+            )));
+        }
+        if end <= now {
+            return Err(Error::InvalidChange(format!("reservation {id} is ended")));
+        }
+
+        // the buffer after the reservation is kept from now on
+        let after = self.buffers().for_resource(&rsvp.resource_id).after;
+        let sql = "UPDATE rsvt.reservations
+            SET rperiod = tstzrange(lower(rperiod), $2),
+                bperiod = tstzrange(lower(bperiod), $2 + $3 * interval '1 second'),
+                checked_out_at = CASE WHEN checked_in_at IS NOT NULL
+                    THEN COALESCE(checked_out_at, $2) END
+            WHERE id = $1 RETURNING *";
+        let released: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .bind(now)
+            .bind(after as f64)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        self.promote_waitlist(&mut tx, &released.resource_id, now..end)
+            .await?;
+        tx.commit().await?;
+        Ok(released)
+    }
+}
+
+/// the pending or confirmed reservation, locked until the change is committed
+async fn lock_active(
+    conn: &mut PgConnection,
+    id: ReservationId,
+) -> Result<abi::Reservation, Error> {
+    let sql = "SELECT * FROM rsvt.reservations WHERE id = $1 AND deleted_at IS NULL FOR UPDATE";
+    let rsvp: abi::Reservation = sqlx::query_as(sql)
+        .bind(id)
+        .fetch_one(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    if rsvp.status != ReservationStatus::Pending as i32
+        && rsvp.status != ReservationStatus::Confirmed as i32
+    {
+        return Err(Error::InvalidStatus(rsvp.status));
+    }
+    Ok(rsvp)
+}
+
+/// the first reservation taking the padded window of the extension, with the start of its
+/// padded period which the extension could reach
+async fn find_blocking(
+    conn: &mut PgConnection,
+    wanted: &abi::Reservation,
+    blocked: std::ops::Range<DateTime<Utc>>,
+) -> Result<Option<(ReservationConflict, DateTime<Utc>)>, Error> {
+    let sql = "SELECT id, lower(bperiod) FROM rsvt.reservations
+        WHERE resource_id = $1 AND id <> $2 AND bperiod && $3
+        AND rstatus NOT IN ('blocked', 'rejected')
+        ORDER BY lower(bperiod) LIMIT 1";
+    let found: Option<(i64, DateTime<Utc>)> = sqlx::query_as(sql)
+        .bind(&wanted.resource_id)
+        .bind(wanted.id)
+        .bind(PgRange::from(blocked))
+        .fetch_optional(&mut *conn)
+        .instrument(sql_span(sql))
+        .await?;
+    let (id, blocked_from) = match found {
+        Some(found) => found,
+        None => return Ok(None),
+    };
+    let existing = find_reservation(conn, id).await?;
+    Ok(Some((
+        ReservationConflict::new(wanted, &existing),
+        blocked_from,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Audit, Order};
+    use abi::{Buffer, BufferConfig, Reservation, ReservationUpdateType};
+
+    /// a confirmed reservation of the window relative to now in minutes
+    async fn confirmed(manager: &OrderManager, uid: &str, start: i64, end: i64) -> Reservation {
+        let now = Utc::now();
+        let rsvp = Reservation::new_pending(
+            uid,
+            "room-1",
+            (now + Duration::minutes(start)).into(),
+            (now + Duration::minutes(end)).into(),
+            "",
+        );
+        let rsvp = manager.create_order(rsvp).await.unwrap();
+        manager.change_status(rsvp.id).await.unwrap()
+    }
+
+    fn minutes_later(rsvp: &Reservation, minutes: i64) -> DateTime<Utc> {
+        convert_to_utc_time(rsvp.end_time.as_ref().unwrap()) + Duration::minutes(minutes)
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn extension_should_be_limited_by_the_next_reservation() {
+        let buffers = BufferConfig {
+            default: Buffer {
+                before: 0,
+                after: 600,
+            },
+            ..Default::default()
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_buffers(buffers);
+        let rsvp = confirmed(&manager, "tosei", 60, 120).await;
+        let next = confirmed(&manager, "alice", 180, 240).await;
+
+        let end = minutes_later(&rsvp, 30);
+        let extended = manager.extend(rsvp.id, end).await.unwrap();
+        assert_eq!(
+            convert_to_timestamp(end).seconds,
+            extended.end_time.unwrap().seconds
+        );
+        assert_eq!(rsvp.start_time, extended.start_time);
+        assert_eq!(
+            Error::InvalidTime,
+            manager.extend(rsvp.id, end).await.unwrap_err()
+        );
+
+        // the buffer of the extended one must end before the next one
+        let err = manager
+            .extend(rsvp.id, minutes_later(&rsvp, 60))
+            .await
+            .unwrap_err();
+        let conflict = match err {
+            Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict)) => conflict,
+            _ => panic!("expect conflict, got {err:?}"),
+        };
+        assert_eq!(next.id, conflict.conflicting_id);
+        let max_end =
+            convert_to_utc_time(next.start_time.as_ref().unwrap()) - Duration::minutes(10);
+        assert_eq!(Some(max_end), conflict.max_end);
+        let extended = manager.extend(rsvp.id, max_end).await.unwrap();
+        assert_eq!(Some(convert_to_timestamp(max_end)), extended.end_time);
+
+        let history = manager.history(rsvp.id).await.unwrap();
+        assert_eq!(4, history.len());
+        assert_eq!(ReservationUpdateType::Update as i32, history[3].op);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn released_reservation_should_free_the_rest_of_its_window() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let mut events = manager.listen_events().await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let rsvp = confirmed(&manager, "tosei", -30, 30).await;
+        let later = confirmed(&manager, "tosei", 60, 120).await;
+
+        let released = manager.release_early(rsvp.id).await.unwrap();
+        let end = convert_to_utc_time(released.end_time.as_ref().unwrap());
+        assert!(end <= Utc::now());
+        let err = manager.release_early(rsvp.id).await.unwrap_err();
+        let reason = format!("reservation {} is ended", rsvp.id);
+        assert_eq!(Error::InvalidChange(reason), err);
+        let err = manager.release_early(later.id).await.unwrap_err();
+        let reason = format!("reservation {} is not started", later.id);
+        assert_eq!(Error::InvalidChange(reason), err);
+
+        // the change of the period is sent to the listeners, after the create and the confirm.
+        // the events carry the reservation as it is read, so only the last one is checked
+        let mut changes = vec![];
+        while changes.len() < 3 {
+            let event = events.recv().await.unwrap().unwrap();
+            let changed = event.reservation.unwrap();
+            if changed.id == rsvp.id {
+                changes.push((event.op, changed.end_time));
+            }
+        }
+        assert_eq!(
+            (ReservationUpdateType::Update as i32, released.end_time),
+            changes.pop().unwrap()
+        );
+
+        let now = Utc::now();
+        let rest = Reservation::new_pending(
+            "alice",
+            "room-1",
+            (now + Duration::minutes(1)).into(),
+            (now + Duration::minutes(30)).into(),
+            "",
+        );
+        assert!(manager.create_order(rest).await.is_ok());
+
+        manager.cancel_reservation(later.id).await.unwrap();
+        let err = manager.extend(later.id, now).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidStatus(_)));
+    }
+}
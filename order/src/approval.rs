@@ -0,0 +1,291 @@
+use crate::{manager::sql_span, Approval, OrderManager, ReservationId};
+use abi::{convert_to_utc_time, ApprovalPolicy, Error, ReservationStatus};
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use tracing::{info, Instrument};
+
+#[async_trait]
+impl Approval for OrderManager {
+    async fn approve(
+        &self,
+        id: ReservationId,
+        approver: &str,
+        reason: &str,
+    ) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        let (rsvp, policy, step) = self.current_step(&mut tx, id, approver).await?;
+        record(&mut tx, id, step, approver, "approved", reason).await?;
+
+        let approved = approved(&mut tx, id).await?;
+        let rsvp = match policy.current_step(&approved) {
+            // the next step is requested, or the approver has approved before
+            Some(next) => {
+                if next != step {
+                    request_approval(&mut tx, id).await?;
+                }
+                rsvp
+            }
+            None => {
+                let sql = "UPDATE rsvt.reservations SET rstatus = 'confirmed'
+                    WHERE id = $1 RETURNING *";
+                sqlx::query_as(sql)
+                    .bind(id)
+                    .fetch_one(&mut tx)
+                    .instrument(sql_span(sql))
+                    .await?
+            }
+        };
+        tx.commit().await?;
+        info!(
+            "reservation {} is approved by {} at step {}",
+            id, approver, step
+        );
+        Ok(rsvp)
+    }
+
+    async fn reject(
+        &self,
+        id: ReservationId,
+        approver: &str,
+        reason: &str,
+    ) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        let (_, _, step) = self.current_step(&mut tx, id, approver).await?;
+        record(&mut tx, id, step, approver, "rejected", reason).await?;
+
+        // rejected is terminal and not covered by the exclusion constraint
+        let sql = "UPDATE rsvt.reservations SET rstatus = 'rejected' WHERE id = $1 RETURNING *";
+        let rsvp: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+        self.promote_waitlist(&mut tx, &rsvp.resource_id, start..end)
+            .await?;
+        tx.commit().await?;
+        info!(
+            "reservation {} is rejected by {} at step {}",
+            id, approver, step
+        );
+        Ok(rsvp)
+    }
+}
+
+impl OrderManager {
+    /// lock the pending reservation, the approver must be in the current step of its policy
+    async fn current_step(
+        &self,
+        conn: &mut PgConnection,
+        id: ReservationId,
+        approver: &str,
+    ) -> Result<(abi::Reservation, &ApprovalPolicy, usize), Error> {
+        let sql = "SELECT * FROM rsvt.reservations WHERE id = $1 AND rstatus = 'pending'
+            FOR UPDATE";
+        let rsvp: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .instrument(sql_span(sql))
+            .await?;
+        let not_approver = || Error::NotApprover(approver.to_string());
+        let policy = self
+            .approvals()
+            .for_resource(&rsvp.resource_id)
+            .ok_or_else(not_approver)?;
+        let approved = approved(conn, id).await?;
+        let step = policy.current_step(&approved).ok_or_else(not_approver)?;
+        if !policy.steps[step].approvers.iter().any(|a| a == approver) {
+            return Err(not_approver());
+        }
+        Ok((rsvp, policy, step))
+    }
+
+    /// a new pending reservation of a restricted resource waits for the first step
+    pub(crate) async fn request_first_approval(
+        &self,
+        conn: &mut PgConnection,
+        rsvp: &abi::Reservation,
+    ) -> Result<(), Error> {
+        if rsvp.status == ReservationStatus::Pending as i32
+            && self.approvals().for_resource(&rsvp.resource_id).is_some()
+        {
+            request_approval(conn, rsvp.id).await?;
+        }
+        Ok(())
+    }
+}
+
+/// the approval_requested event is sent to the listeners of the change feed
+async fn request_approval(conn: &mut PgConnection, id: ReservationId) -> Result<(), Error> {
+    let sql = "INSERT INTO rsvt.reservation_changes (reservation_id, op)
+        VALUES ($1, 'approval_requested')";
+    sqlx::query(sql)
+        .bind(id)
+        .execute(&mut *conn)
+        .instrument(sql_span(sql))
+        .await?;
+    // delivered when the transaction commits
+    let sql = "SELECT pg_notify('reservation_update', '')";
+    sqlx::query(sql)
+        .execute(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(())
+}
+
+async fn record(
+    conn: &mut PgConnection,
+    id: ReservationId,
+    step: usize,
+    approver: &str,
+    decision: &str,
+    reason: &str,
+) -> Result<(), Error> {
+    let sql = "INSERT INTO rsvt.approvals (reservation_id, step, approver, decision, reason)
+        VALUES ($1, $2, $3, $4::rsvt.approval_decision, $5)
+        ON CONFLICT (reservation_id, step, approver) DO NOTHING";
+    sqlx::query(sql)
+        .bind(id)
+        .bind(step as i32)
+        .bind(approver)
+        .bind(decision)
+        .bind(reason)
+        .execute(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(())
+}
+
+/// the steps and the approvers which have approved the reservation
+async fn approved(
+    conn: &mut PgConnection,
+    id: ReservationId,
+) -> Result<Vec<(usize, String)>, Error> {
+    let sql = "SELECT step, approver FROM rsvt.approvals
+        WHERE reservation_id = $1 AND decision = 'approved'";
+    let rows: Vec<(i32, String)> = sqlx::query_as(sql)
+        .bind(id)
+        .fetch_all(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(step, approver)| (step as usize, approver))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Hold, Order};
+    use abi::{ApprovalConfig, ApprovalMode, ApprovalStep, Reservation, ReservationUpdateType};
+    use std::{collections::HashMap, time::Duration};
+
+    fn manager(pool: sqlx::PgPool) -> OrderManager {
+        let step = |approvers: &[&str], mode| ApprovalStep {
+            approvers: approvers.iter().map(|a| a.to_string()).collect(),
+            mode,
+        };
+        let approvals = ApprovalConfig {
+            resources: HashMap::from([(
+                "boardroom-*".to_string(),
+                ApprovalPolicy {
+                    steps: vec![
+                        step(&["alice", "bob"], ApprovalMode::AnyOf),
+                        step(&["carol", "dave"], ApprovalMode::AllOf),
+                    ],
+                },
+            )]),
+        };
+        OrderManager::new(pool).with_approvals(approvals)
+    }
+
+    fn boardroom(uid: &str) -> Reservation {
+        Reservation::new_pending(
+            uid,
+            "boardroom-1",
+            "2030-01-01T10:00:00+0800".parse().unwrap(),
+            "2030-01-01T12:00:00+0800".parse().unwrap(),
+            "board meeting",
+        )
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn reservation_should_be_confirmed_after_every_step_is_approved() {
+        let manager = manager(migrated_pool.clone());
+        let mut events = manager.listen_events().await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let rsvp = manager.create_order(boardroom("tosei")).await.unwrap();
+        let err = manager.change_status(rsvp.id).await.unwrap_err();
+        assert_eq!(Error::ApprovalRequired("boardroom-1".to_string()), err);
+
+        // carol is an approver of the second step only
+        let err = manager.approve(rsvp.id, "carol", "").await.unwrap_err();
+        assert_eq!(Error::NotApprover("carol".to_string()), err);
+
+        let got = manager.approve(rsvp.id, "bob", "ok").await.unwrap();
+        assert_eq!(ReservationStatus::Pending as i32, got.status);
+        let got = manager.approve(rsvp.id, "carol", "ok").await.unwrap();
+        assert_eq!(ReservationStatus::Pending as i32, got.status);
+        let got = manager.approve(rsvp.id, "dave", "ok").await.unwrap();
+        assert_eq!(ReservationStatus::Confirmed as i32, got.status);
+
+        let ops: Vec<i32> = [
+            ReservationUpdateType::Create,
+            ReservationUpdateType::ApprovalRequested,
+            ReservationUpdateType::ApprovalRequested,
+            ReservationUpdateType::Update,
+        ]
+        .into_iter()
+        .map(|op| op as i32)
+        .collect();
+        for op in ops {
+            let event = events.recv().await.unwrap().unwrap();
+            assert_eq!(op, event.op);
+            assert_eq!(rsvp.id, event.reservation.unwrap().id);
+        }
+
+        let err = manager.approve(rsvp.id, "dave", "").await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn rejected_reservation_should_free_the_window() {
+        let manager = manager(migrated_pool.clone());
+        let rsvp = manager.create_order(boardroom("tosei")).await.unwrap();
+        let err = manager.create_order(boardroom("wxy")).await.unwrap_err();
+        assert!(matches!(err, Error::ConfilictReservation(_)));
+
+        let got = manager.reject(rsvp.id, "alice", "busy").await.unwrap();
+        assert_eq!(ReservationStatus::Rejected as i32, got.status);
+        let err = manager.approve(rsvp.id, "bob", "").await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+        assert!(manager.create_order(boardroom("wxy")).await.is_ok());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn restricted_reservation_should_not_be_confirmed_directly() {
+        let manager = manager(migrated_pool.clone());
+        let mut rsvp = boardroom("tosei");
+        rsvp.status = ReservationStatus::Confirmed as i32;
+        let err = manager.create_order(rsvp).await.unwrap_err();
+        assert_eq!(Error::ApprovalRequired("boardroom-1".to_string()), err);
+
+        let (held, _) = manager
+            .hold(boardroom("tosei"), Duration::from_secs(60))
+            .await
+            .unwrap();
+        let err = manager.promote_hold(held.id, true).await.unwrap_err();
+        assert_eq!(Error::ApprovalRequired("boardroom-1".to_string()), err);
+        let rsvp = manager.promote_hold(held.id, false).await.unwrap();
+        assert_eq!(ReservationStatus::Pending as i32, rsvp.status);
+
+        // the other resources are not restricted
+        let mut rsvp = boardroom("tosei");
+        rsvp.resource_id = "room-1".to_string();
+        let rsvp = manager.create_order(rsvp).await.unwrap();
+        assert!(manager.change_status(rsvp.id).await.is_ok());
+    }
+}
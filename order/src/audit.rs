@@ -0,0 +1,415 @@
+use crate::{
+    manager::sql_span,
+    tenant::{Tenant, TENANT_ROLE},
+    Audit, OrderManager, ReservationId,
+};
+use abi::{convert_to_utc_time, AuditEntry, Error, QueryAuditRequest, ReservationUpdateType};
+use async_trait::async_trait;
+use sqlx::{PgConnection, Postgres, Transaction};
+use std::future::Future;
+use tracing::Instrument;
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 1000;
+
+tokio::task_local! {
+    static ACTOR: Actor;
+}
+
+/// who makes the changes of the current request, recorded in the audit log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Actor {
+    pub user_id: String,
+    pub request_id: String,
+    /// the user is taken from a verified token rather than the metadata of the client
+    pub verified: bool,
+}
+
+impl Actor {
+    pub fn new(user_id: impl Into<String>, request_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            request_id: request_id.into(),
+            verified: false,
+        }
+    }
+
+    /// the actor of a verified token, trusted by the checks of the admins and approvers
+    pub fn verified(user_id: impl Into<String>, request_id: impl Into<String>) -> Self {
+        Self {
+            verified: true,
+            ..Self::new(user_id, request_id)
+        }
+    }
+
+    /// the changes made by the future are recorded as the actor's
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        ACTOR.scope(self, f).await
+    }
+
+    /// the actor of the current task, none for the background jobs
+    pub fn current() -> Option<Actor> {
+        ACTOR.try_with(|actor| actor.clone()).ok()
+    }
+
+    /// the verified user of the current task, the privileged calls fail without it
+    pub fn verified_user() -> Result<String, Error> {
+        match Self::current() {
+            Some(actor) if actor.verified => Ok(actor.user_id),
+            _ => Err(Error::Unauthenticated),
+        }
+    }
+}
+
+#[async_trait]
+impl Audit for OrderManager {
+    async fn history(&self, id: ReservationId) -> Result<Vec<AuditEntry>, Error> {
+        let sql = "SELECT id, reservation_id, op, actor, request_id, changed_at,
+                before::text AS before, after::text AS after
+            FROM rsvt.reservation_audit WHERE reservation_id = $1 ORDER BY id";
+        let mut tx = self.begin().await?;
+        let entries: Vec<AuditEntry> = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        // the history is kept after the reservation is deleted, so it is empty only if the
+        // reservation never existed
+        if entries.is_empty() {
+            return Err(Error::NotFound);
+        }
+        Ok(entries)
+    }
+
+    async fn query_audit(&self, query: QueryAuditRequest) -> Result<(Vec<AuditEntry>, i64), Error> {
+        self.check_admin()?;
+        let page_size = match query.page_size {
+            0 => DEFAULT_PAGE_SIZE,
+            n if (1..=MAX_PAGE_SIZE).contains(&n) => n,
+            n => return Err(Error::InvalidPageSize(n)),
+        };
+        if query.cursor < 0 {
+            return Err(Error::InvalidCursor(query.cursor));
+        }
+        let start = query.start.as_ref().map(convert_to_utc_time);
+        let end = query.end.as_ref().map(convert_to_utc_time);
+        if let (Some(start), Some(end)) = (start, end) {
+            if start >= end {
+                return Err(Error::InvalidTime);
+            }
+        }
+
+        let sql = "SELECT id, reservation_id, op, actor, request_id, changed_at,
+                before::text AS before, after::text AS after
+            FROM rsvt.reservation_audit
+            WHERE id > $1 AND ($2 = '' OR actor = $2)
+            AND ($3::timestamptz IS NULL OR changed_at >= $3)
+            AND ($4::timestamptz IS NULL OR changed_at < $4)
+            ORDER BY id LIMIT $5";
+        let mut tx = self.begin().await?;
+        let mut entries: Vec<AuditEntry> = sqlx::query_as(sql)
+            .bind(query.cursor)
+            .bind(&query.actor)
+            .bind(start)
+            .bind(end)
+            .bind(page_size + 1)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+
+        let next_cursor = if entries.len() as i64 > page_size {
+            entries.truncate(page_size as usize);
+            entries.last().map(|entry| entry.id).unwrap_or_default()
+        } else {
+            0
+        };
+        Ok((entries, next_cursor))
+    }
+}
+
+impl OrderManager {
+    /// begin a transaction in the current tenant whose changes are recorded as the current
+    /// actor's, the transactions out of a tenant are not restricted by the row level security
+    pub(crate) async fn begin(&self) -> Result<Transaction<'static, Postgres>, Error> {
+        let mut tx = self.conn.begin().await?;
+        if let Some(tenant) = Tenant::current() {
+            // the owner of the tables is not restricted, so the role is switched as well
+            let sql = "SELECT set_config('role', $1, true), set_config('rsvt.tenant_id', $2, true)";
+            sqlx::query(sql)
+                .bind(TENANT_ROLE)
+                .bind(tenant.id())
+                .execute(&mut tx)
+                .instrument(sql_span(sql))
+                .await?;
+        }
+        if let Some(actor) = Actor::current() {
+            // local to the transaction, read by the audit trigger
+            let sql = "SELECT set_config('rsvt.actor', left($1, 64), true),
+                set_config('rsvt.request_id', left($2, 64), true),
+                set_config('rsvt.verified', $3::text, true)";
+            sqlx::query(sql)
+                .bind(actor.user_id)
+                .bind(actor.request_id)
+                .bind(actor.verified)
+                .execute(&mut tx)
+                .instrument(sql_span(sql))
+                .await?;
+        }
+        Ok(tx)
+    }
+
+    /// the verified user of the request must be an admin, the user id metadata is not trusted
+    pub(crate) fn check_admin(&self) -> Result<String, Error> {
+        let user = Actor::verified_user()?;
+        if !self.auth.admins.contains(&user) {
+            return Err(Error::NotAdmin(user));
+        }
+        Ok(user)
+    }
+
+    /// the reservation changed by an earlier attempt of the current request, the retry of the
+    /// client sends the same request id, so it returns the reservation instead of changing it
+    /// twice. `op` is the change of the request and `id` the reservation it changes, if known
+    pub(crate) async fn replayed(
+        &self,
+        conn: &mut PgConnection,
+        op: ReservationUpdateType,
+        id: Option<ReservationId>,
+    ) -> Result<Option<abi::Reservation>, Error> {
+        let actor = match Actor::current() {
+            Some(actor) if !actor.request_id.is_empty() => actor,
+            _ => return Ok(None),
+        };
+        // the attempts of a request are serialized until the transaction ends, so a retry
+        // waits for the attempt which is still running and sees its change
+        let sql = "SELECT pg_advisory_xact_lock(hashtextextended('request:' || $1, 0))";
+        sqlx::query(sql)
+            .bind(&actor.request_id)
+            .execute(&mut *conn)
+            .instrument(sql_span(sql))
+            .await?;
+
+        // the same truncation as the audit trigger. The user of a verified token only matches
+        // its own changes, the user id metadata of another client could name the same user
+        let sql = "SELECT reservation_id FROM rsvt.reservation_audit
+            WHERE request_id = left($1, 64) AND actor = left($2, 64) AND verified = $3
+            AND op = $4::rsvt.reservation_update_type
+            AND ($5::bigint IS NULL OR reservation_id = $5)
+            ORDER BY id DESC LIMIT 1";
+        let changed: Option<ReservationId> = sqlx::query_scalar(sql)
+            .bind(&actor.request_id)
+            .bind(&actor.user_id)
+            .bind(actor.verified)
+            .bind(op.to_string())
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .instrument(sql_span(sql))
+            .await?;
+        let id = match changed {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        let sql = "SELECT * FROM rsvt.reservations WHERE id = $1";
+        let rsvp = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .instrument(sql_span(sql))
+            .await?;
+        Ok(Some(rsvp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+    use abi::{convert_to_timestamp, AuthConfig, Reservation, ReservationUpdateType};
+    use chrono::{Duration, Utc};
+
+    fn rsvp(uid: &str, rid: &str) -> Reservation {
+        Reservation::new_pending(
+            uid,
+            rid,
+            "2030-01-01T10:00:00+0800".parse().unwrap(),
+            "2030-01-01T12:00:00+0800".parse().unwrap(),
+            "hello",
+        )
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn history_should_record_the_actor_and_the_snapshots() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let rsvp = Actor::new("tosei", "req-1")
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap();
+        Actor::new("admin", "req-2")
+            .scope(manager.update_note(rsvp.id, "world".to_string()))
+            .await
+            .unwrap();
+        // the changes out of a request are made by the system
+        manager.cancel_reservation(rsvp.id).await.unwrap();
+
+        let entries = manager.history(rsvp.id).await.unwrap();
+        assert_eq!(3, entries.len());
+        let ops: Vec<i32> = entries.iter().map(|entry| entry.op).collect();
+        assert_eq!(
+            vec![
+                ReservationUpdateType::Create as i32,
+                ReservationUpdateType::Update as i32,
+                ReservationUpdateType::Update as i32,
+            ],
+            ops
+        );
+        assert_eq!(
+            ("tosei", "req-1"),
+            (entries[0].actor.as_str(), entries[0].request_id.as_str())
+        );
+        assert!(entries[0].before.is_empty());
+        assert_eq!(
+            ("admin", "req-2"),
+            (entries[1].actor.as_str(), entries[1].request_id.as_str())
+        );
+        assert!(entries[1].before.contains(r#""note": "hello""#));
+        assert!(entries[1].after.contains(r#""note": "world""#));
+        assert_eq!("system", entries[2].actor);
+        assert!(entries[2].after.contains(r#""rstatus": "blocked""#));
+
+        assert_eq!(
+            Error::NotFound,
+            manager.history(rsvp.id + 1).await.unwrap_err()
+        );
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn audit_should_be_queried_by_actor_and_time_range() {
+        let manager = OrderManager::new(migrated_pool.clone()).with_auth(AuthConfig {
+            admins: vec!["admin".to_string()],
+        });
+        for rid in ["room-1", "room-2", "room-3"] {
+            Actor::new("tosei", "")
+                .scope(manager.create_order(rsvp("tosei", rid)))
+                .await
+                .unwrap();
+        }
+        Actor::new("wxy", "")
+            .scope(manager.create_order(rsvp("wxy", "room-4")))
+            .await
+            .unwrap();
+
+        // the audit log is read by the verified admins only
+        let query = QueryAuditRequest::default();
+        let err = Actor::new("admin", "")
+            .scope(manager.query_audit(query.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(Error::Unauthenticated, err);
+        let err = Actor::verified("tosei", "")
+            .scope(manager.query_audit(query))
+            .await
+            .unwrap_err();
+        assert_eq!(Error::NotAdmin("tosei".to_string()), err);
+
+        Actor::verified("admin", "")
+            .scope(async {
+                let query = QueryAuditRequest {
+                    actor: "tosei".to_string(),
+                    page_size: 2,
+                    ..Default::default()
+                };
+                let (entries, cursor) = manager.query_audit(query.clone()).await.unwrap();
+                assert_eq!(2, entries.len());
+                assert_eq!(entries[1].id, cursor);
+                let (entries, cursor) = manager
+                    .query_audit(QueryAuditRequest { cursor, ..query })
+                    .await
+                    .unwrap();
+                assert_eq!(1, entries.len());
+                assert_eq!(0, cursor);
+                assert!(entries.iter().all(|entry| entry.actor == "tosei"));
+
+                let now = Utc::now();
+                let query = |start, end| QueryAuditRequest {
+                    start: Some(convert_to_timestamp(start)),
+                    end: Some(convert_to_timestamp(end)),
+                    ..Default::default()
+                };
+                let (entries, _) = manager
+                    .query_audit(query(now - Duration::hours(1), now + Duration::hours(1)))
+                    .await
+                    .unwrap();
+                assert_eq!(4, entries.len());
+                let (entries, _) = manager
+                    .query_audit(query(now + Duration::hours(1), now + Duration::hours(2)))
+                    .await
+                    .unwrap();
+                assert!(entries.is_empty());
+
+                let err = manager
+                    .query_audit(query(now, now - Duration::hours(1)))
+                    .await
+                    .unwrap_err();
+                assert_eq!(Error::InvalidTime, err);
+            })
+            .await;
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn retried_request_should_not_change_twice() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let attempt = || Actor::new("tosei", "req-1");
+        let created = attempt()
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap();
+        let retried = attempt()
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap();
+        assert_eq!(created, retried);
+        // the other request is a new reservation, which conflicts with the first one
+        let err = Actor::new("tosei", "req-2")
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ConfilictReservation(_)));
+
+        let confirm = || Actor::new("tosei", "req-3");
+        let confirmed = confirm()
+            .scope(manager.change_status(created.id))
+            .await
+            .unwrap();
+        let retried = confirm()
+            .scope(manager.change_status(created.id))
+            .await
+            .unwrap();
+        assert_eq!(confirmed, retried);
+        // without the request id, the confirmed one is not found
+        let err = manager.change_status(created.id).await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+        assert_eq!(2, manager.history(created.id).await.unwrap().len());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn retried_request_should_be_matched_by_the_verified_user() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let created = Actor::verified("tosei", "req-1")
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap();
+        // the client which only names the user in the metadata is not the verified user
+        let err = Actor::new("tosei", "req-1")
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ConfilictReservation(_)));
+
+        let retried = Actor::verified("tosei", "req-1")
+            .scope(manager.create_order(rsvp("tosei", "room-1")))
+            .await
+            .unwrap();
+        assert_eq!(created, retried);
+    }
+}
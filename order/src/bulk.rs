@@ -0,0 +1,140 @@
+use crate::{Bulk, OrderManager};
+use abi::{Error, Reservation};
+use sqlx::{Acquire, Postgres, Transaction};
+
+/// imports the reservations in chunks, the rows of a chunk are committed in one transaction
+#[derive(Debug)]
+pub struct Importer {
+    manager: OrderManager,
+    tx: Option<Transaction<'static, Postgres>>,
+    dry_run: bool,
+}
+
+impl Bulk for OrderManager {
+    fn importer(&self, dry_run: bool) -> Importer {
+        Importer {
+            manager: self.clone(),
+            tx: None,
+            dry_run,
+        }
+    }
+}
+
+impl Importer {
+    /// make the reservations of the chunk the same as create_order, a failed row is rolled
+    /// back alone and does not stop the others of the chunk
+    pub async fn import_chunk(
+        &mut self,
+        rsvps: Vec<Reservation>,
+    ) -> Result<Vec<Result<Reservation, Error>>, Error> {
+        // a dry run keeps one transaction for all the chunks, so the rows conflicting with the
+        // earlier chunks are also reported
+        let tx = match self.tx.as_mut() {
+            Some(tx) => tx,
+            None => self.tx.insert(self.manager.begin().await?),
+        };
+        let mut results = Vec::with_capacity(rsvps.len());
+        for rsvp in rsvps {
+            let mut savepoint = tx.begin().await?;
+            match self.manager.insert_order(&mut savepoint, rsvp).await {
+                Ok(mut rsvp) => {
+                    savepoint.commit().await?;
+                    if self.dry_run {
+                        // the id is rolled back with the dry run
+                        rsvp.id = 0;
+                    }
+                    results.push(Ok(rsvp));
+                }
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    results.push(Err(e));
+                }
+            }
+        }
+        if !self.dry_run {
+            if let Some(tx) = self.tx.take() {
+                tx.commit().await?;
+            }
+        }
+        Ok(results)
+    }
+
+    /// roll back the dry run, the chunks of a real run are committed already
+    pub async fn finish(mut self) -> Result<(), Error> {
+        if let Some(tx) = self.tx.take() {
+            tx.rollback().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+    use abi::{ReservationConflictInfo, ReservationStatus};
+
+    fn rsvp(uid: &str, start: &str, end: &str) -> Reservation {
+        Reservation::new_pending(
+            uid,
+            "room-1",
+            start.parse().unwrap(),
+            end.parse().unwrap(),
+            "",
+        )
+    }
+
+    fn rows() -> Vec<Vec<Reservation>> {
+        vec![
+            vec![
+                rsvp("tosei", "2030-01-01T10:00:00Z", "2030-01-01T11:00:00Z"),
+                rsvp("", "2030-01-01T11:00:00Z", "2030-01-01T12:00:00Z"),
+            ],
+            // conflicts with the first row, which is in the previous chunk
+            vec![rsvp("wxy", "2030-01-01T10:30:00Z", "2030-01-01T11:30:00Z")],
+        ]
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn dry_run_should_report_conflicts_without_committing() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let mut importer = manager.importer(true);
+        let mut results = vec![];
+        for chunk in rows() {
+            results.extend(importer.import_chunk(chunk).await.unwrap());
+        }
+        importer.finish().await.unwrap();
+
+        assert_eq!(0, results[0].as_ref().unwrap().id);
+        assert_eq!(&Err(Error::InvalidUserId("".to_string())), &results[1]);
+        match &results[2] {
+            Err(Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict))) => {
+                assert_eq!("tosei", conflict.conflicting_user_id);
+                assert_eq!(ReservationStatus::Pending, conflict.conflicting_status);
+            }
+            other => panic!("expect a parsed conflict, got {other:?}"),
+        }
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rsvt.reservations")
+            .fetch_one(&migrated_pool)
+            .await
+            .unwrap();
+        assert_eq!(0, count);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn chunks_should_be_committed_without_the_failed_rows() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let mut importer = manager.importer(false);
+        let mut results = vec![];
+        for chunk in rows() {
+            results.extend(importer.import_chunk(chunk).await.unwrap());
+        }
+        importer.finish().await.unwrap();
+
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first, &manager.get_reservation(first.id).await.unwrap());
+        assert!(results[1].is_err());
+        assert!(matches!(results[2], Err(Error::ConfilictReservation(_))));
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    manager::{find_reservation, sql_span},
+    CheckIn, OrderManager, ReservationId, Tenant,
+};
+use abi::{convert_to_utc_time, Error, ReservationStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::{info, Instrument};
+
+#[async_trait]
+impl CheckIn for OrderManager {
+    async fn check_in(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        let sql = "UPDATE rsvt.reservations SET checked_in_at = now()
+            WHERE id = $1 AND deleted_at IS NULL AND rstatus = 'confirmed'
+            AND checked_in_at IS NULL AND upper(rperiod) > now()
+            AND lower(rperiod) - $2 * interval '1 second' <= now()
+            RETURNING *";
+        let rsvp: Option<abi::Reservation> = sqlx::query_as(sql)
+            .bind(id)
+            .bind(self.check_in.early as f64)
+            .fetch_optional(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        let rsvp = match rsvp {
+            Some(rsvp) => rsvp,
+            None => {
+                let rsvp = find_reservation(&mut tx, id).await?;
+                let reason = if rsvp.status != ReservationStatus::Confirmed as i32 {
+                    format!("reservation {id} is not confirmed")
+                } else if rsvp.checked_in_at.is_some() {
+                    format!("reservation {id} is already checked in")
+                } else if convert_to_utc_time(rsvp.end_time.as_ref().unwrap()) <= Utc::now() {
+                    format!("reservation {id} is ended")
+                } else {
+                    format!("reservation {id} is not started")
+                };
+                return Err(Error::InvalidCheckIn(reason));
+            }
+        };
+        tx.commit().await?;
+        Ok(rsvp)
+    }
+
+    async fn check_out(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        let sql = "UPDATE rsvt.reservations SET checked_out_at = now()
+            WHERE id = $1 AND deleted_at IS NULL
+            AND checked_in_at IS NOT NULL AND checked_out_at IS NULL
+            RETURNING *";
+        let rsvp: Option<abi::Reservation> = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_optional(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        let rsvp = match rsvp {
+            Some(rsvp) => rsvp,
+            None => {
+                let rsvp = find_reservation(&mut tx, id).await?;
+                let reason = if rsvp.checked_in_at.is_none() {
+                    format!("reservation {id} is not checked in")
+                } else {
+                    format!("reservation {id} is already checked out")
+                };
+                return Err(Error::InvalidCheckIn(reason));
+            }
+        };
+        tx.commit().await?;
+        Ok(rsvp)
+    }
+
+    async fn mark_no_shows(&self) -> Result<Vec<abi::Reservation>, Error> {
+        let grace_period = match self.check_in.grace_period {
+            Some(grace_period) => grace_period,
+            None => return Ok(vec![]),
+        };
+        if Tenant::current().is_some() {
+            return self.mark_tenant_no_shows(grace_period).await;
+        }
+        // the sweeper sees every tenant, the waitlist is promoted in the tenant of the no-show
+        let sql = format!("SELECT DISTINCT tenant_id FROM rsvt.reservations {NO_SHOW_CONDITION}");
+        let tenants: Vec<String> = sqlx::query_scalar(&sql)
+            .bind(grace_period as f64)
+            .fetch_all(&self.conn)
+            .instrument(sql_span(&sql))
+            .await?;
+        let mut marked = vec![];
+        for tenant in tenants {
+            let no_shows = Tenant::new(tenant).scope(self.mark_tenant_no_shows(grace_period));
+            marked.extend(no_shows.await?);
+        }
+        Ok(marked)
+    }
+
+    async fn no_shows(&self, user_id: &str, since: Option<DateTime<Utc>>) -> Result<i64, Error> {
+        if user_id.is_empty() {
+            return Err(Error::InvalidUserId(user_id.to_string()));
+        }
+
+        let mut tx = self.begin().await?;
+        let sql = "SELECT COUNT(*) FROM rsvt.reservations
+            WHERE user_id = $1 AND rstatus = 'no_show' AND deleted_at IS NULL
+            AND lower(rperiod) >= COALESCE($2, '-infinity')";
+        let count = sqlx::query_scalar(sql)
+            .bind(user_id)
+            .bind(since)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(count)
+    }
+}
+
+/// confirmed, not checked in and the grace period passed, or the reservation ended before it.
+/// The ones ended before the grace period are not marked, so enabling the job keeps the old ones
+const NO_SHOW_CONDITION: &str = "WHERE rstatus = 'confirmed' AND checked_in_at IS NULL
+    AND deleted_at IS NULL
+    AND LEAST(lower(rperiod) + $1 * interval '1 second', upper(rperiod)) <= now()
+    AND upper(rperiod) + $1 * interval '1 second' > now()";
+
+impl OrderManager {
+    async fn mark_tenant_no_shows(
+        &self,
+        grace_period: u64,
+    ) -> Result<Vec<abi::Reservation>, Error> {
+        let mut tx = self.begin().await?;
+        // the window is kept until now, the rest of it could be booked again
+        let sql = format!(
+            "UPDATE rsvt.reservations SET rstatus = 'no_show',
+                bperiod = tstzrange(lower(bperiod), LEAST(upper(bperiod), now()))
+            {NO_SHOW_CONDITION} RETURNING *"
+        );
+        let marked: Vec<abi::Reservation> = sqlx::query_as(&sql)
+            .bind(grace_period as f64)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(&sql))
+            .await?;
+
+        let now = Utc::now();
+        for rsvp in &marked {
+            info!("reservation {} of {} is a no-show", rsvp.id, rsvp.user_id);
+            let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+            if end > now {
+                self.promote_waitlist(&mut tx, &rsvp.resource_id, now..end)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(marked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Audit, Order};
+    use abi::{CheckInConfig, Reservation, ReservationUpdateType};
+    use chrono::Duration;
+
+    fn manager(pool: sqlx::PgPool) -> OrderManager {
+        OrderManager::new(pool).with_check_in(CheckInConfig {
+            early: 900,
+            grace_period: Some(600),
+            ..Default::default()
+        })
+    }
+
+    /// a confirmed reservation of the window relative to now in minutes
+    async fn confirmed(manager: &OrderManager, rid: &str, start: i64, end: i64) -> Reservation {
+        let now = Utc::now();
+        let rsvp = Reservation::new_pending(
+            "tosei",
+            rid,
+            (now + Duration::minutes(start)).into(),
+            (now + Duration::minutes(end)).into(),
+            "",
+        );
+        let rsvp = manager.create_order(rsvp).await.unwrap();
+        manager.change_status(rsvp.id).await.unwrap()
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn reservation_should_be_checked_in_and_out_in_its_window() {
+        let manager = manager(migrated_pool.clone());
+        let rsvp = confirmed(&manager, "room-1", 10, 60).await;
+
+        let err = manager.check_out(rsvp.id).await.unwrap_err();
+        let reason = format!("reservation {} is not checked in", rsvp.id);
+        assert_eq!(Error::InvalidCheckIn(reason), err);
+
+        // 10 minutes before the start is within the early check-in
+        let checked_in = manager.check_in(rsvp.id).await.unwrap();
+        assert!(checked_in.checked_in_at.is_some());
+        let err = manager.check_in(rsvp.id).await.unwrap_err();
+        let reason = format!("reservation {} is already checked in", rsvp.id);
+        assert_eq!(Error::InvalidCheckIn(reason), err);
+
+        let checked_out = manager.check_out(rsvp.id).await.unwrap();
+        assert_eq!(checked_in.checked_in_at, checked_out.checked_in_at);
+        assert!(checked_out.checked_out_at.is_some());
+        let history = manager.history(rsvp.id).await.unwrap();
+        assert_eq!(4, history.len());
+
+        let later = confirmed(&manager, "room-1", 60, 120).await;
+        let err = manager.check_in(later.id).await.unwrap_err();
+        let reason = format!("reservation {} is not started", later.id);
+        assert_eq!(Error::InvalidCheckIn(reason), err);
+        let pending = Reservation::new_pending(
+            "tosei",
+            "room-2",
+            Utc::now().into(),
+            (Utc::now() + Duration::minutes(60)).into(),
+            "",
+        );
+        let pending = manager.create_order(pending).await.unwrap();
+        let err = manager.check_in(pending.id).await.unwrap_err();
+        let reason = format!("reservation {} is not confirmed", pending.id);
+        assert_eq!(Error::InvalidCheckIn(reason), err);
+        assert_eq!(Error::NotFound, manager.check_in(1000).await.unwrap_err());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn missed_reservation_should_be_a_no_show_and_free_the_rest() {
+        let manager = manager(migrated_pool.clone());
+        let mut events = manager.listen_events().await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let missed = confirmed(&manager, "room-1", -20, 40).await;
+        let used = confirmed(&manager, "room-2", -80, -20).await;
+        let err = manager.check_in(used.id).await.unwrap_err();
+        let reason = format!("reservation {} is ended", used.id);
+        assert_eq!(Error::InvalidCheckIn(reason), err);
+        // within the grace period and ended long ago are kept
+        let late = confirmed(&manager, "room-3", -5, 40).await;
+        let old = confirmed(&manager, "room-1", -200, -100).await;
+        let sql = "UPDATE rsvt.reservations SET checked_in_at = lower(rperiod) WHERE id = $1";
+        sqlx::query(sql)
+            .bind(used.id)
+            .execute(&migrated_pool)
+            .await
+            .unwrap();
+
+        let marked = manager.mark_no_shows().await.unwrap();
+        assert_eq!(1, marked.len());
+        assert_eq!(missed.id, marked[0].id);
+        assert_eq!(ReservationStatus::NoShow as i32, marked[0].status);
+        let kept = [used.id, late.id, old.id];
+        for id in kept {
+            let rsvp = manager.get_reservation(id).await.unwrap();
+            assert_eq!(ReservationStatus::Confirmed as i32, rsvp.status);
+        }
+        assert!(manager.mark_no_shows().await.unwrap().is_empty());
+
+        // the change is sent to the listeners
+        let mut op = None;
+        while let Some(Ok(event)) = events.recv().await {
+            if event.reservation.as_ref().map(|r| r.id) == Some(missed.id)
+                && event.reservation.as_ref().map(|r| r.status)
+                    == Some(ReservationStatus::NoShow as i32)
+            {
+                op = Some(event.op);
+                break;
+            }
+        }
+        assert_eq!(Some(ReservationUpdateType::Update as i32), op);
+
+        // the rest of the window could be booked, the time before now is kept
+        let now = Utc::now();
+        let rest = Reservation::new_pending(
+            "bob",
+            "room-1",
+            (now + Duration::minutes(1)).into(),
+            (now + Duration::minutes(40)).into(),
+            "",
+        );
+        assert!(manager.create_order(rest).await.is_ok());
+        let before = Reservation::new_pending(
+            "bob",
+            "room-1",
+            (now - Duration::minutes(19)).into(),
+            (now - Duration::minutes(10)).into(),
+            "",
+        );
+        let err = manager.create_order(before).await.unwrap_err();
+        assert!(matches!(err, Error::ConfilictReservation(_)));
+
+        assert_eq!(1, manager.no_shows("tosei", None).await.unwrap());
+        let since = Some(now);
+        assert_eq!(0, manager.no_shows("tosei", since).await.unwrap());
+        let err = manager.no_shows("", None).await.unwrap_err();
+        assert_eq!(Error::InvalidUserId("".to_string()), err);
+    }
+}
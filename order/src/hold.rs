@@ -0,0 +1,206 @@
+use crate::{
+    manager::{insert_reservation, sql_span},
+    quota::check_quota,
+    Hold, OrderManager, ReservationId, Tenant,
+};
+use abi::{convert_to_utc_time, Error, ReservationStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tracing::{info, Instrument};
+
+#[async_trait]
+impl Hold for OrderManager {
+    async fn hold(
+        &self,
+        mut rsvp: abi::Reservation,
+        ttl: Duration,
+    ) -> Result<(abi::Reservation, DateTime<Utc>), Error> {
+        rsvp.status = ReservationStatus::Held as i32;
+        let rules = self.rules().for_resource(&rsvp.resource_id);
+        rsvp.validate_rules(rules, Utc::now())?;
+        rsvp.set_default_timezone(rules.timezone);
+
+        let buffer = self.buffers().for_resource(&rsvp.resource_id);
+        let mut tx = self.begin().await?;
+        check_quota(&mut tx, self.quotas(), &rsvp).await?;
+        let resource_id = rsvp.resource_id.clone();
+        let rsvp = insert_reservation(&mut tx, rsvp, buffer, rules)
+            .await
+            .map_err(|e| self.count_conflict(&resource_id, e))?;
+        // the expiry follows the clock of the database, the same as the sweeper
+        let sql = "INSERT INTO rsvt.holds (reservation_id, expires_at)
+            VALUES ($1, now() + $2 * interval '1 second') RETURNING expires_at";
+        let expires_at = sqlx::query_scalar(sql)
+            .bind(rsvp.id)
+            .bind(ttl.as_secs_f64())
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok((rsvp, expires_at))
+    }
+
+    async fn promote_hold(
+        &self,
+        id: ReservationId,
+        confirm: bool,
+    ) -> Result<abi::Reservation, Error> {
+        let status = if confirm {
+            ReservationStatus::Confirmed
+        } else {
+            ReservationStatus::Pending
+        };
+
+        let mut tx = self.begin().await?;
+        let sql = "UPDATE rsvt.reservations r SET rstatus = $2::rsvt.reservation_status
+            FROM rsvt.holds h
+            WHERE r.id = $1 AND h.reservation_id = r.id AND r.rstatus = 'held'
+            AND h.expires_at > now() RETURNING r.*";
+        let rsvp: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .bind(status.to_string())
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        // the restricted one is confirmed by the approvers, the update is rolled back
+        if confirm && self.approvals().for_resource(&rsvp.resource_id).is_some() {
+            return Err(Error::ApprovalRequired(rsvp.resource_id));
+        }
+        self.request_first_approval(&mut tx, &rsvp).await?;
+        let sql = "DELETE FROM rsvt.holds WHERE reservation_id = $1";
+        sqlx::query(sql)
+            .bind(id)
+            .execute(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(rsvp)
+    }
+
+    async fn release_expired_holds(&self) -> Result<Vec<abi::Reservation>, Error> {
+        if Tenant::current().is_some() {
+            return self.release_tenant_holds().await;
+        }
+        // the sweeper sees every tenant, the waitlist is promoted in the tenant of the hold
+        let sql = "SELECT DISTINCT tenant_id FROM rsvt.holds WHERE expires_at <= now()";
+        let tenants: Vec<String> = sqlx::query_scalar(sql)
+            .fetch_all(&self.conn)
+            .instrument(sql_span(sql))
+            .await?;
+        let mut released = vec![];
+        for tenant in tenants {
+            released.extend(
+                Tenant::new(tenant)
+                    .scope(self.release_tenant_holds())
+                    .await?,
+            );
+        }
+        Ok(released)
+    }
+}
+
+impl OrderManager {
+    async fn release_tenant_holds(&self) -> Result<Vec<abi::Reservation>, Error> {
+        let mut tx = self.begin().await?;
+        // released holds are cancelled, the trigger emits the change events
+        let sql = "UPDATE rsvt.reservations r SET rstatus = 'blocked'
+            FROM rsvt.holds h
+            WHERE h.reservation_id = r.id AND r.rstatus = 'held' AND h.expires_at <= now()
+            RETURNING r.*";
+        let released: Vec<abi::Reservation> = sqlx::query_as(sql)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        let ids: Vec<i64> = released.iter().map(|rsvp| rsvp.id).collect();
+        let sql = "DELETE FROM rsvt.holds WHERE reservation_id = ANY($1)";
+        sqlx::query(sql)
+            .bind(&ids)
+            .execute(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+
+        for rsvp in &released {
+            info!("hold {} of {} is released", rsvp.id, rsvp.resource_id);
+            let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
+            let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+            self.promote_waitlist(&mut tx, &rsvp.resource_id, start..end)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+    use abi::{Reservation, ReservationConflictInfo};
+
+    fn checkout(uid: &str) -> Reservation {
+        Reservation::new_pending(
+            uid,
+            "ocean room-745",
+            "2030-11-01T15:00:00+0800".parse().unwrap(),
+            "2030-11-07T12:00:00+0800".parse().unwrap(),
+            "paying",
+        )
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn hold_should_block_the_window_until_promoted() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let before = Utc::now();
+        let (held, expires_at) = manager
+            .hold(checkout("tosei"), Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert_eq!(ReservationStatus::Held as i32, held.status);
+        assert!(expires_at > before + chrono::Duration::seconds(590));
+
+        let err = manager.create_order(checkout("wxy")).await.unwrap_err();
+        match err {
+            Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict)) => {
+                assert_eq!(held.id, conflict.conflicting_id);
+                assert_eq!(ReservationStatus::Held, conflict.conflicting_status);
+            }
+            _ => panic!("expect conflict, got {err:?}"),
+        }
+
+        // the sweeper keeps the unexpired hold
+        assert!(manager.release_expired_holds().await.unwrap().is_empty());
+        let rsvp = manager.promote_hold(held.id, true).await.unwrap();
+        assert_eq!(ReservationStatus::Confirmed as i32, rsvp.status);
+        assert_eq!(held.id, rsvp.id);
+        // promoted only once
+        let err = manager.promote_hold(held.id, false).await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn expired_hold_should_be_released() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let (held, _) = manager
+            .hold(checkout("tosei"), Duration::ZERO)
+            .await
+            .unwrap();
+
+        let err = manager.promote_hold(held.id, false).await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+
+        let released = manager.release_expired_holds().await.unwrap();
+        assert_eq!(1, released.len());
+        assert_eq!(ReservationStatus::Cancelled as i32, released[0].status);
+        assert!(manager.create_order(checkout("wxy")).await.is_ok());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn held_status_should_not_be_created_directly() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let mut rsvp = checkout("tosei");
+        rsvp.status = ReservationStatus::Held as i32;
+        let err = manager.create_order(rsvp).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidStatus(s) if s == ReservationStatus::Held as i32));
+    }
+}
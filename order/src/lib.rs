@@ -1,10 +1,35 @@
+mod adjust;
+mod approval;
+mod audit;
+mod bulk;
+mod check_in;
+mod hold;
 mod manager;
+mod quota;
+mod retention;
+mod tenant;
+mod waitlist;
+mod webhook;
 
-use abi::{Error, FilterPager};
+use abi::{
+    ApprovalConfig, AuthConfig, BufferConfig, CheckInConfig, Error, FilterPager, QuotaConfig,
+    RetentionConfig, RuleConfig, TenantConfig, WaitlistMode,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tokio::sync::mpsc;
 
+pub use audit::Actor;
+pub use bulk::Importer;
+pub use tenant::{spawn_scoped, Tenant};
+pub use webhook::Delivery;
+
 pub type ReservationId = i64;
 
 #[async_trait]
@@ -36,9 +61,187 @@ pub trait Order {
         &self,
         filter: abi::ReservationFilter,
     ) -> Result<(FilterPager, Vec<abi::Reservation>), Error>;
+
+    /// listen to newly added/confirmed/cancelled reservations
+    async fn listen(&self) -> mpsc::Receiver<Result<abi::Reservation, abi::Error>>;
+
+    /// listen to the changes of the reservations with their update type
+    async fn listen_events(&self) -> mpsc::Receiver<Result<abi::ListenResponse, abi::Error>>;
+}
+
+#[async_trait]
+pub trait Waitlist {
+    /// queue for the window of the resource
+    async fn join_waitlist(&self, entry: abi::WaitlistEntry) -> Result<abi::WaitlistEntry, Error>;
+
+    /// leave the waitlist, only the waiting or notified entry could leave
+    async fn leave_waitlist(&self, id: i64) -> Result<abi::WaitlistEntry, Error>;
+
+    /// waiting and notified entries in queue order
+    async fn list_waitlist(
+        &self,
+        resource_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<abi::WaitlistEntry>, Error>;
+
+    /// mark the entries which are not served before the expiry as expired
+    async fn expire_waitlist(&self) -> Result<Vec<abi::WaitlistEntry>, Error>;
+
+    /// listen to the changed waitlist entries
+    async fn listen_waitlist(&self) -> mpsc::Receiver<Result<abi::WaitlistEntry, Error>>;
+}
+
+#[async_trait]
+pub trait Hold {
+    /// lock the window until the ttl passes, returns the held reservation and its expiry
+    async fn hold(
+        &self,
+        rsvp: abi::Reservation,
+        ttl: Duration,
+    ) -> Result<(abi::Reservation, DateTime<Utc>), Error>;
+
+    /// turn an unexpired hold into a pending reservation, or a confirmed one
+    async fn promote_hold(
+        &self,
+        id: ReservationId,
+        confirm: bool,
+    ) -> Result<abi::Reservation, Error>;
+
+    /// cancel the expired holds and offer their windows to the waitlist
+    async fn release_expired_holds(&self) -> Result<Vec<abi::Reservation>, Error>;
+}
+
+#[async_trait]
+pub trait Approval {
+    /// approve the current step of the pending reservation, confirmed after the last step
+    async fn approve(
+        &self,
+        id: ReservationId,
+        approver: &str,
+        reason: &str,
+    ) -> Result<abi::Reservation, Error>;
+
+    /// reject the pending reservation at its current step, the window is freed
+    async fn reject(
+        &self,
+        id: ReservationId,
+        approver: &str,
+        reason: &str,
+    ) -> Result<abi::Reservation, Error>;
+}
+
+#[async_trait]
+pub trait Quota {
+    /// the usage and the remaining of the configured quotas of the user
+    async fn quota(&self, user_id: &str) -> Result<Vec<abi::QuotaUsage>, Error>;
+}
+
+#[async_trait]
+pub trait Retention {
+    /// hide the reservation from get, query and filter, an active one is cancelled
+    async fn delete_reservation(&self, id: ReservationId) -> Result<abi::Reservation, Error>;
+
+    /// remove the reservation permanently, the current actor must be an admin
+    async fn purge_reservation(&self, id: ReservationId) -> Result<abi::Reservation, Error>;
+
+    /// move the reservations ended before the max age into the archive
+    async fn archive_reservations(&self) -> Result<Vec<abi::Reservation>, Error>;
+}
+
+#[async_trait]
+pub trait CheckIn {
+    /// record the actual start of the confirmed reservation, from the early check-in on
+    async fn check_in(&self, id: ReservationId) -> Result<abi::Reservation, Error>;
+
+    /// record the actual end of the checked in reservation
+    async fn check_out(&self, id: ReservationId) -> Result<abi::Reservation, Error>;
+
+    /// mark the confirmed reservations not checked in within the grace period as no-shows, the
+    /// rest of their windows is offered to the waitlist
+    async fn mark_no_shows(&self) -> Result<Vec<abi::Reservation>, Error>;
+
+    /// the no-shows of the user which started after the time
+    async fn no_shows(&self, user_id: &str, since: Option<DateTime<Utc>>) -> Result<i64, Error>;
+}
+
+#[async_trait]
+pub trait Adjust {
+    /// move the end of the ongoing or upcoming reservation later, a conflict has the latest end
+    /// which the resource is free until
+    async fn extend(
+        &self,
+        id: ReservationId,
+        end: DateTime<Utc>,
+    ) -> Result<abi::Reservation, Error>;
+
+    /// end the ongoing reservation now, the rest of its window is offered to the waitlist
+    async fn release_early(&self, id: ReservationId) -> Result<abi::Reservation, Error>;
+}
+
+#[async_trait]
+pub trait Outbox {
+    /// post the changes made after the registration to the webhook
+    async fn register_webhook(&self, webhook: abi::Webhook) -> Result<abi::Webhook, Error>;
+
+    /// remove the webhook with its outbox and dead letters
+    async fn unregister_webhook(&self, id: i64) -> Result<abi::Webhook, Error>;
+
+    /// the registered webhooks without their secrets
+    async fn list_webhooks(&self) -> Result<Vec<abi::Webhook>, Error>;
+
+    /// the deliveries of the webhook which failed every attempt
+    async fn list_dead_letters(&self, webhook_id: i64) -> Result<Vec<abi::DeadLetter>, Error>;
+
+    /// move the dead letters back to the outbox, all of the webhook if `ids` is empty
+    async fn replay_webhook(&self, webhook_id: i64, ids: &[i64]) -> Result<i64, Error>;
+
+    /// queue the changes after the cursors of the webhooks which match their filters
+    async fn fan_out(&self) -> Result<u64, Error>;
+
+    /// the due deliveries, which are not due again until the lease passes
+    async fn claim_deliveries(&self, limit: i64, lease: Duration) -> Result<Vec<Delivery>, Error>;
+
+    /// remove the delivery accepted by the webhook
+    async fn complete_delivery(&self, id: i64) -> Result<(), Error>;
+
+    /// retry the delivery after the backoff, or move it to the dead letters if there is none
+    async fn fail_delivery(
+        &self,
+        id: i64,
+        error: &str,
+        retry_after: Option<Duration>,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait]
+pub trait Audit {
+    /// the changes of the reservation in order, kept after it is deleted
+    async fn history(&self, id: ReservationId) -> Result<Vec<abi::AuditEntry>, Error>;
+
+    /// the audit log filtered by the actor and the time range, returns the next cursor
+    async fn query_audit(
+        &self,
+        query: abi::QueryAuditRequest,
+    ) -> Result<(Vec<abi::AuditEntry>, i64), Error>;
+}
+
+pub trait Bulk {
+    /// import the reservations in chunks, a dry run reports the failures without committing
+    fn importer(&self, dry_run: bool) -> Importer;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OrderManager {
     conn: PgPool,
+    waitlist_mode: WaitlistMode,
+    buffers: BufferConfig,
+    rules: RuleConfig,
+    quotas: QuotaConfig,
+    approvals: ApprovalConfig,
+    auth: AuthConfig,
+    retention: RetentionConfig,
+    check_in: CheckInConfig,
+    tenants: HashMap<String, TenantConfig>,
+    /// the bookings rejected by conflict, by resource, shared by the clones
+    conflicts: Arc<Mutex<HashMap<String, u64>>>,
 }
@@ -1,21 +1,86 @@
-use crate::{Order, OrderManager, ReservationId};
+use crate::{quota::check_quota, spawn_scoped, Order, OrderManager, ReservationId};
 use abi::{
-    convert_to_utc_time, DbConfig, Error, FilterPager, ReservationQuery, ReservationStatus,
-    Validator,
+    convert_to_utc_time, ApprovalConfig, AuthConfig, BookingRules, Buffer, BufferConfig,
+    CheckInConfig, DbConfig, Error, FilterPager, QuotaConfig, ReservationConflict,
+    ReservationConflictInfo, ReservationQuery, ReservationStatus, ReservationUpdateType,
+    ReservationWindow, RetentionConfig, RuleConfig, Validator, WaitlistMode,
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use sqlx::{
-    postgres::{types::PgRange, PgPoolOptions},
-    Either, PgPool, Row,
+    postgres::{types::PgRange, PgListener, PgPoolOptions, PgRow},
+    Connection, Either, FromRow, PgConnection, PgPool, Row,
 };
+use std::{collections::HashMap, ops::Range, time::Duration};
 use tokio::sync::mpsc;
-use tracing::{info, warn};
+use tracing::{info, info_span, warn, Instrument, Span};
+
+/// number of free windows suggested when the reservation conflicts
+const MAX_ALTERNATIVES: usize = 3;
+/// the free windows are searched within the days before and after the wanted one
+const ALTERNATIVE_HORIZON_DAYS: i64 = 7;
 
 impl OrderManager {
     pub fn new(conn: PgPool) -> Self {
-        Self { conn }
+        Self {
+            conn,
+            waitlist_mode: WaitlistMode::default(),
+            buffers: BufferConfig::default(),
+            rules: RuleConfig::default(),
+            quotas: QuotaConfig::default(),
+            approvals: ApprovalConfig::default(),
+            auth: AuthConfig::default(),
+            retention: RetentionConfig::default(),
+            check_in: CheckInConfig::default(),
+            tenants: HashMap::new(),
+            conflicts: Default::default(),
+        }
+    }
+
+    /// the buffers are applied to the reservations created afterwards
+    pub fn with_buffers(mut self, buffers: BufferConfig) -> Self {
+        self.buffers = buffers;
+        self
+    }
+
+    /// the booking rules are checked when a reservation is created or held
+    pub fn with_rules(mut self, rules: RuleConfig) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// the quotas are checked in the transaction which creates or holds a reservation
+    pub fn with_quotas(mut self, quotas: QuotaConfig) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// the reservations of the restricted resources are confirmed by the approvers only
+    pub fn with_approvals(mut self, approvals: ApprovalConfig) -> Self {
+        self.approvals = approvals;
+        self
+    }
+
+    /// the admins of the privileged calls, e.g. reading the audit log
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn with_retention(mut self, retention: RetentionConfig) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn with_check_in(mut self, check_in: CheckInConfig) -> Self {
+        self.check_in = check_in;
+        self
+    }
+
+    pub fn with_waitlist_mode(mut self, mode: WaitlistMode) -> Self {
+        self.waitlist_mode = mode;
+        self
     }
 
     pub async fn from_config(config: &DbConfig) -> Result<Self, Error> {
@@ -26,34 +91,79 @@ impl OrderManager {
             .await?;
         Ok(Self::new(conn))
     }
-}
 
-#[async_trait]
-impl Order for OrderManager {
-    async fn create_order(&self, mut rsvp: abi::Reservation) -> Result<abi::Reservation, Error> {
-        rsvp.validate()?;
+    /// current number of connections and idle connections of the pool
+    pub fn pool_state(&self) -> (u32, usize) {
+        (self.conn.size(), self.conn.num_idle())
+    }
 
-        let status = ReservationStatus::from_i32(rsvp.status).unwrap_or(ReservationStatus::Pending);
+    /// the bookings rejected by conflict since the start, by resource
+    pub fn conflicts(&self) -> HashMap<String, u64> {
+        self.conflicts.lock().unwrap().clone()
+    }
 
-        // can not get Timestamp of prost_type, because not import tonic crate
-        let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
-        let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
-        let timespan: PgRange<DateTime<Utc>> = (start..end).into();
+    /// count the conflict of a booking where it is produced, the other errors pass through
+    pub(crate) fn count_conflict(&self, resource_id: &str, e: Error) -> Error {
+        if let Error::ConfilictReservation(_) = e {
+            *self
+                .conflicts
+                .lock()
+                .unwrap()
+                .entry(resource_id.to_string())
+                .or_default() += 1;
+        }
+        e
+    }
 
-        let id: i64 = sqlx::query(
-            "INSERT INTO rsvt.reservations (user_id, resource_id, rperiod, rstatus, note)
-            VALUES ($1, $2, $3, $4::rsvt.reservation_status, $5) RETURNING id",
-        )
-        .bind(rsvp.user_id.clone())
-        .bind(rsvp.resource_id.clone())
-        .bind(timespan)
-        .bind(status.to_string())
-        .bind(rsvp.note.clone())
-        .fetch_one(&self.conn)
-        .await?
-        .get(0);
+    /// close the pool and wait for the connections to be released
+    pub async fn close(&self) {
+        self.conn.close().await;
+    }
+
+    /// check and insert a new reservation in the transaction of the caller
+    pub(crate) async fn insert_order(
+        &self,
+        conn: &mut PgConnection,
+        mut rsvp: abi::Reservation,
+    ) -> Result<abi::Reservation, Error> {
+        // a held reservation without the expiry would never be released
+        if rsvp.status == ReservationStatus::Held as i32
+            || rsvp.status == ReservationStatus::Rejected as i32
+            || rsvp.status == ReservationStatus::NoShow as i32
+        {
+            return Err(Error::InvalidStatus(rsvp.status));
+        }
+        let restricted = self.approvals().for_resource(&rsvp.resource_id).is_some();
+        if restricted && rsvp.status == ReservationStatus::Confirmed as i32 {
+            return Err(Error::ApprovalRequired(rsvp.resource_id));
+        }
+        let rules = self.rules().for_resource(&rsvp.resource_id);
+        rsvp.validate_rules(rules, Utc::now())?;
+        rsvp.set_default_timezone(rules.timezone);
 
-        rsvp.id = id;
+        let buffer = self.buffers().for_resource(&rsvp.resource_id);
+        check_quota(&mut *conn, self.quotas(), &rsvp).await?;
+        let resource_id = rsvp.resource_id.clone();
+        let rsvp = insert_reservation(&mut *conn, rsvp, buffer, rules)
+            .await
+            .map_err(|e| self.count_conflict(&resource_id, e))?;
+        self.request_first_approval(conn, &rsvp).await?;
+        Ok(rsvp)
+    }
+}
+
+#[async_trait]
+impl Order for OrderManager {
+    async fn create_order(&self, rsvp: abi::Reservation) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        if let Some(rsvp) = self
+            .replayed(&mut tx, ReservationUpdateType::Create, None)
+            .await?
+        {
+            return Ok(rsvp);
+        }
+        let rsvp = self.insert_order(&mut tx, rsvp).await?;
+        tx.commit().await?;
         Ok(rsvp)
     }
 
@@ -66,12 +176,28 @@ impl Order for OrderManager {
         // )
         // .fetch_one(&self.conn)
         // .await?;
-        let reservation = sqlx::query_as(
-            "update rsvt.reservations set rstatus = 'confirmed' where id = $1 and rstatus = 'pending' RETURNING *"
-        )
-        .bind(id)
-        .fetch_one(&self.conn)
-        .await?;
+        let mut tx = self.begin().await?;
+        if let Some(rsvp) = self
+            .replayed(&mut tx, ReservationUpdateType::Update, Some(id))
+            .await?
+        {
+            return Ok(rsvp);
+        }
+        let sql = "update rsvt.reservations set rstatus = 'confirmed' where id = $1 and rstatus = 'pending' RETURNING *";
+        let reservation: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        // the restricted one is confirmed by the approvers, the update is rolled back
+        if self
+            .approvals
+            .for_resource(&reservation.resource_id)
+            .is_some()
+        {
+            return Err(Error::ApprovalRequired(reservation.resource_id));
+        }
+        tx.commit().await?;
         Ok(reservation)
     }
 
@@ -81,32 +207,57 @@ impl Order for OrderManager {
         id: ReservationId,
         note: String,
     ) -> Result<abi::Reservation, Error> {
-        let rsvp =
-            sqlx::query_as("update rsvt.reservations set note = $1 where id = $2 RETURNING *")
-                .bind(note)
-                .bind(id)
-                .fetch_one(&self.conn)
-                .await?;
+        let mut tx = self.begin().await?;
+        let sql = "update rsvt.reservations set note = $1 where id = $2 and deleted_at IS NULL RETURNING *";
+        let rsvp = sqlx::query_as(sql)
+            .bind(note)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
         Ok(rsvp)
     }
 
-    /// cancel the book reservation resource
+    /// cancel the book reservation resource, the freed window is offered to the waitlist
     async fn cancel_reservation(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
-        let rsvp = sqlx::query_as(
-            "update rsvt.reservations set rstatus = 'pending' where id = $1 RETURNING *",
-        )
-        .bind(id)
-        .fetch_one(&self.conn)
-        .await?;
+        let mut tx = self.begin().await?;
+        // cancelled is stored as blocked, which is not covered by the exclusion constraint.
+        // Only a pending or confirmed one holds its window, so the waitlist is promoted only
+        // when it is the one cancelled
+        let sql = "update rsvt.reservations set rstatus = 'blocked'
+            where id = $1 and deleted_at IS NULL and rstatus IN ('pending', 'confirmed')
+            RETURNING *";
+        let rsvp: Option<abi::Reservation> = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_optional(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        let rsvp = match rsvp {
+            Some(rsvp) => rsvp,
+            None => {
+                let rsvp = find_reservation(&mut tx, id).await?;
+                return Err(Error::InvalidStatus(rsvp.status));
+            }
+        };
+        let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+        self.promote_waitlist(&mut tx, &rsvp.resource_id, start..end)
+            .await?;
+        tx.commit().await?;
         Ok(rsvp)
     }
 
     /// get reservation resources by id
     async fn get_reservation(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
-        let rsvp = sqlx::query_as("select * from rsvt.reservations where id = $1")
+        let sql = "select * from rsvt.reservations where id = $1 and deleted_at IS NULL";
+        let mut tx = self.begin().await?;
+        let rsvp = sqlx::query_as(sql)
             .bind(id)
-            .fetch_one(&self.conn)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
             .await?;
+        tx.commit().await?;
         Ok(rsvp)
     }
 
@@ -115,47 +266,62 @@ impl Order for OrderManager {
         &self,
         query: ReservationQuery,
     ) -> mpsc::Receiver<Result<abi::Reservation, abi::Error>> {
+        let (tx, rx) = mpsc::channel(128);
+        // the invalid period is the only item of the stream
+        if let Err(e) = query.validate() {
+            let _ = tx.send(Err(e)).await;
+            return rx;
+        }
         let user_id = string_to_option(&query.user_id);
         let resource_id = string_to_option(&query.resource_id);
         let range = query.timespan();
         let status =
             ReservationStatus::from_i32(query.status).unwrap_or(ReservationStatus::Pending);
-        let conn = self.conn.clone();
+        let manager = self.clone();
 
-        let (tx, rx) = mpsc::channel(128);
-        tokio::spawn(async move {
-            let mut rsvps = sqlx::query_as(
-                "select * from rsvt.query($1, $2, $3, $4::rsvt.reservation_status, $5, $6, $7)",
-            )
-            .bind(user_id)
-            .bind(resource_id)
-            .bind(range)
-            .bind(status.to_string())
-            .bind(query.page)
-            .bind(query.desc)
-            .bind(query.page_size)
-            .fetch_many(&conn);
-
-            while let Some(ret) = rsvps.next().await {
-                match ret {
-                    Ok(Either::Left(result)) => {
-                        info!("query result: {:?}", result)
+        let sql = "select * from rsvt.query($1, $2, $3, $4::rsvt.reservation_status, $5, $6, $7)";
+        let span = sql_span(sql);
+        spawn_scoped(
+            async move {
+                let mut conn = match manager.begin().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
                     }
-                    Ok(Either::Right(rsvp)) => {
-                        if tx.send(Ok(rsvp)).await.is_err() {
-                            // rx is dropped, so client disconnected
-                            break;
+                };
+                let mut rsvps = sqlx::query_as(sql)
+                    .bind(user_id)
+                    .bind(resource_id)
+                    .bind(range)
+                    .bind(status.to_string())
+                    .bind(query.page)
+                    .bind(query.desc)
+                    .bind(query.page_size)
+                    .fetch_many(&mut conn);
+
+                while let Some(ret) = rsvps.next().await {
+                    match ret {
+                        Ok(Either::Left(result)) => {
+                            info!("query result: {:?}", result)
                         }
-                    }
-                    Err(e) => {
-                        warn!("query error: {:?}", e);
-                        if tx.send(Err(e.into())).await.is_err() {
-                            break;
+                        Ok(Either::Right(rsvp)) => {
+                            if tx.send(Ok(rsvp)).await.is_err() {
+                                // rx is dropped, so client disconnected
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("query error: {:?}", e);
+                            if tx.send(Err(e.into())).await.is_err() {
+                                break;
+                            }
                         }
                     }
                 }
             }
-        });
+            .instrument(span),
+        );
 
         rx
     }
@@ -168,27 +334,268 @@ impl Order for OrderManager {
         let resource_id = str_to_option(&filter.resource_id);
         let status =
             ReservationStatus::from_i32(filter.status).unwrap_or(ReservationStatus::Pending);
-        let rsvps: Vec<abi::Reservation> = sqlx::query_as(
-            "select * from rsvt.filter($1, $2, $3::rsvt.reservation_status, $4, $5, $6)",
-        )
-        .bind(user_id)
-        .bind(resource_id)
-        .bind(status.to_string())
-        .bind(filter.cursor)
-        .bind(filter.desc)
-        .bind(filter.page_size)
-        .fetch_all(&self.conn)
-        .await?;
+        let sql = "select * from rsvt.filter($1, $2, $3::rsvt.reservation_status, $4, $5, $6)";
+        let mut tx = self.begin().await?;
+        let rsvps: Vec<abi::Reservation> = sqlx::query_as(sql)
+            .bind(user_id)
+            .bind(resource_id)
+            .bind(status.to_string())
+            .bind(filter.cursor)
+            .bind(filter.desc)
+            .bind(filter.page_size)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
 
+        // the pager is empty if there is no more reservations
         let pager = FilterPager {
-            prev: Some(rsvps[0].id),
-            next: Some(rsvps[rsvps.len() - 1].id),
+            prev: rsvps.first().map(|rsvp| rsvp.id),
+            next: rsvps.last().map(|rsvp| rsvp.id),
             // TODO: how to get total count?
             total: Some(0),
         };
 
         Ok((pager, rsvps))
     }
+
+    /// subscribe the reservation_update channel and send the changed reservations
+    async fn listen(&self) -> mpsc::Receiver<Result<abi::Reservation, abi::Error>> {
+        spawn_listener(self.clone(), &RESERVATION_FEED).await
+    }
+
+    /// subscribe the reservation_update channel and send the changes with the update type
+    async fn listen_events(&self) -> mpsc::Receiver<Result<abi::ListenResponse, Error>> {
+        spawn_listener(self.clone(), &RESERVATION_EVENTS).await
+    }
+}
+
+/// a changes queue filled by the trigger, which notifies the channel
+pub(crate) struct ChangeFeed {
+    pub(crate) channel: &'static str,
+    /// the changes after the cursor of ($1 txid, $2 change id) in the order of the cursor,
+    /// the columns of `change_cursor!()` are returned
+    pub(crate) changes_sql: &'static str,
+}
+
+/// the changed reservation, the removed one is read from its last snapshot in the audit log
+macro_rules! changed_reservation {
+    () => {
+        "JOIN LATERAL (
+            SELECT * FROM rsvt.reservations WHERE id = c.reservation_id
+            UNION ALL
+            (SELECT (jsonb_populate_record(NULL::rsvt.reservations, a.before)).*
+            FROM rsvt.reservation_audit a
+            WHERE a.reservation_id = c.reservation_id AND a.op = 'delete'
+            AND NOT EXISTS (SELECT 1 FROM rsvt.reservations WHERE id = c.reservation_id)
+            ORDER BY a.id DESC LIMIT 1)
+        ) r ON true"
+    };
+}
+pub(crate) use changed_reservation;
+
+/// the cursor of the change `c`, and whether its transaction and every older one ended
+macro_rules! change_cursor {
+    () => {
+        "c.txid::text::bigint AS change_txid, c.id::bigint AS change_id,
+        c.txid < pg_snapshot_xmin(pg_current_snapshot()) AS settled"
+    };
+}
+pub(crate) use change_cursor;
+
+// the listeners of the reservations are not sent the approval requests
+const RESERVATION_FEED: ChangeFeed = ChangeFeed {
+    channel: "reservation_update",
+    changes_sql: concat!(
+        "SELECT ",
+        change_cursor!(),
+        ", r.* FROM rsvt.reservation_changes c ",
+        changed_reservation!(),
+        " WHERE (c.txid, c.id) > ($1::bigint::text::xid8, $2) AND c.op <> 'approval_requested'
+        ORDER BY c.txid, c.id"
+    ),
+};
+
+const RESERVATION_EVENTS: ChangeFeed = ChangeFeed {
+    channel: "reservation_update",
+    changes_sql: concat!(
+        "SELECT ",
+        change_cursor!(),
+        ", c.op, r.* FROM rsvt.reservation_changes c ",
+        changed_reservation!(),
+        " WHERE (c.txid, c.id) > ($1::bigint::text::xid8, $2) ORDER BY c.txid, c.id"
+    ),
+};
+
+/// a change read before the older transactions ended is read again after the interval, in
+/// case no later change notifies the listener
+const UNSETTLED_RETRY: Duration = Duration::from_millis(500);
+
+/// the changes are read in the tenant of the caller, so the listeners of a tenant are not sent
+/// the changes of the others
+pub(crate) async fn spawn_listener<T>(
+    manager: OrderManager,
+    feed: &'static ChangeFeed,
+) -> mpsc::Receiver<Result<T, Error>>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'static,
+{
+    let (tx, rx) = mpsc::channel(128);
+    // subscribed before the receiver is returned, so the changes after the call are sent
+    match subscribe(&manager, feed).await {
+        Ok((listener, cursor)) => spawn_scoped(async move {
+            if let Err(e) = listen_changes(manager, feed, listener, cursor, &tx).await {
+                warn!("listen error: {:?}", e);
+                let _ = tx.send(Err(e)).await;
+            }
+        }),
+        Err(e) => {
+            warn!("listen error: {:?}", e);
+            let _ = tx.send(Err(e)).await;
+        }
+    }
+    rx
+}
+
+/// listen the channel of the feed, the cursor is before the changes of the later transactions
+async fn subscribe(
+    manager: &OrderManager,
+    feed: &ChangeFeed,
+) -> Result<(PgListener, (i64, i64)), Error> {
+    let mut listener = PgListener::connect_with(&manager.conn).await?;
+    listener.listen(feed.channel).await?;
+
+    let sql = "SELECT pg_snapshot_xmax(pg_current_snapshot())::text::bigint";
+    let mut conn = manager.begin().await?;
+    let txid: i64 = sqlx::query_scalar(sql).fetch_one(&mut conn).await?;
+    conn.commit().await?;
+    Ok((listener, (txid, 0)))
+}
+
+/// wait for the notification of trigger, then read the changes queue after the cursor
+///
+/// the change ids are taken before the commits, so the cursor follows the transactions of the
+/// changes, and stops at a change whose older transactions may still commit a change before it
+async fn listen_changes<T>(
+    manager: OrderManager,
+    feed: &ChangeFeed,
+    mut listener: PgListener,
+    mut cursor: (i64, i64),
+    tx: &mpsc::Sender<Result<T, Error>>,
+) -> Result<(), Error>
+where
+    T: for<'r> FromRow<'r, PgRow>,
+{
+    let mut unsettled = false;
+    loop {
+        tokio::select! {
+            // client disconnected
+            _ = tx.closed() => return Ok(()),
+            notification = listener.recv() => {
+                notification?;
+            }
+            _ = tokio::time::sleep(UNSETTLED_RETRY), if unsettled => {}
+        }
+
+        let mut conn = manager.begin().await?;
+        let rows = sqlx::query(feed.changes_sql)
+            .bind(cursor.0)
+            .bind(cursor.1)
+            .fetch_all(&mut conn)
+            .await?;
+        conn.commit().await?;
+
+        unsettled = false;
+        for row in rows {
+            if !row.get::<bool, _>("settled") {
+                unsettled = true;
+                break;
+            }
+            cursor = (row.get("change_txid"), row.get("change_id"));
+            let item = T::from_row(&row)?;
+            if tx.send(Ok(item)).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// insert the reservation in the transaction, the conflict is filled with the existing one
+/// and the free windows which follow the booking rules of the resource
+///
+/// the exclusion constraint checks the period padded with the buffer of the resource
+pub(crate) async fn insert_reservation(
+    conn: &mut PgConnection,
+    rsvp: abi::Reservation,
+    buffer: Buffer,
+    rules: &BookingRules,
+) -> Result<abi::Reservation, Error> {
+    rsvp.validate()?;
+
+    let status = ReservationStatus::from_i32(rsvp.status).unwrap_or(ReservationStatus::Pending);
+
+    // can not get Timestamp of prost_type, because not import tonic crate
+    let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
+    let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+    let timespan: PgRange<DateTime<Utc>> = (start..end).into();
+    let padded: PgRange<DateTime<Utc>> = pad(start..end, buffer).into();
+
+    // the row has the local times in the timezone
+    let sql = "INSERT INTO rsvt.reservations
+        (user_id, resource_id, rperiod, rstatus, note, bperiod, timezone)
+        VALUES ($1, $2, $3, $4::rsvt.reservation_status, $5, $6, COALESCE(NULLIF($7, ''), 'UTC'))
+        RETURNING *";
+    // the savepoint keeps the transaction usable after the exclusion violation
+    let mut savepoint = conn.begin().await?;
+    let ret = sqlx::query_as(sql)
+        .bind(rsvp.user_id.clone())
+        .bind(rsvp.resource_id.clone())
+        .bind(timespan)
+        .bind(status.to_string())
+        .bind(rsvp.note.clone())
+        .bind(padded)
+        .bind(rsvp.timezone.clone())
+        .fetch_one(&mut savepoint)
+        .instrument(sql_span(sql))
+        .await;
+    let rsvp = match ret.map_err(Error::from) {
+        Ok(rsvp) => rsvp,
+        Err(Error::ConfilictReservation(info)) => {
+            savepoint.rollback().await?;
+            let info = match find_conflict(conn, &rsvp, start..end, buffer, rules).await? {
+                Some(conflict) => ReservationConflictInfo::Parsed(Box::new(conflict)),
+                None => info,
+            };
+            return Err(Error::ConfilictReservation(info));
+        }
+        Err(e) => return Err(e),
+    };
+    savepoint.commit().await?;
+    Ok(rsvp)
+}
+
+/// the reservation which a change is refused for, to explain why
+pub(crate) async fn find_reservation(
+    conn: &mut PgConnection,
+    id: ReservationId,
+) -> Result<abi::Reservation, Error> {
+    let sql = "SELECT * FROM rsvt.reservations WHERE id = $1 AND deleted_at IS NULL";
+    let rsvp = sqlx::query_as(sql)
+        .bind(id)
+        .fetch_one(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(rsvp)
+}
+
+/// child span of the rpc for every sql statement
+pub(crate) fn sql_span(statement: &str) -> Span {
+    info_span!(
+        "sql",
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.statement = statement,
+    )
 }
 
 fn str_to_option(s: &str) -> Option<&str> {
@@ -199,6 +606,117 @@ fn str_to_option(s: &str) -> Option<&str> {
     }
 }
 
+/// the existing reservation and the nearest free windows of the resource
+async fn find_conflict(
+    conn: &mut PgConnection,
+    rsvp: &abi::Reservation,
+    wanted: Range<DateTime<Utc>>,
+    buffer: Buffer,
+    rules: &BookingRules,
+) -> Result<Option<ReservationConflict>, Error> {
+    let padded = pad(wanted.clone(), buffer);
+    let sql = "SELECT * FROM rsvt.reservations
+        WHERE resource_id = $1 AND bperiod && $2 AND rstatus NOT IN ('blocked', 'rejected')
+        ORDER BY lower(rperiod) LIMIT 1";
+    let existing: Option<abi::Reservation> = sqlx::query_as(sql)
+        .bind(&rsvp.resource_id)
+        .bind(PgRange::from(padded.clone()))
+        .fetch_optional(&mut *conn)
+        .instrument(sql_span(sql))
+        .await?;
+    let mut conflict = match existing {
+        Some(existing) => ReservationConflict::new(rsvp, &existing),
+        None => return Ok(None),
+    };
+
+    // the gaps between the padded periods must hold the padded window, and the
+    // alternatives could not start in the past
+    let now = Utc::now();
+    let (before, after) = buffer_durations(buffer);
+    let days = chrono::Duration::days(ALTERNATIVE_HORIZON_DAYS);
+    let horizon = (wanted.start - days).max(now - before)..(wanted.end + days);
+    if horizon.start >= horizon.end {
+        return Ok(Some(conflict));
+    }
+    let sql = "SELECT lower(bperiod), upper(bperiod) FROM rsvt.reservations
+        WHERE resource_id = $1 AND bperiod && $2 AND rstatus NOT IN ('blocked', 'rejected')
+        ORDER BY lower(bperiod)";
+    let busy: Vec<(DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(sql)
+        .bind(&rsvp.resource_id)
+        .bind(PgRange::from(horizon.clone()))
+        .fetch_all(&mut *conn)
+        .instrument(sql_span(sql))
+        .await?;
+    let unpad = |window: Range<DateTime<Utc>>| (window.start + before)..(window.end - after);
+    let allowed = |window: &Range<DateTime<Utc>>| {
+        let window = unpad(window.clone());
+        rules
+            .check(&rsvp.resource_id, window.start, window.end, now)
+            .is_ok()
+    };
+    conflict.alternatives = free_windows(&busy, horizon, padded, MAX_ALTERNATIVES, allowed)
+        .into_iter()
+        .map(|window| {
+            let window = unpad(window);
+            ReservationWindow {
+                rid: rsvp.resource_id.clone(),
+                start: window.start,
+                end: window.end,
+            }
+        })
+        .collect();
+    Ok(Some(conflict))
+}
+
+/// the window with the turnover time before and after it
+pub(crate) fn pad(window: Range<DateTime<Utc>>, buffer: Buffer) -> Range<DateTime<Utc>> {
+    let (before, after) = buffer_durations(buffer);
+    (window.start - before)..(window.end + after)
+}
+
+fn buffer_durations(buffer: Buffer) -> (chrono::Duration, chrono::Duration) {
+    (
+        chrono::Duration::seconds(buffer.before as i64),
+        chrono::Duration::seconds(buffer.after as i64),
+    )
+}
+
+/// windows with the same duration as `wanted` in the gaps between the busy periods,
+/// at most one per gap and only the `allowed` ones, the nearest `limit` windows to `wanted`
+/// are returned in time order
+fn free_windows(
+    busy: &[(DateTime<Utc>, DateTime<Utc>)],
+    horizon: Range<DateTime<Utc>>,
+    wanted: Range<DateTime<Utc>>,
+    limit: usize,
+    allowed: impl Fn(&Range<DateTime<Utc>>) -> bool,
+) -> Vec<Range<DateTime<Utc>>> {
+    let duration = wanted.end - wanted.start;
+    let mut gaps = vec![];
+    let mut cursor = horizon.start;
+    for (start, end) in busy {
+        if *start > cursor {
+            gaps.push(cursor..*start);
+        }
+        cursor = cursor.max(*end);
+    }
+    gaps.push(cursor..horizon.end);
+
+    let mut windows: Vec<_> = gaps
+        .into_iter()
+        .filter(|gap| gap.end - gap.start >= duration)
+        .map(|gap| {
+            let start = wanted.start.clamp(gap.start, gap.end - duration);
+            start..start + duration
+        })
+        .filter(|window| allowed(window))
+        .collect();
+    windows.sort_by_key(|w| (w.start - wanted.start).num_seconds().abs());
+    windows.truncate(limit);
+    windows.sort_by_key(|w| w.start);
+    windows
+}
+
 fn string_to_option(s: &str) -> Option<String> {
     if s.is_empty() {
         None
@@ -209,11 +727,8 @@ fn string_to_option(s: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use abi::{
-        Reservation, ReservationConflict, ReservationConflictInfo, ReservationFilterBuilder,
-        ReservationQueryBuilder, ReservationWindow,
-    };
-    use chrono::FixedOffset;
+    use abi::{BookingRules, Reservation, ReservationFilterBuilder, ReservationQueryBuilder};
+    use chrono::{FixedOffset, Timelike};
     use prost_types::Timestamp;
     use sqlx::PgPool;
 
@@ -243,34 +758,297 @@ mod tests {
         let rsvp1 = Reservation::new_pending(
             "tosei",
             "ocean roon-745",
-            "2022-11-01T15:00:00+0800".parse().unwrap(),
-            "2022-11-07T12:00:00+0800".parse().unwrap(),
+            "2030-11-01T15:00:00+0800".parse().unwrap(),
+            "2030-11-07T12:00:00+0800".parse().unwrap(),
             "please check the room for me",
         );
 
         let rsvp2 = Reservation::new_pending(
             "wxy",
             "ocean roon-745",
-            "2022-11-04T15:00:00+0800".parse().unwrap(),
-            "2022-11-08T12:00:00+0800".parse().unwrap(),
+            "2030-11-04T15:00:00+0800".parse().unwrap(),
+            "2030-11-08T12:00:00+0800".parse().unwrap(),
             "love this room",
         );
 
-        let _rsvp1 = order_manage.create_order(rsvp1).await.unwrap();
+        let rsvp1 = order_manage.create_order(rsvp1).await.unwrap();
         let error_rsvp2: abi::Error = order_manage.create_order(rsvp2).await.unwrap_err();
-        let info = ReservationConflictInfo::Parsed(ReservationConflict {
+        let info = ReservationConflictInfo::Parsed(Box::new(ReservationConflict {
             new: ReservationWindow {
                 rid: "ocean roon-745".to_string(),
-                start: "2022-11-04T15:00:00+0800".parse().unwrap(),
-                end: "2022-11-08T12:00:00+0800".parse().unwrap(),
+                start: "2030-11-04T15:00:00+0800".parse().unwrap(),
+                end: "2030-11-08T12:00:00+0800".parse().unwrap(),
             },
             old: ReservationWindow {
                 rid: "ocean roon-745".to_string(),
-                start: "2022-11-01T15:00:00+0800".parse().unwrap(),
-                end: "2022-11-07T12:00:00+0800".parse().unwrap(),
+                start: "2030-11-01T15:00:00+0800".parse().unwrap(),
+                end: "2030-11-07T12:00:00+0800".parse().unwrap(),
             },
-        });
+            conflicting_id: rsvp1.id,
+            conflicting_user_id: "tosei".to_string(),
+            conflicting_status: ReservationStatus::Pending,
+            buffer: false,
+            // the nearest windows before and after the existing reservation
+            alternatives: vec![
+                ReservationWindow {
+                    rid: "ocean roon-745".to_string(),
+                    start: "2030-10-28T18:00:00+0800".parse().unwrap(),
+                    end: "2030-11-01T15:00:00+0800".parse().unwrap(),
+                },
+                ReservationWindow {
+                    rid: "ocean roon-745".to_string(),
+                    start: "2030-11-07T12:00:00+0800".parse().unwrap(),
+                    end: "2030-11-11T09:00:00+0800".parse().unwrap(),
+                },
+            ],
+            max_end: None,
+        }));
         assert_eq!(error_rsvp2, abi::Error::ConfilictReservation(info));
+        // the conflicts are counted by resource for the metrics
+        let conflicts = order_manage.conflicts();
+        assert_eq!(Some(&1), conflicts.get("ocean roon-745"));
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn booking_rules_should_be_enforced_by_resource() {
+        let rules = RuleConfig {
+            default: BookingRules::default(),
+            resources: [(
+                "meeting-room-*".to_string(),
+                BookingRules {
+                    max_duration: Some(8 * 3600),
+                    min_lead_time: Some(0),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_rules(rules);
+        let start = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        let month = |rid: &str| {
+            Reservation::new_pending("tosei", rid, start, start + chrono::Duration::days(30), "")
+        };
+
+        let err = manager
+            .create_order(month("meeting-room-1"))
+            .await
+            .unwrap_err();
+        assert_eq!(Error::DurationTooLong(8 * 3600), err);
+        // the other resources are not restricted
+        assert!(manager.create_order(month("room-1")).await.is_ok());
+
+        let past = Reservation::new_pending(
+            "tosei",
+            "meeting-room-1",
+            start - chrono::Duration::hours(2),
+            start - chrono::Duration::hours(1),
+            "",
+        );
+        let err = manager.create_order(past).await.unwrap_err();
+        assert_eq!(Error::LeadTimeTooShort(0), err);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn buffer_should_be_kept_after_reservation() {
+        let buffers = BufferConfig {
+            default: Buffer::default(),
+            resources: [(
+                "vehicle-*".to_string(),
+                Buffer {
+                    before: 0,
+                    after: 3600,
+                },
+            )]
+            .into(),
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_buffers(buffers);
+        let t = |s: &str| s.parse::<DateTime<FixedOffset>>().unwrap();
+        let rsvp = Reservation::new_pending(
+            "tosei",
+            "vehicle-1",
+            t("2030-01-01T10:00:00Z"),
+            t("2030-01-01T12:00:00Z"),
+            "",
+        );
+        let rsvp = manager.create_order(rsvp).await.unwrap();
+        // the visible period is not padded
+        let got = manager.get_reservation(rsvp.id).await.unwrap();
+        assert_eq!(rsvp.end_time, got.end_time);
+
+        let next = Reservation::new_pending(
+            "wxy",
+            "vehicle-1",
+            t("2030-01-01T12:30:00Z"),
+            t("2030-01-01T13:00:00Z"),
+            "",
+        );
+        let err = manager.create_order(next).await.unwrap_err();
+        let conflict = match err {
+            Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict)) => conflict,
+            _ => panic!("expect conflict, got {err:?}"),
+        };
+        assert!(conflict.buffer);
+        assert_eq!(rsvp.id, conflict.conflicting_id);
+        // the alternatives leave the buffer time of the new window free as well
+        let alternatives: Vec<_> = conflict
+            .alternatives
+            .iter()
+            .map(|w| (w.start.to_rfc3339(), w.end.to_rfc3339()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "2030-01-01T08:30:00+00:00".to_string(),
+                    "2030-01-01T09:00:00+00:00".to_string()
+                ),
+                (
+                    "2030-01-01T13:00:00+00:00".to_string(),
+                    "2030-01-01T13:30:00+00:00".to_string()
+                ),
+            ],
+            alternatives
+        );
+
+        let next = Reservation::new_pending(
+            "wxy",
+            "vehicle-1",
+            t("2030-01-01T13:00:00Z"),
+            t("2030-01-01T13:30:00Z"),
+            "",
+        );
+        assert!(manager.create_order(next).await.is_ok());
+        // other resources have no buffer
+        let car = |start, end| Reservation::new_pending("wxy", "car-1", t(start), t(end), "");
+        let first = car("2030-01-01T10:00:00Z", "2030-01-01T12:00:00Z");
+        manager.create_order(first).await.unwrap();
+        let second = car("2030-01-01T12:00:00Z", "2030-01-01T13:00:00Z");
+        assert!(manager.create_order(second).await.is_ok());
+    }
+
+    #[test]
+    fn free_windows_should_be_the_nearest_gaps() {
+        let t = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let busy = [
+            (t("2023-01-01T08:00:00Z"), t("2023-01-01T10:00:00Z")),
+            (t("2023-01-01T10:00:00Z"), t("2023-01-01T11:00:00Z")),
+            (t("2023-01-01T11:30:00Z"), t("2023-01-01T14:00:00Z")),
+            (t("2023-01-01T15:00:00Z"), t("2023-01-01T16:00:00Z")),
+        ];
+        let horizon = t("2023-01-01T00:00:00Z")..t("2023-01-02T00:00:00Z");
+        let wanted = t("2023-01-01T09:00:00Z")..t("2023-01-01T10:00:00Z");
+
+        // the gap between 11:00 and 11:30 is too short
+        let windows = free_windows(&busy, horizon.clone(), wanted.clone(), 2, |_| true);
+        assert_eq!(
+            vec![
+                t("2023-01-01T07:00:00Z")..t("2023-01-01T08:00:00Z"),
+                t("2023-01-01T14:00:00Z")..t("2023-01-01T15:00:00Z"),
+            ],
+            windows
+        );
+
+        let windows = free_windows(&busy, horizon.clone(), wanted.clone(), 5, |_| true);
+        assert_eq!(3, windows.len());
+        assert_eq!(
+            t("2023-01-01T16:00:00Z")..t("2023-01-01T17:00:00Z"),
+            windows[2]
+        );
+
+        // the rejected windows are not counted in the limit
+        let windows = free_windows(&busy, horizon, wanted, 2, |w| w.start.hour() >= 12);
+        assert_eq!(
+            vec![
+                t("2023-01-01T14:00:00Z")..t("2023-01-01T15:00:00Z"),
+                t("2023-01-01T16:00:00Z")..t("2023-01-01T17:00:00Z"),
+            ],
+            windows
+        );
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn conflict_should_be_found_for_any_resource_id() {
+        let order_manage = OrderManager::new(migrated_pool.clone());
+        let rid = "海景房, room 745 (north)";
+        let rsvp1 = Reservation::new_pending(
+            "tosei",
+            rid,
+            "2030-11-01T15:00:00+0800".parse().unwrap(),
+            "2030-11-07T12:00:00+0800".parse().unwrap(),
+            "",
+        );
+        let rsvp1 = order_manage.create_order(rsvp1).await.unwrap();
+        let rsvp1 = order_manage.change_status(rsvp1.id).await.unwrap();
+
+        let rsvp2 = Reservation::new_pending(
+            "wxy",
+            rid,
+            "2030-11-06T15:00:00+0800".parse().unwrap(),
+            "2030-11-08T12:00:00+0800".parse().unwrap(),
+            "",
+        );
+        let err = order_manage.create_order(rsvp2.clone()).await.unwrap_err();
+        let mut expected = ReservationConflict::new(&rsvp2, &rsvp1);
+        expected.alternatives = vec![
+            ReservationWindow {
+                rid: rid.to_string(),
+                start: "2030-10-30T18:00:00+0800".parse().unwrap(),
+                end: "2030-11-01T15:00:00+0800".parse().unwrap(),
+            },
+            ReservationWindow {
+                rid: rid.to_string(),
+                start: "2030-11-07T12:00:00+0800".parse().unwrap(),
+                end: "2030-11-09T09:00:00+0800".parse().unwrap(),
+            },
+        ];
+        let expected = ReservationConflictInfo::Parsed(Box::new(expected));
+        assert_eq!(abi::Error::ConfilictReservation(expected), err);
+
+        // the failed insert does not leave anything behind
+        let query = ReservationQueryBuilder::default()
+            .user_id("wxy")
+            .status(ReservationStatus::Pending)
+            .start("2030-11-01T00:00:00Z".parse::<Timestamp>().unwrap())
+            .end("2030-11-30T00:00:00Z".parse::<Timestamp>().unwrap())
+            .build()
+            .unwrap();
+        let mut rx = order_manage.query_reservations(query).await;
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn alternatives_should_be_after_now_and_follow_the_rules() {
+        let rules = BookingRules {
+            min_lead_time: Some(900),
+            ..Default::default()
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_rules(RuleConfig {
+            resources: [("room-1".to_string(), rules)].into(),
+            ..Default::default()
+        });
+        let now = Utc::now().with_nanosecond(0).unwrap();
+        let at = |minutes| now + chrono::Duration::minutes(minutes);
+        let alternatives = |e: Error| match e {
+            Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict)) => conflict
+                .alternatives
+                .into_iter()
+                .map(|w| w.start..w.end)
+                .collect::<Vec<_>>(),
+            e => panic!("unexpected error: {:?}", e),
+        };
+
+        // the window before the existing one starts too soon for the lead time
+        let rsvp = Reservation::new_pending("tosei", "room-1", at(70).into(), at(130).into(), "");
+        manager.create_order(rsvp).await.unwrap();
+        let rsvp = Reservation::new_pending("wxy", "room-1", at(80).into(), at(140).into(), "");
+        let e = manager.create_order(rsvp).await.unwrap_err();
+        assert_eq!(vec![at(130)..at(190)], alternatives(e));
+
+        // the gap before the existing one is in the past
+        let rsvp = Reservation::new_pending("tosei", "room-2", at(10).into(), at(70).into(), "");
+        manager.create_order(rsvp).await.unwrap();
+        let rsvp = Reservation::new_pending("wxy", "room-2", at(20).into(), at(80).into(), "");
+        let e = manager.create_order(rsvp).await.unwrap_err();
+        assert_eq!(vec![at(70)..at(130)], alternatives(e));
     }
 
     #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
@@ -340,7 +1118,17 @@ mod tests {
         let rsvp = order_manage.create_order(rsvp).await.unwrap();
         order_manage.cancel_reservation(rsvp.id).await.unwrap();
         let get_rsvp_info = order_manage.get_reservation(rsvp.id).await.unwrap();
-        assert_eq!(get_rsvp_info.status, rsvp.status);
+        assert_eq!(ReservationStatus::Cancelled as i32, get_rsvp_info.status);
+        // the cancelled one could not be cancelled again
+        let err = order_manage.cancel_reservation(rsvp.id).await.unwrap_err();
+        assert_eq!(
+            Error::InvalidStatus(ReservationStatus::Cancelled as i32),
+            err
+        );
+
+        // the cancelled reservation does not occupy the window
+        let rsvp = Reservation::new_pending("wxy", "room-test-1", start, end, "");
+        assert!(order_manage.create_order(rsvp).await.is_ok());
     }
 
     #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
@@ -410,6 +1198,70 @@ mod tests {
         assert_eq!(None, rx.recv().await);
     }
 
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn reservations_should_be_local_to_their_timezone() {
+        let tokyo = BookingRules {
+            timezone: chrono_tz::Tz::Asia__Tokyo,
+            ..Default::default()
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_rules(RuleConfig {
+            resources: HashMap::from([("room-tokyo".to_string(), tokyo)]),
+            ..Default::default()
+        });
+        let rsvp = |start: &str, end: &str, timezone: &str| Reservation {
+            timezone: timezone.to_string(),
+            ..Reservation::new_pending(
+                "tosei",
+                "room-tokyo",
+                start.parse().unwrap(),
+                end.parse().unwrap(),
+                "",
+            )
+        };
+
+        // the timezone of the resource is the default one
+        let first = rsvp("2030-01-01T01:00:00Z", "2030-01-01T03:00:00Z", "");
+        let first = manager.create_order(first).await.unwrap();
+        assert_eq!("Asia/Tokyo", first.timezone);
+        assert_eq!("2030-01-01T10:00:00+09:00", first.local_start);
+        assert_eq!("2030-01-01T12:00:00+09:00", first.local_end);
+        let second = rsvp(
+            "2030-01-02T01:00:00Z",
+            "2030-01-02T03:00:00Z",
+            "America/New_York",
+        );
+        let second = manager.create_order(second).await.unwrap();
+        assert_eq!("2030-01-01T20:00:00-05:00", second.local_start);
+        assert_eq!(second, manager.get_reservation(second.id).await.unwrap());
+
+        let invalid = rsvp("2030-01-03T01:00:00Z", "2030-01-03T03:00:00Z", "Asia/Osaka");
+        let err = manager.create_order(invalid).await.unwrap_err();
+        assert_eq!(Error::InvalidTimezone("Asia/Osaka".to_string()), err);
+
+        // the first of january in tokyo holds the first one only
+        let query = ReservationQueryBuilder::default()
+            .status(ReservationStatus::Pending)
+            .start("2030-01-01T05:00:00Z".parse::<Timestamp>().unwrap())
+            .end("2030-01-01T05:00:00Z".parse::<Timestamp>().unwrap())
+            .period("day")
+            .timezone("Asia/Tokyo")
+            .build()
+            .unwrap();
+        let mut rx = manager.query_reservations(query.clone()).await;
+        assert_eq!(Some(Ok(first)), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+
+        let query = ReservationQuery {
+            period: "month".to_string(),
+            ..query
+        };
+        let mut rx = manager.query_reservations(query).await;
+        assert_eq!(
+            Some(Err(Error::InvalidPeriod("month".to_string()))),
+            rx.recv().await
+        );
+    }
+
     #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
     async fn filter_reservations_should_be_work() {
         let (rsvp, manager) = make_alice_reservation(migrated_pool.clone()).await;
@@ -426,6 +1278,72 @@ mod tests {
         assert_eq!(rsvp, rsvps[0]);
         assert_eq!(1, filter_page.prev.unwrap());
         assert_eq!(1, filter_page.next.unwrap());
+
+        // the page after the last one is empty
+        let filter = ReservationFilterBuilder::default()
+            .status(ReservationStatus::Pending)
+            .cursor(filter_page.next.unwrap())
+            .build()
+            .unwrap();
+        let (filter_page, rsvps) = manager.filter_reservations(filter).await.unwrap();
+        assert!(rsvps.is_empty());
+        assert_eq!(None, filter_page.next);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn listen_reservations_should_be_work() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let mut rx = manager.listen().await;
+        // wait for the listener to subscribe the channel
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let (rsvp, manager) = make_alice_reservation(migrated_pool.clone()).await;
+        assert_eq!(Some(Ok(rsvp.clone())), rx.recv().await);
+
+        let rsvp = manager.change_status(rsvp.id).await.unwrap();
+        assert_eq!(Some(Ok(rsvp)), rx.recv().await);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn listen_should_send_the_change_committed_after_a_newer_one() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let mut rx = manager.listen().await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        // the older transaction takes the smaller change id, but commits after the newer one
+        let rsvp = |rid: &str| {
+            Reservation::new_pending(
+                "tosei",
+                rid,
+                "2030-01-01T10:00:00Z".parse().unwrap(),
+                "2030-01-01T12:00:00Z".parse().unwrap(),
+                "",
+            )
+        };
+        let mut older = manager.begin().await.unwrap();
+        let first = insert_reservation(
+            &mut older,
+            rsvp("room-1"),
+            Buffer::default(),
+            &BookingRules::default(),
+        )
+        .await
+        .unwrap();
+        let mut newer = manager.begin().await.unwrap();
+        let second = insert_reservation(
+            &mut newer,
+            rsvp("room-2"),
+            Buffer::default(),
+            &BookingRules::default(),
+        )
+        .await
+        .unwrap();
+        newer.commit().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        older.commit().await.unwrap();
+
+        assert_eq!(Some(Ok(first)), rx.recv().await);
+        assert_eq!(Some(Ok(second)), rx.recv().await);
     }
 
     async fn make_alice_reservation(pool: PgPool) -> (Reservation, OrderManager) {
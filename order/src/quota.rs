@@ -0,0 +1,342 @@
+use crate::{manager::sql_span, OrderManager, Quota};
+use abi::{convert_to_utc_time, Error, QuotaConfig, QuotaUsage};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use sqlx::{postgres::types::PgRange, PgConnection};
+use std::ops::Range;
+use tracing::Instrument;
+
+#[async_trait]
+impl Quota for OrderManager {
+    async fn quota(&self, user_id: &str) -> Result<Vec<QuotaUsage>, Error> {
+        if user_id.is_empty() {
+            return Err(Error::InvalidUserId(user_id.to_string()));
+        }
+
+        let mut conn = self.begin().await?;
+        let now = Utc::now();
+        let mut quotas = vec![];
+        if let Some(limit) = self.quotas().max_active {
+            let usage = active_count(&mut conn, user_id).await?;
+            quotas.push(quota_usage("active".to_string(), limit, usage));
+        }
+        if let Some(limit) = self.quotas().max_concurrent {
+            let usage =
+                concurrent_count(&mut conn, user_id, now..now + Duration::seconds(1)).await?;
+            quotas.push(quota_usage("concurrent".to_string(), limit, usage));
+        }
+        let mut weekly: Vec<_> = self.quotas().weekly.iter().collect();
+        weekly.sort();
+        for (pattern, limit) in weekly {
+            let usage = weekly_usage(&mut conn, user_id, pattern, week_of(now)).await?;
+            quotas.push(quota_usage(format!("weekly:{pattern}"), *limit, usage));
+        }
+        Ok(quotas)
+    }
+}
+
+/// reject the reservation if it would exceed a quota of its user, must be called in the
+/// transaction which inserts the reservation
+pub(crate) async fn check_quota(
+    conn: &mut PgConnection,
+    quotas: &QuotaConfig,
+    rsvp: &abi::Reservation,
+) -> Result<(), Error> {
+    if quotas.is_empty() {
+        return Ok(());
+    }
+
+    // the bookings of a user are serialized until the transaction ends, so two concurrent
+    // ones could not both see the usage below the limit
+    let sql = "SELECT pg_advisory_xact_lock(hashtextextended($1, 0))";
+    sqlx::query(sql)
+        .bind(&rsvp.user_id)
+        .execute(&mut *conn)
+        .instrument(sql_span(sql))
+        .await?;
+
+    let user_id = rsvp.user_id.as_str();
+    let window = convert_to_utc_time(rsvp.start_time.as_ref().unwrap())
+        ..convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+    if let Some(limit) = quotas.max_active {
+        let usage = active_count(conn, user_id).await?;
+        if usage >= limit {
+            return Err(exceeded("active", limit, usage));
+        }
+    }
+    if let Some(limit) = quotas.max_concurrent {
+        let usage = concurrent_count(conn, user_id, window.clone()).await?;
+        if usage >= limit {
+            return Err(exceeded("concurrent", limit, usage));
+        }
+    }
+    if let Some((pattern, limit)) = quotas.weekly_for_resource(&rsvp.resource_id) {
+        // a reservation across the weeks counts in each of them
+        let mut week = week_of(window.start);
+        while week.start < window.end {
+            let booked = (window.end.min(week.end) - window.start.max(week.start)).num_seconds();
+            let usage = weekly_usage(conn, user_id, pattern, week.clone()).await?;
+            if usage + booked as u64 > limit {
+                return Err(exceeded(&format!("weekly:{pattern}"), limit, usage));
+            }
+            week = week.end..week.end + Duration::weeks(1);
+        }
+    }
+    Ok(())
+}
+
+/// reservations of the user which are not cancelled and not ended yet
+async fn active_count(conn: &mut PgConnection, user_id: &str) -> Result<u64, Error> {
+    let sql = "SELECT COUNT(*) FROM rsvt.reservations WHERE user_id = $1
+        AND rstatus IN ('pending', 'confirmed', 'held') AND upper(rperiod) > now()";
+    let count: i64 = sqlx::query_scalar(sql)
+        .bind(user_id)
+        .fetch_one(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(count as u64)
+}
+
+/// active reservations of the user overlapping the window
+async fn concurrent_count(
+    conn: &mut PgConnection,
+    user_id: &str,
+    window: Range<DateTime<Utc>>,
+) -> Result<u64, Error> {
+    let sql = "SELECT COUNT(*) FROM rsvt.reservations WHERE user_id = $1
+        AND rstatus IN ('pending', 'confirmed', 'held') AND rperiod && $2";
+    let timespan: PgRange<DateTime<Utc>> = window.into();
+    let count: i64 = sqlx::query_scalar(sql)
+        .bind(user_id)
+        .bind(timespan)
+        .fetch_one(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(count as u64)
+}
+
+/// seconds booked by the user in the week on the resources matched by the pattern
+async fn weekly_usage(
+    conn: &mut PgConnection,
+    user_id: &str,
+    pattern: &str,
+    week: Range<DateTime<Utc>>,
+) -> Result<u64, Error> {
+    let (prefix, is_prefix) = match pattern.strip_suffix('*') {
+        Some(prefix) => (prefix, true),
+        None => (pattern, false),
+    };
+    let sql = "SELECT COALESCE(SUM(
+            EXTRACT(EPOCH FROM upper(rperiod * $3) - lower(rperiod * $3))), 0)::bigint
+        FROM rsvt.reservations WHERE user_id = $1
+        AND (resource_id = $2 OR ($4 AND starts_with(resource_id, $2)))
+        AND rstatus IN ('pending', 'confirmed', 'held') AND rperiod && $3";
+    let week: PgRange<DateTime<Utc>> = week.into();
+    let seconds: i64 = sqlx::query_scalar(sql)
+        .bind(user_id)
+        .bind(prefix)
+        .bind(week)
+        .bind(is_prefix)
+        .fetch_one(conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(seconds as u64)
+}
+
+/// the week from monday 00:00 in UTC which the time is in
+fn week_of(time: DateTime<Utc>) -> Range<DateTime<Utc>> {
+    let date = time.date_naive();
+    let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    let start = Utc.from_utc_datetime(&monday.and_hms_opt(0, 0, 0).unwrap());
+    start..start + Duration::weeks(1)
+}
+
+fn exceeded(quota: &str, limit: u64, usage: u64) -> Error {
+    Error::QuotaExceeded {
+        quota: quota.to_string(),
+        limit,
+        usage,
+    }
+}
+
+fn quota_usage(name: String, limit: u64, usage: u64) -> QuotaUsage {
+    QuotaUsage {
+        name,
+        limit,
+        usage,
+        remaining: limit.saturating_sub(usage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+    use abi::Reservation;
+    use std::collections::HashMap;
+
+    fn rsvp(uid: &str, rid: &str, start: &str, end: &str) -> Reservation {
+        Reservation::new_pending(uid, rid, start.parse().unwrap(), end.parse().unwrap(), "")
+    }
+
+    #[test]
+    fn week_should_start_on_monday() {
+        let week = week_of("2030-01-02T10:00:00Z".parse().unwrap());
+        assert_eq!("2029-12-31T00:00:00+00:00", week.start.to_rfc3339());
+        assert_eq!("2030-01-07T00:00:00+00:00", week.end.to_rfc3339());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn weekly_quota_should_count_the_resources_with_the_prefix() {
+        let quotas = QuotaConfig {
+            weekly: HashMap::from([("meeting-room-*".to_string(), 3 * 3600)]),
+            ..Default::default()
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_quotas(quotas);
+        let book = |uid, rid, start, end| manager.create_order(rsvp(uid, rid, start, end));
+
+        book(
+            "tosei",
+            "meeting-room-1",
+            "2030-01-01T09:00:00Z",
+            "2030-01-01T11:00:00Z",
+        )
+        .await
+        .unwrap();
+        book(
+            "tosei",
+            "meeting-room-2",
+            "2030-01-02T09:00:00Z",
+            "2030-01-02T10:00:00Z",
+        )
+        .await
+        .unwrap();
+        let err = book(
+            "tosei",
+            "meeting-room-1",
+            "2030-01-03T09:00:00Z",
+            "2030-01-03T09:30:00Z",
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(exceeded("weekly:meeting-room-*", 3 * 3600, 3 * 3600), err);
+
+        // other users, resources and weeks are not counted
+        assert!(book(
+            "wxy",
+            "meeting-room-1",
+            "2030-01-03T09:00:00Z",
+            "2030-01-03T10:00:00Z"
+        )
+        .await
+        .is_ok());
+        assert!(book(
+            "tosei",
+            "vehicle-1",
+            "2030-01-03T09:00:00Z",
+            "2030-01-03T10:00:00Z"
+        )
+        .await
+        .is_ok());
+        assert!(book(
+            "tosei",
+            "meeting-room-1",
+            "2030-01-07T09:00:00Z",
+            "2030-01-07T10:00:00Z"
+        )
+        .await
+        .is_ok());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn active_and_concurrent_quotas_should_be_enforced() {
+        let quotas = QuotaConfig {
+            max_active: Some(2),
+            max_concurrent: Some(1),
+            ..Default::default()
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_quotas(quotas);
+        let first = manager
+            .create_order(rsvp(
+                "tosei",
+                "room-1",
+                "2030-01-01T10:00:00Z",
+                "2030-01-01T12:00:00Z",
+            ))
+            .await
+            .unwrap();
+
+        let err = manager
+            .create_order(rsvp(
+                "tosei",
+                "room-2",
+                "2030-01-01T11:00:00Z",
+                "2030-01-01T13:00:00Z",
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(exceeded("concurrent", 1, 1), err);
+        manager
+            .create_order(rsvp(
+                "tosei",
+                "room-2",
+                "2030-01-01T12:00:00Z",
+                "2030-01-01T13:00:00Z",
+            ))
+            .await
+            .unwrap();
+
+        let third = || {
+            rsvp(
+                "tosei",
+                "room-3",
+                "2030-01-02T10:00:00Z",
+                "2030-01-02T12:00:00Z",
+            )
+        };
+        let err = manager.create_order(third()).await.unwrap_err();
+        assert_eq!(exceeded("active", 2, 2), err);
+
+        let quotas = manager.quota("tosei").await.unwrap();
+        assert_eq!(quota_usage("active".to_string(), 2, 2), quotas[0]);
+        assert_eq!(0, quotas[0].remaining);
+        assert_eq!(quota_usage("concurrent".to_string(), 1, 0), quotas[1]);
+
+        // the cancelled reservation is not counted
+        manager.cancel_reservation(first.id).await.unwrap();
+        assert!(manager.create_order(third()).await.is_ok());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn concurrent_bookings_should_not_bypass_the_quota() {
+        let quotas = QuotaConfig {
+            max_active: Some(1),
+            ..Default::default()
+        };
+        let manager = OrderManager::new(migrated_pool.clone()).with_quotas(quotas);
+        let tasks: Vec<_> = (0..5)
+            .map(|i| {
+                let manager = manager.clone();
+                let rid = format!("room-{i}");
+                tokio::spawn(async move {
+                    let rsvp = rsvp(
+                        "tosei",
+                        &rid,
+                        "2030-01-01T10:00:00Z",
+                        "2030-01-01T12:00:00Z",
+                    );
+                    manager.create_order(rsvp).await
+                })
+            })
+            .collect();
+
+        let mut created = 0;
+        for task in tasks {
+            match task.await.unwrap() {
+                Ok(_) => created += 1,
+                Err(err) => assert_eq!(exceeded("active", 1, 1), err),
+            }
+        }
+        assert_eq!(1, created);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{manager::sql_span, OrderManager, ReservationId, Retention};
+use abi::{convert_to_utc_time, Error};
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use tracing::{info, Instrument};
+
+/// the reservations archived in a sweep at most
+const ARCHIVE_BATCH: i64 = 1000;
+
+#[async_trait]
+impl Retention for OrderManager {
+    async fn delete_reservation(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
+        let mut tx = self.begin().await?;
+        // the deleted one does not hold its window any more
+        let sql = "UPDATE rsvt.reservations SET deleted_at = now(),
+                rstatus = CASE WHEN rstatus IN ('pending', 'confirmed', 'held')
+                    THEN 'blocked'::rsvt.reservation_status ELSE rstatus END
+            WHERE id = $1 AND deleted_at IS NULL RETURNING *";
+        let rsvp: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        self.offer_window(&mut tx, &rsvp).await?;
+        tx.commit().await?;
+        Ok(rsvp)
+    }
+
+    async fn purge_reservation(&self, id: ReservationId) -> Result<abi::Reservation, Error> {
+        let actor = self.check_admin()?;
+
+        let mut tx = self.begin().await?;
+        // the approvals and the hold are removed with it, the audit log is kept
+        let sql = "DELETE FROM rsvt.reservations WHERE id = $1 RETURNING *";
+        let rsvp: abi::Reservation = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        self.offer_window(&mut tx, &rsvp).await?;
+        tx.commit().await?;
+        info!("reservation {} is purged by {}", id, actor);
+        Ok(rsvp)
+    }
+
+    async fn archive_reservations(&self) -> Result<Vec<abi::Reservation>, Error> {
+        let max_age = match self.retention.max_age {
+            Some(max_age) => max_age,
+            None => return Ok(vec![]),
+        };
+
+        let mut tx = self.begin().await?;
+        let sql = "WITH archived AS (
+                DELETE FROM rsvt.reservations WHERE id IN (
+                    SELECT id FROM rsvt.reservations
+                    WHERE upper(rperiod) < now() - $1 * interval '1 second'
+                    ORDER BY id LIMIT $2 FOR UPDATE SKIP LOCKED
+                ) RETURNING *
+            )
+            INSERT INTO rsvt.reservations_archive
+                (id, user_id, rstatus, resource_id, rperiod, note, bperiod, deleted_at, tenant_id,
+                timezone, checked_in_at, checked_out_at)
+            SELECT id, user_id, rstatus, resource_id, rperiod, note, bperiod, deleted_at, tenant_id,
+                timezone, checked_in_at, checked_out_at
+            FROM archived RETURNING *";
+        let rsvps: Vec<abi::Reservation> = sqlx::query_as(sql)
+            .bind(max_age as f64)
+            .bind(ARCHIVE_BATCH)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(rsvps)
+    }
+}
+
+impl OrderManager {
+    /// the window of the removed reservation is offered to the waitlist
+    async fn offer_window(
+        &self,
+        conn: &mut PgConnection,
+        rsvp: &abi::Reservation,
+    ) -> Result<(), Error> {
+        let start = convert_to_utc_time(rsvp.start_time.as_ref().unwrap());
+        let end = convert_to_utc_time(rsvp.end_time.as_ref().unwrap());
+        self.promote_waitlist(conn, &rsvp.resource_id, start..end)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Actor, Audit, Order};
+    use abi::{AuthConfig, Reservation, ReservationStatus, ReservationUpdateType, RetentionConfig};
+    use std::time::Duration;
+
+    fn rsvp(uid: &str, start: &str, end: &str) -> Reservation {
+        Reservation::new_pending(
+            uid,
+            "room-1",
+            start.parse().unwrap(),
+            end.parse().unwrap(),
+            "",
+        )
+    }
+
+    fn manager(pool: sqlx::PgPool) -> OrderManager {
+        OrderManager::new(pool)
+            .with_auth(AuthConfig {
+                admins: vec!["admin".to_string()],
+            })
+            .with_retention(RetentionConfig {
+                max_age: Some(86400),
+                ..Default::default()
+            })
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn deleted_reservation_should_be_hidden_but_kept_for_audit() {
+        let manager = manager(migrated_pool.clone());
+        let window = || rsvp("tosei", "2030-01-01T10:00:00Z", "2030-01-01T12:00:00Z");
+        let created = manager.create_order(window()).await.unwrap();
+
+        let deleted = manager.delete_reservation(created.id).await.unwrap();
+        assert_eq!(ReservationStatus::Cancelled as i32, deleted.status);
+        assert_eq!(
+            Error::NotFound,
+            manager.get_reservation(created.id).await.unwrap_err()
+        );
+        let err = manager.delete_reservation(created.id).await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+        // cancelled is stored as blocked
+        let sql = "SELECT COUNT(*) FROM rsvt.filter('tosei', NULL, 'blocked')";
+        let count: i64 = sqlx::query_scalar(sql)
+            .fetch_one(&migrated_pool)
+            .await
+            .unwrap();
+        assert_eq!(0, count);
+
+        let history = manager.history(created.id).await.unwrap();
+        assert_eq!(ReservationUpdateType::Delete as i32, history[1].op);
+        // the window is freed
+        assert!(manager.create_order(window()).await.is_ok());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn only_admins_should_purge_reservations() {
+        let manager = manager(migrated_pool.clone());
+        let mut events = manager.listen_events().await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let created = manager
+            .create_order(rsvp(
+                "tosei",
+                "2030-01-01T10:00:00Z",
+                "2030-01-01T12:00:00Z",
+            ))
+            .await
+            .unwrap();
+
+        let err = Actor::verified("tosei", "")
+            .scope(manager.purge_reservation(created.id))
+            .await
+            .unwrap_err();
+        assert_eq!(Error::NotAdmin("tosei".to_string()), err);
+        // the user of the metadata is not verified, neither is a background job
+        let err = Actor::new("admin", "")
+            .scope(manager.purge_reservation(created.id))
+            .await
+            .unwrap_err();
+        assert_eq!(Error::Unauthenticated, err);
+        let err = manager.purge_reservation(created.id).await.unwrap_err();
+        assert_eq!(Error::Unauthenticated, err);
+        Actor::verified("admin", "")
+            .scope(manager.purge_reservation(created.id))
+            .await
+            .unwrap();
+
+        // the removed reservation is sent from its snapshot
+        events.recv().await.unwrap().unwrap();
+        let event = events.recv().await.unwrap().unwrap();
+        assert_eq!(ReservationUpdateType::Delete as i32, event.op);
+        assert_eq!(Some(created.clone()), event.reservation);
+
+        let history = manager.history(created.id).await.unwrap();
+        assert_eq!("admin", history[1].actor);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn old_reservations_should_be_archived() {
+        let manager = manager(migrated_pool.clone());
+        let old = manager
+            .create_order(rsvp(
+                "tosei",
+                "2020-01-01T10:00:00Z",
+                "2020-01-01T12:00:00Z",
+            ))
+            .await
+            .unwrap();
+        let new = manager
+            .create_order(rsvp(
+                "tosei",
+                "2030-01-01T10:00:00Z",
+                "2030-01-01T12:00:00Z",
+            ))
+            .await
+            .unwrap();
+
+        let archived = manager.archive_reservations().await.unwrap();
+        assert_eq!(vec![old.clone()], archived);
+        assert_eq!(
+            Error::NotFound,
+            manager.get_reservation(old.id).await.unwrap_err()
+        );
+        assert!(manager.get_reservation(new.id).await.is_ok());
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rsvt.reservations_archive")
+            .fetch_one(&migrated_pool)
+            .await
+            .unwrap();
+        assert_eq!(1, count);
+
+        // not archived without the max age
+        let manager = OrderManager::new(migrated_pool.clone());
+        assert!(manager.archive_reservations().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{Actor, OrderManager};
+use abi::{ApprovalConfig, BufferConfig, QuotaConfig, RuleConfig, TenantConfig};
+use std::{collections::HashMap, future::Future};
+
+tokio::task_local! {
+    static TENANT: Tenant;
+}
+
+/// the role which the row level security applies to, switched to in the transactions of a tenant
+pub(crate) const TENANT_ROLE: &str = "rsvt_tenant";
+
+/// the business unit of the current request, its queries see and write its own rows only
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tenant(String);
+
+impl Tenant {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// the queries of the future are isolated to the tenant
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        TENANT.scope(self, f).await
+    }
+
+    /// the tenant of the current task, none for the background jobs which see every tenant
+    pub fn current() -> Option<Tenant> {
+        TENANT.try_with(|tenant| tenant.clone()).ok()
+    }
+}
+
+/// spawn the task with the tenant and the actor of the current one, a spawned task does not
+/// inherit them, so its queries would not be isolated otherwise
+pub fn spawn_scoped<F>(f: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let actor = Actor::current();
+    let f = async move {
+        match actor {
+            Some(actor) => actor.scope(f).await,
+            None => f.await,
+        }
+    };
+    match Tenant::current() {
+        Some(tenant) => tokio::spawn(tenant.scope(f)),
+        None => tokio::spawn(f),
+    };
+}
+
+impl OrderManager {
+    /// the settings of the tenants which differ from the ones of the manager
+    pub fn with_tenants(mut self, tenants: HashMap<String, TenantConfig>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    pub(crate) fn buffers(&self) -> &BufferConfig {
+        self.overrides()
+            .and_then(|tenant| tenant.buffers.as_ref())
+            .unwrap_or(&self.buffers)
+    }
+
+    pub(crate) fn rules(&self) -> &RuleConfig {
+        self.overrides()
+            .and_then(|tenant| tenant.rules.as_ref())
+            .unwrap_or(&self.rules)
+    }
+
+    pub(crate) fn quotas(&self) -> &QuotaConfig {
+        self.overrides()
+            .and_then(|tenant| tenant.quotas.as_ref())
+            .unwrap_or(&self.quotas)
+    }
+
+    pub(crate) fn approvals(&self) -> &ApprovalConfig {
+        self.overrides()
+            .and_then(|tenant| tenant.approvals.as_ref())
+            .unwrap_or(&self.approvals)
+    }
+
+    fn overrides(&self) -> Option<&TenantConfig> {
+        TENANT
+            .try_with(|tenant| self.tenants.get(tenant.id()))
+            .ok()
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+    use abi::{Error, QuotaConfig, Reservation, ReservationFilterBuilder, ReservationStatus};
+
+    fn meeting(uid: &str) -> Reservation {
+        Reservation::new_pending(
+            uid,
+            "meeting room-1",
+            "2030-12-25T10:00:00-0700".parse().unwrap(),
+            "2030-12-25T12:00:00-0700".parse().unwrap(),
+            "standup",
+        )
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn tenants_should_not_see_the_reservations_of_each_other() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let acme = Tenant::new("acme");
+        let globex = Tenant::new("globex");
+
+        // the same resource of different tenants is not in conflict
+        let rsvp = acme
+            .clone()
+            .scope(manager.create_order(meeting("tosei")))
+            .await
+            .unwrap();
+        let other = globex
+            .clone()
+            .scope(manager.create_order(meeting("alice")))
+            .await
+            .unwrap();
+        let err = acme
+            .clone()
+            .scope(manager.create_order(meeting("bob")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ConfilictReservation(_)));
+
+        let err = globex
+            .clone()
+            .scope(manager.get_reservation(rsvp.id))
+            .await
+            .unwrap_err();
+        assert_eq!(Error::NotFound, err);
+        let found = acme
+            .clone()
+            .scope(manager.get_reservation(rsvp.id))
+            .await
+            .unwrap();
+        assert_eq!(rsvp, found);
+
+        let filter = ReservationFilterBuilder::default()
+            .status(ReservationStatus::Pending)
+            .build()
+            .unwrap();
+        let (_, rsvps) = globex
+            .scope(manager.filter_reservations(filter))
+            .await
+            .unwrap();
+        assert_eq!(vec![other], rsvps);
+    }
+
+    #[tokio::test]
+    async fn tenant_overrides_should_be_resolved() {
+        let manager = OrderManager::new(sqlx::PgPool::connect_lazy("postgres://").unwrap())
+            .with_quotas(QuotaConfig {
+                max_active: Some(10),
+                ..Default::default()
+            })
+            .with_tenants(HashMap::from([(
+                "acme".to_string(),
+                TenantConfig {
+                    quotas: Some(QuotaConfig {
+                        max_active: Some(1),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )]));
+        let max_active = || manager.quotas().max_active;
+        assert_eq!(Some(10), max_active());
+        assert_eq!(
+            Some(1),
+            Tenant::new("acme").scope(async { max_active() }).await
+        );
+        assert_eq!(
+            Some(10),
+            Tenant::new("globex").scope(async { max_active() }).await
+        );
+    }
+}
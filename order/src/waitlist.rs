@@ -0,0 +1,451 @@
+use crate::{
+    manager::{change_cursor, insert_reservation, pad, spawn_listener, sql_span, ChangeFeed},
+    quota::check_quota,
+    OrderManager, Waitlist,
+};
+use abi::{
+    convert_to_timestamp, convert_to_utc_time, BookingRules, Buffer, Error, QuotaConfig,
+    Reservation, ReservationStatus, Validator, WaitlistEntry, WaitlistMode,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::types::PgRange, PgConnection};
+use std::ops::Range;
+use tokio::sync::mpsc;
+use tracing::{info, Instrument};
+
+const WAITLIST_FEED: ChangeFeed = ChangeFeed {
+    channel: "waitlist_update",
+    changes_sql: concat!(
+        "SELECT ",
+        change_cursor!(),
+        ", w.* FROM rsvt.waitlist_changes c
+        JOIN rsvt.waitlist w ON w.id = c.waitlist_id
+        WHERE (c.txid, c.id) > ($1::bigint::text::xid8, $2) ORDER BY c.txid, c.id"
+    ),
+};
+
+#[async_trait]
+impl Waitlist for OrderManager {
+    async fn join_waitlist(&self, entry: WaitlistEntry) -> Result<WaitlistEntry, Error> {
+        entry.validate()?;
+
+        let window = window(&entry);
+        // a window the rules reject would never be booked for the entry
+        self.rules().for_resource(&entry.resource_id).check(
+            &entry.resource_id,
+            window.start,
+            window.end,
+            Utc::now(),
+        )?;
+        let expires_at = entry
+            .expires_at
+            .as_ref()
+            .map(convert_to_utc_time)
+            .unwrap_or(window.start);
+        let timespan: PgRange<DateTime<Utc>> = window.clone().into();
+
+        let mut tx = self.begin().await?;
+        let sql = "INSERT INTO rsvt.waitlist (user_id, resource_id, rperiod, expires_at)
+            VALUES ($1, $2, $3, $4) RETURNING id";
+        let id: i64 = sqlx::query_scalar(sql)
+            .bind(&entry.user_id)
+            .bind(&entry.resource_id)
+            .bind(timespan)
+            .bind(expires_at)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        // the window may have been freed before the user joins
+        self.promote_waitlist(&mut tx, &entry.resource_id, window)
+            .await?;
+
+        let sql = "SELECT * FROM rsvt.waitlist WHERE id = $1";
+        let entry = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(entry)
+    }
+
+    async fn leave_waitlist(&self, id: i64) -> Result<WaitlistEntry, Error> {
+        let sql = "UPDATE rsvt.waitlist SET wstatus = 'left'
+            WHERE id = $1 AND wstatus IN ('waiting', 'notified') RETURNING *";
+        let mut tx = self.begin().await?;
+        let entry = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(entry)
+    }
+
+    async fn list_waitlist(
+        &self,
+        resource_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<WaitlistEntry>, Error> {
+        let sql = "SELECT * FROM rsvt.waitlist WHERE wstatus IN ('waiting', 'notified')
+            AND ($1 = '' OR resource_id = $1) AND ($2 = '' OR user_id = $2) ORDER BY id";
+        let mut tx = self.begin().await?;
+        let entries = sqlx::query_as(sql)
+            .bind(resource_id)
+            .bind(user_id)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(entries)
+    }
+
+    async fn expire_waitlist(&self) -> Result<Vec<WaitlistEntry>, Error> {
+        let sql = "UPDATE rsvt.waitlist SET wstatus = 'expired'
+            WHERE wstatus IN ('waiting', 'notified') AND expires_at <= now() RETURNING *";
+        let mut tx = self.begin().await?;
+        let entries = sqlx::query_as(sql)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(entries)
+    }
+
+    /// subscribe the waitlist_update channel and send the changed entries
+    async fn listen_waitlist(&self) -> mpsc::Receiver<Result<WaitlistEntry, Error>> {
+        spawn_listener(self.clone(), &WAITLIST_FEED).await
+    }
+}
+
+impl OrderManager {
+    /// offer the freed window of the resource to the waiting entries in queue order
+    ///
+    /// an entry is served only when its whole window is free, so a later entry is
+    /// skipped if it overlaps the window of an entry served before it
+    pub(crate) async fn promote_waitlist(
+        &self,
+        conn: &mut PgConnection,
+        resource_id: &str,
+        freed: Range<DateTime<Utc>>,
+    ) -> Result<Vec<WaitlistEntry>, Error> {
+        let buffer = self.buffers().for_resource(resource_id);
+        let rules = self.rules().for_resource(resource_id);
+        let sql = "SELECT * FROM rsvt.waitlist w
+            WHERE w.resource_id = $1 AND w.rperiod && $2
+            AND w.wstatus = 'waiting' AND w.expires_at > now()
+            AND NOT EXISTS (
+                SELECT 1 FROM rsvt.reservations r WHERE r.resource_id = w.resource_id
+                AND r.bperiod && tstzrange(
+                    lower(w.rperiod) - $3 * interval '1 second',
+                    upper(w.rperiod) + $4 * interval '1 second'
+                )
+                AND r.rstatus NOT IN ('blocked', 'rejected')
+            )
+            ORDER BY w.id FOR UPDATE SKIP LOCKED";
+        let candidates: Vec<WaitlistEntry> = sqlx::query_as(sql)
+            .bind(resource_id)
+            .bind(PgRange::from(freed))
+            .bind(buffer.before as f64)
+            .bind(buffer.after as f64)
+            .fetch_all(&mut *conn)
+            .instrument(sql_span(sql))
+            .await?;
+
+        let mut served: Vec<WaitlistEntry> = vec![];
+        for entry in candidates {
+            let wanted = pad(window(&entry), buffer);
+            let overlapped = served.iter().any(|s| {
+                let window = pad(window(s), buffer);
+                window.start < wanted.end && wanted.start < window.end
+            });
+            if overlapped {
+                continue;
+            }
+
+            let entry = match self.waitlist_mode {
+                WaitlistMode::Reserve => {
+                    match reserve(conn, &entry, buffer, rules, self.quotas()).await? {
+                        Some((entry, rsvp)) => {
+                            self.request_first_approval(conn, &rsvp).await?;
+                            entry
+                        }
+                        None => continue,
+                    }
+                }
+                WaitlistMode::Notify => {
+                    let sql =
+                        "UPDATE rsvt.waitlist SET wstatus = 'notified' WHERE id = $1 RETURNING *";
+                    sqlx::query_as(sql)
+                        .bind(entry.id)
+                        .fetch_one(&mut *conn)
+                        .instrument(sql_span(sql))
+                        .await?
+                }
+            };
+            info!(
+                "waitlist entry {} of {} is {:?}",
+                entry.id, entry.user_id, self.waitlist_mode
+            );
+            served.push(entry);
+        }
+        Ok(served)
+    }
+}
+
+/// convert the entry into a pending reservation, None if the window is taken meanwhile or the
+/// user is over a quota, the entry keeps waiting in that case
+async fn reserve(
+    conn: &mut PgConnection,
+    entry: &WaitlistEntry,
+    buffer: Buffer,
+    rules: &BookingRules,
+    quotas: &QuotaConfig,
+) -> Result<Option<(WaitlistEntry, Reservation)>, Error> {
+    let window = window(entry);
+    let rsvp = Reservation {
+        user_id: entry.user_id.clone(),
+        resource_id: entry.resource_id.clone(),
+        start_time: Some(convert_to_timestamp(window.start)),
+        end_time: Some(convert_to_timestamp(window.end)),
+        status: ReservationStatus::Pending as i32,
+        note: format!("from waitlist #{}", entry.id),
+        timezone: rules.timezone.name().to_string(),
+        ..Default::default()
+    };
+    // the advisory lock of the user is held until the conversion is committed
+    match check_quota(conn, quotas, &rsvp).await {
+        Ok(()) => {}
+        Err(Error::QuotaExceeded { quota, .. }) => {
+            info!(
+                "waitlist entry {} of {} is over {}",
+                entry.id, entry.user_id, quota
+            );
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    }
+    let rsvp = match insert_reservation(conn, rsvp, buffer, rules).await {
+        Ok(rsvp) => rsvp,
+        Err(Error::ConfilictReservation(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let sql = "UPDATE rsvt.waitlist SET wstatus = 'fulfilled', reservation_id = $2
+        WHERE id = $1 RETURNING *";
+    let entry = sqlx::query_as(sql)
+        .bind(entry.id)
+        .bind(rsvp.id)
+        .fetch_one(&mut *conn)
+        .instrument(sql_span(sql))
+        .await?;
+    Ok(Some((entry, rsvp)))
+}
+
+fn window(entry: &WaitlistEntry) -> Range<DateTime<Utc>> {
+    let start = convert_to_utc_time(entry.start_time.as_ref().unwrap());
+    let end = convert_to_utc_time(entry.end_time.as_ref().unwrap());
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+    use abi::WaitlistStatus;
+
+    fn entry(uid: &str, start: &str, end: &str) -> WaitlistEntry {
+        WaitlistEntry::new_waiting(
+            uid,
+            "ocean room-745",
+            start.parse().unwrap(),
+            end.parse().unwrap(),
+        )
+    }
+
+    async fn booked(manager: &OrderManager) -> Reservation {
+        let rsvp = Reservation::new_pending(
+            "tosei",
+            "ocean room-745",
+            "2030-11-01T15:00:00+0800".parse().unwrap(),
+            "2030-11-07T12:00:00+0800".parse().unwrap(),
+            "",
+        );
+        manager.create_order(rsvp).await.unwrap()
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn cancellation_should_reserve_for_the_first_waiting_entry() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let rsvp = booked(&manager).await;
+
+        let first = entry(
+            "wxy",
+            "2030-11-02T15:00:00+0800",
+            "2030-11-04T12:00:00+0800",
+        );
+        let second = entry(
+            "alice",
+            "2030-11-03T15:00:00+0800",
+            "2030-11-05T12:00:00+0800",
+        );
+        // does not overlap the first one, so it is served too
+        let third = entry(
+            "bob",
+            "2030-11-06T15:00:00+0800",
+            "2030-11-07T12:00:00+0800",
+        );
+        let first = manager.join_waitlist(first).await.unwrap();
+        let second = manager.join_waitlist(second).await.unwrap();
+        let third = manager.join_waitlist(third).await.unwrap();
+        assert_eq!(WaitlistStatus::Waiting as i32, first.status);
+
+        let waiting = manager.list_waitlist("ocean room-745", "").await.unwrap();
+        assert_eq!(vec![first.clone(), second.clone(), third.clone()], waiting);
+
+        manager.cancel_reservation(rsvp.id).await.unwrap();
+
+        // bob is served as well because his window does not overlap the one of wxy
+        let waiting = manager.list_waitlist("ocean room-745", "").await.unwrap();
+        assert_eq!(vec![second.clone()], waiting);
+
+        let filter = abi::ReservationFilterBuilder::default()
+            .resource_id("ocean room-745")
+            .status(ReservationStatus::Pending)
+            .build()
+            .unwrap();
+        let (_, rsvps) = manager.filter_reservations(filter).await.unwrap();
+        let users: Vec<&str> = rsvps.iter().map(|r| r.user_id.as_str()).collect();
+        assert_eq!(vec!["wxy", "bob"], users);
+        assert_eq!(format!("from waitlist #{}", first.id), rsvps[0].note);
+
+        // fulfilled entry can not be left any more
+        let err = manager.leave_waitlist(first.id).await.unwrap_err();
+        assert_eq!(Error::NotFound, err);
+        let mut rx = manager.listen_waitlist().await;
+        // wait for the listener to subscribe the channel
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let left = manager.leave_waitlist(second.id).await.unwrap();
+        assert_eq!(WaitlistStatus::Left as i32, left.status);
+        assert_eq!(Some(Ok(left)), rx.recv().await);
+        assert!(manager.list_waitlist("", "").await.unwrap().is_empty());
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn entry_over_quota_should_be_skipped() {
+        let manager = OrderManager::new(migrated_pool.clone()).with_quotas(QuotaConfig {
+            max_active: Some(1),
+            ..Default::default()
+        });
+        let rsvp = booked(&manager).await;
+        let other = Reservation::new_pending(
+            "wxy",
+            "ocean room-746",
+            "2030-11-01T15:00:00+0800".parse().unwrap(),
+            "2030-11-02T12:00:00+0800".parse().unwrap(),
+            "",
+        );
+        manager.create_order(other).await.unwrap();
+
+        let first = entry(
+            "wxy",
+            "2030-11-02T15:00:00+0800",
+            "2030-11-04T12:00:00+0800",
+        );
+        let second = entry(
+            "alice",
+            "2030-11-03T15:00:00+0800",
+            "2030-11-05T12:00:00+0800",
+        );
+        let first = manager.join_waitlist(first).await.unwrap();
+        manager.join_waitlist(second).await.unwrap();
+
+        manager.cancel_reservation(rsvp.id).await.unwrap();
+
+        // wxy has an active reservation already, so the window goes to alice
+        let waiting = manager.list_waitlist("ocean room-745", "").await.unwrap();
+        assert_eq!(vec![first], waiting);
+        let filter = abi::ReservationFilterBuilder::default()
+            .resource_id("ocean room-745")
+            .status(ReservationStatus::Pending)
+            .build()
+            .unwrap();
+        let (_, rsvps) = manager.filter_reservations(filter).await.unwrap();
+        let users: Vec<&str> = rsvps.iter().map(|r| r.user_id.as_str()).collect();
+        assert_eq!(vec!["alice"], users);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn cancellation_should_notify_in_notify_mode() {
+        let manager =
+            OrderManager::new(migrated_pool.clone()).with_waitlist_mode(WaitlistMode::Notify);
+        let rsvp = booked(&manager).await;
+        let waiting = entry(
+            "wxy",
+            "2030-11-02T15:00:00+0800",
+            "2030-11-04T12:00:00+0800",
+        );
+        let waiting = manager.join_waitlist(waiting).await.unwrap();
+
+        manager.cancel_reservation(rsvp.id).await.unwrap();
+
+        let entries = manager.list_waitlist("", "wxy").await.unwrap();
+        assert_eq!(WaitlistStatus::Notified as i32, entries[0].status);
+        assert_eq!(waiting.id, entries[0].id);
+        assert_eq!(None, entries[0].reservation_id);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn cancelled_reservation_should_not_promote_again() {
+        let manager =
+            OrderManager::new(migrated_pool.clone()).with_waitlist_mode(WaitlistMode::Notify);
+        let rsvp = booked(&manager).await;
+        manager.cancel_reservation(rsvp.id).await.unwrap();
+        let rebooked = booked(&manager).await;
+        let waiting = entry(
+            "wxy",
+            "2030-11-02T15:00:00+0800",
+            "2030-11-04T12:00:00+0800",
+        );
+        let waiting = manager.join_waitlist(waiting).await.unwrap();
+
+        // the window is still held by the one booked again
+        let err = manager.cancel_reservation(rsvp.id).await.unwrap_err();
+        assert_eq!(
+            Error::InvalidStatus(ReservationStatus::Cancelled as i32),
+            err
+        );
+        let entries = manager.list_waitlist("", "wxy").await.unwrap();
+        assert_eq!(vec![waiting], entries);
+
+        manager.cancel_reservation(rebooked.id).await.unwrap();
+        let entries = manager.list_waitlist("", "wxy").await.unwrap();
+        assert_eq!(WaitlistStatus::Notified as i32, entries[0].status);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn expired_entries_should_be_removed() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        booked(&manager).await;
+        let mut expired = entry(
+            "wxy",
+            "2030-11-02T15:00:00+0800",
+            "2030-11-04T12:00:00+0800",
+        );
+        expired.expires_at = Some(abi::convert_to_timestamp(Utc::now()));
+        let expired = manager.join_waitlist(expired).await.unwrap();
+        let waiting = entry(
+            "alice",
+            "2030-11-02T15:00:00+0800",
+            "2030-11-04T12:00:00+0800",
+        );
+        let waiting = manager.join_waitlist(waiting).await.unwrap();
+
+        let entries = manager.expire_waitlist().await.unwrap();
+        assert_eq!(1, entries.len());
+        assert_eq!(expired.id, entries[0].id);
+        assert_eq!(WaitlistStatus::Expired as i32, entries[0].status);
+        assert_eq!(vec![waiting], manager.list_waitlist("", "").await.unwrap());
+    }
+}
@@ -0,0 +1,479 @@
+use crate::{
+    manager::{change_cursor, changed_reservation, sql_span},
+    OrderManager, Outbox,
+};
+use abi::{DeadLetter, Error, ListenResponse, ReservationUpdateType, Validator, Webhook};
+use async_trait::async_trait;
+use serde_json::json;
+use sqlx::{FromRow, Row};
+use std::time::Duration;
+use tracing::{info, Instrument};
+
+/// the changes fanned out in a sweep at most
+const FAN_OUT_BATCH: i64 = 500;
+
+/// a change queued for a webhook, posted to its url
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct Delivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub change_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub payload: String,
+    /// the failed attempts before
+    pub attempts: i32,
+}
+
+#[async_trait]
+impl Outbox for OrderManager {
+    async fn register_webhook(&self, webhook: Webhook) -> Result<Webhook, Error> {
+        webhook.validate()?;
+        // only the changes of the transactions after the registration are delivered
+        let sql =
+            "INSERT INTO rsvt.webhooks (url, secret, events, resource_id, cursor, cursor_txid)
+            VALUES ($1, $2, $3, $4, 0, pg_snapshot_xmax(pg_current_snapshot()))
+            RETURNING *";
+        let mut tx = self.begin().await?;
+        let webhook: Webhook = sqlx::query_as(sql)
+            .bind(&webhook.url)
+            .bind(&webhook.secret)
+            .bind(&webhook.events)
+            .bind(&webhook.resource_id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        info!("webhook {} registered for {}", webhook.id, webhook.url);
+        Ok(webhook)
+    }
+
+    async fn unregister_webhook(&self, id: i64) -> Result<Webhook, Error> {
+        let sql = "DELETE FROM rsvt.webhooks WHERE id = $1 RETURNING *";
+        let mut tx = self.begin().await?;
+        let webhook: Webhook = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(without_secret(webhook))
+    }
+
+    async fn list_webhooks(&self) -> Result<Vec<Webhook>, Error> {
+        let sql = "SELECT * FROM rsvt.webhooks ORDER BY id";
+        let mut tx = self.begin().await?;
+        let webhooks: Vec<Webhook> = sqlx::query_as(sql)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(webhooks.into_iter().map(without_secret).collect())
+    }
+
+    async fn list_dead_letters(&self, webhook_id: i64) -> Result<Vec<DeadLetter>, Error> {
+        self.find_webhook(webhook_id).await?;
+        let sql = "SELECT * FROM rsvt.webhook_dead_letters WHERE webhook_id = $1 ORDER BY id";
+        let mut tx = self.begin().await?;
+        let dead_letters = sqlx::query_as(sql)
+            .bind(webhook_id)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(dead_letters)
+    }
+
+    async fn replay_webhook(&self, webhook_id: i64, ids: &[i64]) -> Result<i64, Error> {
+        self.find_webhook(webhook_id).await?;
+        // a change which is in the outbox again is not queued twice
+        let sql = "WITH moved AS (
+                DELETE FROM rsvt.webhook_dead_letters
+                WHERE webhook_id = $1 AND (cardinality($2::bigint[]) = 0 OR id = ANY($2))
+                RETURNING webhook_id, change_id, payload, tenant_id
+            ), queued AS (
+                INSERT INTO rsvt.webhook_deliveries (webhook_id, change_id, payload, tenant_id)
+                SELECT * FROM moved ON CONFLICT (webhook_id, change_id) DO NOTHING
+            )
+            SELECT COUNT(*) FROM moved";
+        let mut tx = self.begin().await?;
+        let replayed: i64 = sqlx::query_scalar(sql)
+            .bind(webhook_id)
+            .bind(ids)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        info!(
+            "{} dead letters of webhook {} replayed",
+            replayed, webhook_id
+        );
+        Ok(replayed)
+    }
+
+    async fn fan_out(&self) -> Result<u64, Error> {
+        let mut tx = self.begin().await?;
+        // the webhooks are locked, so a change is queued by one dispatcher only
+        let sql = "SELECT *, cursor_txid::text::bigint AS cursor_txid_value
+            FROM rsvt.webhooks ORDER BY id FOR UPDATE";
+        let rows = sqlx::query(sql)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        let mut webhooks = Vec::with_capacity(rows.len());
+        for row in rows {
+            let cursor: (i64, i64) = (row.try_get("cursor_txid_value")?, row.try_get("cursor")?);
+            let tenant: String = row.try_get("tenant_id")?;
+            webhooks.push((Webhook::from_row(&row)?, cursor, tenant));
+        }
+        let from = match webhooks.iter().map(|(_, cursor, _)| *cursor).min() {
+            Some(from) => from,
+            None => return Ok(0),
+        };
+
+        // the ids are taken before the commits, so a change is fanned out in the order of the
+        // transactions once they ended, the change of a transaction still running is not skipped
+        let sql = concat!(
+            "SELECT ",
+            change_cursor!(),
+            ", c.op, c.tenant_id AS change_tenant_id, r.* FROM rsvt.reservation_changes c ",
+            changed_reservation!(),
+            " WHERE (c.txid, c.id) > ($1::bigint::text::xid8, $2)
+                AND c.txid < pg_snapshot_xmin(pg_current_snapshot())
+            ORDER BY c.txid, c.id LIMIT $3"
+        );
+        let rows = sqlx::query(sql)
+            .bind(from.0)
+            .bind(from.1)
+            .bind(FAN_OUT_BATCH)
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+
+        let mut queued = 0;
+        let mut last = from;
+        for row in rows {
+            let change_txid: i64 = row.try_get("change_txid")?;
+            let change_id: i64 = row.try_get("change_id")?;
+            let change_tenant: String = row.try_get("change_tenant_id")?;
+            let change = ListenResponse::from_row(&row)?;
+            let rsvp = change.reservation.unwrap_or_default();
+            let payload = json!({
+                "id": change_id,
+                "event": ReservationUpdateType::from_i32(change.op)
+                    .map(|op| op.to_string())
+                    .unwrap_or_default(),
+                "reservation": rsvp,
+            })
+            .to_string();
+            // a webhook is sent the changes of its own tenant only
+            for (webhook, cursor, tenant) in &webhooks {
+                if (change_txid, change_id) > *cursor
+                    && *tenant == change_tenant
+                    && webhook.matches(change.op, &rsvp)
+                {
+                    let sql = "INSERT INTO rsvt.webhook_deliveries
+                            (webhook_id, change_id, payload, tenant_id)
+                        VALUES ($1, $2, $3, $4) ON CONFLICT (webhook_id, change_id) DO NOTHING";
+                    queued += sqlx::query(sql)
+                        .bind(webhook.id)
+                        .bind(change_id)
+                        .bind(&payload)
+                        .bind(tenant)
+                        .execute(&mut tx)
+                        .instrument(sql_span(sql))
+                        .await?
+                        .rows_affected();
+                }
+            }
+            last = (change_txid, change_id);
+        }
+
+        let sql = "UPDATE rsvt.webhooks SET cursor_txid = $1::bigint::text::xid8, cursor = $2
+            WHERE (cursor_txid, cursor) < ($1::bigint::text::xid8, $2)";
+        sqlx::query(sql)
+            .bind(last.0)
+            .bind(last.1)
+            .execute(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(queued)
+    }
+
+    async fn claim_deliveries(&self, limit: i64, lease: Duration) -> Result<Vec<Delivery>, Error> {
+        // a claimed delivery is not due again until the lease passes, in case the
+        // dispatcher dies before it completes the delivery
+        let sql = "UPDATE rsvt.webhook_deliveries d
+            SET next_attempt_at = now() + $2 * interval '1 second'
+            FROM rsvt.webhooks w
+            WHERE w.id = d.webhook_id AND d.id IN (
+                SELECT id FROM rsvt.webhook_deliveries WHERE next_attempt_at <= now()
+                ORDER BY id LIMIT $1 FOR UPDATE SKIP LOCKED
+            )
+            RETURNING d.id, d.webhook_id, d.change_id, w.url, w.secret, d.payload, d.attempts";
+        let mut tx = self.begin().await?;
+        let mut deliveries: Vec<Delivery> = sqlx::query_as(sql)
+            .bind(limit)
+            .bind(lease.as_secs_f64())
+            .fetch_all(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        deliveries.sort_by_key(|delivery| delivery.id);
+        Ok(deliveries)
+    }
+
+    async fn complete_delivery(&self, id: i64) -> Result<(), Error> {
+        let sql = "DELETE FROM rsvt.webhook_deliveries WHERE id = $1";
+        let mut tx = self.begin().await?;
+        sqlx::query(sql)
+            .bind(id)
+            .execute(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn fail_delivery(
+        &self,
+        id: i64,
+        error: &str,
+        retry_after: Option<Duration>,
+    ) -> Result<(), Error> {
+        let sql = match retry_after {
+            Some(_) => {
+                "UPDATE rsvt.webhook_deliveries SET attempts = attempts + 1, last_error = $2,
+                    next_attempt_at = now() + $3 * interval '1 second'
+                WHERE id = $1"
+            }
+            None => {
+                "WITH dead AS (
+                    DELETE FROM rsvt.webhook_deliveries WHERE id = $1
+                    RETURNING webhook_id, change_id, payload, attempts, tenant_id
+                )
+                INSERT INTO rsvt.webhook_dead_letters
+                    (webhook_id, change_id, payload, attempts, last_error, tenant_id)
+                SELECT webhook_id, change_id, payload, attempts + 1, $2, tenant_id FROM dead"
+            }
+        };
+        let mut query = sqlx::query(sql).bind(id).bind(error);
+        if let Some(retry_after) = retry_after {
+            query = query.bind(retry_after.as_secs_f64());
+        }
+        let mut tx = self.begin().await?;
+        query.execute(&mut tx).instrument(sql_span(sql)).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+impl OrderManager {
+    async fn find_webhook(&self, id: i64) -> Result<Webhook, Error> {
+        let sql = "SELECT * FROM rsvt.webhooks WHERE id = $1";
+        let mut tx = self.begin().await?;
+        let webhook = sqlx::query_as(sql)
+            .bind(id)
+            .fetch_one(&mut tx)
+            .instrument(sql_span(sql))
+            .await?;
+        tx.commit().await?;
+        Ok(webhook)
+    }
+}
+
+fn without_secret(webhook: Webhook) -> Webhook {
+    Webhook {
+        secret: String::new(),
+        ..webhook
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{manager::insert_reservation, Order};
+    use abi::{BookingRules, Buffer, Reservation};
+
+    fn rsvp(rid: &str) -> Reservation {
+        Reservation::new_pending(
+            "tosei",
+            rid,
+            "2030-01-01T10:00:00+0800".parse().unwrap(),
+            "2030-01-01T12:00:00+0800".parse().unwrap(),
+            "",
+        )
+    }
+
+    fn webhook(events: Vec<ReservationUpdateType>, resource_id: &str) -> Webhook {
+        Webhook {
+            url: "http://localhost:8000/hooks".to_string(),
+            secret: "secret".to_string(),
+            events: events.into_iter().map(|op| op as i32).collect(),
+            resource_id: resource_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn lease() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// the changes wait for the transactions before them, even of the other databases
+    async fn settle(manager: &OrderManager) {
+        let sql = "SELECT COALESCE(MAX(txid) < pg_snapshot_xmin(pg_current_snapshot()), true)
+            FROM rsvt.reservation_changes";
+        loop {
+            let mut tx = manager.begin().await.unwrap();
+            let settled: bool = sqlx::query_scalar(sql).fetch_one(&mut tx).await.unwrap();
+            tx.commit().await.unwrap();
+            if settled {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn changes_should_be_queued_for_the_matching_webhooks() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        manager.create_order(rsvp("room-0")).await.unwrap();
+        let created = manager
+            .register_webhook(webhook(vec![ReservationUpdateType::Create], "room-*"))
+            .await
+            .unwrap();
+        assert_eq!("secret", created.secret);
+        let all = manager.register_webhook(webhook(vec![], "")).await.unwrap();
+
+        let room = manager.create_order(rsvp("room-1")).await.unwrap();
+        manager.create_order(rsvp("vehicle-1")).await.unwrap();
+        manager.cancel_reservation(room.id).await.unwrap();
+
+        settle(&manager).await;
+        assert_eq!(4, manager.fan_out().await.unwrap());
+        // the changes are queued once
+        assert_eq!(0, manager.fan_out().await.unwrap());
+
+        let deliveries = manager.claim_deliveries(10, lease()).await.unwrap();
+        let count = |id| deliveries.iter().filter(|d| d.webhook_id == id).count();
+        assert_eq!((1, 3), (count(created.id), count(all.id)));
+        let payload: serde_json::Value = serde_json::from_str(&deliveries[0].payload).unwrap();
+        assert_eq!("create", payload["event"]);
+        assert_eq!(room.id, payload["reservation"]["id"]);
+        assert_eq!("secret", deliveries[0].secret);
+        // claimed until the lease passes
+        assert!(manager
+            .claim_deliveries(10, lease())
+            .await
+            .unwrap()
+            .is_empty());
+
+        let webhooks = manager.list_webhooks().await.unwrap();
+        assert_eq!(2, webhooks.len());
+        assert!(webhooks.iter().all(|webhook| webhook.secret.is_empty()));
+        manager.unregister_webhook(all.id).await.unwrap();
+        assert_eq!(
+            Error::NotFound,
+            manager.list_dead_letters(all.id).await.unwrap_err()
+        );
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn change_committed_after_a_newer_one_should_be_queued() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        manager.register_webhook(webhook(vec![], "")).await.unwrap();
+
+        // the older transaction takes the smaller change id, but commits after the newer one
+        let mut older = manager.begin().await.unwrap();
+        let first = insert_reservation(
+            &mut older,
+            rsvp("room-1"),
+            Buffer::default(),
+            &BookingRules::default(),
+        )
+        .await
+        .unwrap();
+        let mut newer = manager.begin().await.unwrap();
+        let second = insert_reservation(
+            &mut newer,
+            rsvp("room-2"),
+            Buffer::default(),
+            &BookingRules::default(),
+        )
+        .await
+        .unwrap();
+        newer.commit().await.unwrap();
+
+        // the newer change waits for the older transaction
+        assert_eq!(0, manager.fan_out().await.unwrap());
+        older.commit().await.unwrap();
+        settle(&manager).await;
+        assert_eq!(2, manager.fan_out().await.unwrap());
+
+        let deliveries = manager.claim_deliveries(10, lease()).await.unwrap();
+        let ids: Vec<i64> = deliveries
+            .iter()
+            .map(|delivery| {
+                let payload: serde_json::Value = serde_json::from_str(&delivery.payload).unwrap();
+                payload["reservation"]["id"].as_i64().unwrap()
+            })
+            .collect();
+        assert_eq!(vec![first.id, second.id], ids);
+    }
+
+    #[sqlx_database_tester::test(pool(variable = "migrated_pool", migrations = "../migrations"))]
+    async fn failed_delivery_should_be_dead_and_replayed() {
+        let manager = OrderManager::new(migrated_pool.clone());
+        let hook = manager.register_webhook(webhook(vec![], "")).await.unwrap();
+        manager.create_order(rsvp("room-1")).await.unwrap();
+        settle(&manager).await;
+        manager.fan_out().await.unwrap();
+
+        let delivery = manager
+            .claim_deliveries(10, lease())
+            .await
+            .unwrap()
+            .remove(0);
+        manager
+            .fail_delivery(delivery.id, "status 500", Some(Duration::ZERO))
+            .await
+            .unwrap();
+        let retried = manager
+            .claim_deliveries(10, lease())
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(1, retried.attempts);
+        manager
+            .fail_delivery(retried.id, "status 503", None)
+            .await
+            .unwrap();
+
+        let dead = manager.list_dead_letters(hook.id).await.unwrap();
+        assert_eq!(1, dead.len());
+        assert_eq!(
+            (2, "status 503"),
+            (dead[0].attempts, dead[0].last_error.as_str())
+        );
+        assert_eq!(delivery.payload, dead[0].payload);
+
+        assert_eq!(1, manager.replay_webhook(hook.id, &[]).await.unwrap());
+        assert!(manager.list_dead_letters(hook.id).await.unwrap().is_empty());
+        let replayed = manager
+            .claim_deliveries(10, lease())
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(0, replayed.attempts);
+        assert_eq!(delivery.change_id, replayed.change_id);
+        manager.complete_delivery(replayed.id).await.unwrap();
+        assert_eq!(
+            0,
+            manager
+                .replay_webhook(hook.id, &[dead[0].id])
+                .await
+                .unwrap()
+        );
+    }
+}
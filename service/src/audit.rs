@@ -0,0 +1,103 @@
+use futures::future::BoxFuture;
+use order::Actor;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+use crate::USER_ID_METADATA;
+
+pub const REQUEST_ID_METADATA: &str = "x-request-id";
+
+const ANONYMOUS: &str = "anonymous";
+
+/// record the changes made by a request as its user's, shared by the grpc and rest servers
+#[derive(Debug, Clone, Default)]
+pub struct AuditLayer;
+
+impl<S> Layer<S> for AuditLayer {
+    type Service = AuditService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for AuditService<S>
+where
+    S: Service<http::Request<ReqBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        let actor = actor(&req);
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            // the verified actor of the tenant layer is kept, whichever of the layers is outer
+            match Actor::current() {
+                Some(current) if current.verified => fut.await,
+                _ => actor.scope(fut).await,
+            }
+        })
+    }
+}
+
+/// the user and the request id from the headers, a request without the user is anonymous
+fn actor<B>(req: &http::Request<B>) -> Actor {
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+    };
+    Actor::new(
+        header(USER_ID_METADATA).unwrap_or(ANONYMOUS),
+        header(REQUEST_ID_METADATA).unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+
+    #[tokio::test]
+    async fn actor_should_be_taken_from_the_headers() {
+        let svc = || {
+            AuditLayer.layer(service_fn(|_: http::Request<()>| async {
+                Ok::<_, Infallible>(Actor::current())
+            }))
+        };
+        let req = http::Request::builder()
+            .header(USER_ID_METADATA, "tosei")
+            .header(REQUEST_ID_METADATA, "req-1")
+            .body(())
+            .unwrap();
+        let actor = svc().oneshot(req).await.unwrap();
+        assert_eq!(Some(Actor::new("tosei", "req-1")), actor);
+        let req = http::Request::builder().body(()).unwrap();
+        let actor = svc().oneshot(req).await.unwrap();
+        assert_eq!(Some(Actor::new(ANONYMOUS, "")), actor);
+        assert_eq!(None, Actor::current());
+
+        // the verified actor is not replaced by the header
+        let req = http::Request::builder()
+            .header(USER_ID_METADATA, "mallory")
+            .body(())
+            .unwrap();
+        let verified = Actor::verified("tosei", "req-2");
+        let actor = verified.clone().scope(svc().oneshot(req)).await.unwrap();
+        assert_eq!(Some(verified), actor);
+    }
+}
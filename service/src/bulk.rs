@@ -0,0 +1,231 @@
+use abi::{
+    Error, ImportRequest, ImportResult, Reservation, ReservationConflictDetails,
+    ReservationConflictInfo, ReservationFilter,
+};
+use futures::{Stream, StreamExt};
+use order::{Bulk, Order, OrderManager};
+use tokio::sync::mpsc;
+use tonic::Status;
+
+/// the rows committed in one transaction if the client does not give it
+const DEFAULT_CHUNK_SIZE: usize = 500;
+const MAX_CHUNK_SIZE: usize = 5000;
+/// the largest page of the filter
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+/// import the rows of the stream in chunks, the results are sent after each chunk
+pub(crate) async fn import_rows<S>(
+    manager: OrderManager,
+    mut rows: S,
+    results: mpsc::Sender<Result<ImportResult, Error>>,
+) -> Result<(), Error>
+where
+    S: Stream<Item = Result<ImportRequest, Status>> + Unpin,
+{
+    let first = match rows.next().await {
+        Some(row) => row.map_err(rpc_error)?,
+        None => return Ok(()),
+    };
+    let chunk_size = match first.chunk_size {
+        size if size <= 0 => DEFAULT_CHUNK_SIZE,
+        size => (size as usize).min(MAX_CHUNK_SIZE),
+    };
+    let mut importer = manager.importer(first.dry_run);
+    let mut chunk = vec![first.reservation.unwrap_or_default()];
+    let mut row = 0;
+    loop {
+        let next = rows.next().await;
+        if chunk.len() >= chunk_size || next.is_none() {
+            let rsvps = std::mem::take(&mut chunk);
+            for result in importer.import_chunk(rsvps).await? {
+                row += 1;
+                if results.send(Ok(import_result(row, result))).await.is_err() {
+                    // the client is gone, a dry run is rolled back when the importer is dropped
+                    return importer.finish().await;
+                }
+            }
+        }
+        match next {
+            Some(request) => {
+                chunk.push(request.map_err(rpc_error)?.reservation.unwrap_or_default())
+            }
+            None => return importer.finish().await,
+        }
+    }
+}
+
+/// send the reservations matching the filter page by page, order by id
+pub(crate) async fn export_rows(
+    manager: OrderManager,
+    filter: ReservationFilter,
+    rsvps: mpsc::Sender<Result<Reservation, Error>>,
+) -> Result<(), Error> {
+    let mut filter = ReservationFilter {
+        cursor: None,
+        page_size: EXPORT_PAGE_SIZE,
+        ..filter
+    };
+    loop {
+        let (pager, page) = manager.filter_reservations(filter.clone()).await?;
+        if page.is_empty() {
+            return Ok(());
+        }
+        for rsvp in page {
+            if rsvps.send(Ok(rsvp)).await.is_err() {
+                return Ok(());
+            }
+        }
+        filter.cursor = pager.next;
+    }
+}
+
+fn import_result(row: i64, result: Result<Reservation, Error>) -> ImportResult {
+    match result {
+        Ok(rsvp) => ImportResult {
+            row,
+            reservation: Some(rsvp),
+            ..Default::default()
+        },
+        Err(e) => {
+            let conflict = match &e {
+                Error::ConfilictReservation(ReservationConflictInfo::Parsed(conflict)) => {
+                    Some(ReservationConflictDetails::from(conflict.as_ref()))
+                }
+                _ => None,
+            };
+            // the same message as the status of add
+            let status = Status::from(e);
+            ImportResult {
+                row,
+                reservation: None,
+                error: status.message().to_string(),
+                conflict,
+            }
+        }
+    }
+}
+
+fn rpc_error(status: Status) -> Error {
+    Error::RpcError(Box::new(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Metrics, TestConfig};
+    use abi::ReservationStatus;
+    use futures::stream;
+
+    fn row(uid: &str, start: &str, end: &str, dry_run: bool) -> ImportRequest {
+        ImportRequest {
+            reservation: Some(Reservation::new_pending(
+                uid,
+                "room-1",
+                start.parse().unwrap(),
+                end.parse().unwrap(),
+                "",
+            )),
+            dry_run,
+            chunk_size: 2,
+        }
+    }
+
+    async fn import(manager: &OrderManager, rows: Vec<ImportRequest>) -> Vec<ImportResult> {
+        let (tx, mut rx) = mpsc::channel(16);
+        let rows = stream::iter(rows.into_iter().map(Ok));
+        tokio::spawn(import_rows(manager.clone(), rows, tx));
+        let mut results = vec![];
+        while let Some(result) = rx.recv().await {
+            results.push(result.unwrap());
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn import_should_report_the_conflicts_of_each_row() {
+        let config = TestConfig::default();
+        let manager = OrderManager::from_config(&config.db).await.unwrap();
+        let rows = |dry_run| {
+            vec![
+                row(
+                    "tosei",
+                    "2030-01-01T10:00:00Z",
+                    "2030-01-01T11:00:00Z",
+                    dry_run,
+                ),
+                row(
+                    "wxy",
+                    "2030-01-01T11:00:00Z",
+                    "2030-01-01T12:00:00Z",
+                    dry_run,
+                ),
+                row(
+                    "wxy",
+                    "2030-01-01T10:30:00Z",
+                    "2030-01-01T11:30:00Z",
+                    dry_run,
+                ),
+            ]
+        };
+
+        for dry_run in [true, false] {
+            let results = import(&manager, rows(dry_run)).await;
+            assert_eq!(
+                vec![1, 2, 3],
+                results.iter().map(|r| r.row).collect::<Vec<_>>()
+            );
+            assert!(results[0].error.is_empty());
+            assert_eq!(dry_run, results[1].reservation.as_ref().unwrap().id == 0);
+            let conflict = results[2].conflict.as_ref().unwrap();
+            assert_eq!("tosei", conflict.conflicting_user_id);
+            assert!(results[2].error.starts_with("Conflict reservation: room-1"));
+        }
+        // the conflicts of both runs are counted by their resource
+        let metrics = Metrics::new().encode(&manager);
+        assert!(metrics.contains(r#"rorder_conflicts_total{resource="room-1"} 2"#));
+
+        let filter = ReservationFilter {
+            status: ReservationStatus::Pending as i32,
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(export_rows(manager.clone(), filter, tx));
+        let mut exported = vec![];
+        while let Some(rsvp) = rx.recv().await {
+            exported.push(rsvp.unwrap().user_id);
+        }
+        // only the real run is committed
+        assert_eq!(vec!["tosei", "wxy"], exported);
+    }
+
+    #[tokio::test]
+    async fn export_should_page_through_the_filter() {
+        let config = TestConfig::default();
+        let manager = OrderManager::from_config(&config.db).await.unwrap();
+        let rows = (0..EXPORT_PAGE_SIZE + 5)
+            .map(|i| {
+                let start = format!("2030-01-01T{:02}:{:02}:00Z", i / 60, i % 60);
+                let end = format!("2030-01-01T{:02}:{:02}:30Z", i / 60, i % 60);
+                row("tosei", &start, &end, false)
+            })
+            .collect();
+        let results = import(&manager, rows).await;
+        assert!(results.iter().all(|r| r.error.is_empty()));
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let filter = ReservationFilter {
+            status: ReservationStatus::Pending as i32,
+            ..Default::default()
+        };
+        tokio::spawn(export_rows(manager.clone(), filter, tx));
+        let mut ids = vec![];
+        while let Some(rsvp) = rx.recv().await {
+            ids.push(rsvp.unwrap().id);
+        }
+        let expected: Vec<i64> = results
+            .iter()
+            .map(|r| r.reservation.as_ref().unwrap().id)
+            .collect();
+        assert_eq!(expected, ids);
+    }
+}
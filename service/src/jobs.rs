@@ -0,0 +1,101 @@
+use futures::Future;
+use order::{CheckIn, Hold, OrderManager, Retention, Waitlist};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::WebhookDispatcher;
+
+/// expire the stale waitlist entries periodically until the token is cancelled
+pub async fn sweep_waitlist(
+    manager: OrderManager,
+    interval: Duration,
+    shutdown: CancellationToken,
+) {
+    every(interval, shutdown, || async {
+        match manager.expire_waitlist().await {
+            Ok(entries) if !entries.is_empty() => {
+                info!("{} waitlist entries expired", entries.len())
+            }
+            Ok(_) => {}
+            Err(e) => warn!("failed to expire waitlist entries: {e}"),
+        }
+    })
+    .await
+}
+
+/// release the expired holds periodically until the token is cancelled
+pub async fn sweep_holds(manager: OrderManager, interval: Duration, shutdown: CancellationToken) {
+    every(interval, shutdown, || async {
+        match manager.release_expired_holds().await {
+            Ok(released) if !released.is_empty() => info!("{} holds released", released.len()),
+            Ok(_) => {}
+            Err(e) => warn!("failed to release expired holds: {e}"),
+        }
+    })
+    .await
+}
+
+/// mark the missed reservations as no-shows periodically until the token is cancelled
+pub async fn sweep_no_shows(
+    manager: OrderManager,
+    interval: Duration,
+    shutdown: CancellationToken,
+) {
+    every(interval, shutdown, || async {
+        match manager.mark_no_shows().await {
+            Ok(marked) if !marked.is_empty() => info!("{} no-shows marked", marked.len()),
+            Ok(_) => {}
+            Err(e) => warn!("failed to mark no-shows: {e}"),
+        }
+    })
+    .await
+}
+
+/// move the old reservations into the archive periodically until the token is cancelled
+pub async fn sweep_retention(
+    manager: OrderManager,
+    interval: Duration,
+    shutdown: CancellationToken,
+) {
+    every(interval, shutdown, || async {
+        match manager.archive_reservations().await {
+            Ok(archived) if !archived.is_empty() => {
+                info!("{} reservations archived", archived.len())
+            }
+            Ok(_) => {}
+            Err(e) => warn!("failed to archive reservations: {e}"),
+        }
+    })
+    .await
+}
+
+/// post the changes to the webhooks periodically until the token is cancelled
+pub async fn sweep_webhooks(
+    dispatcher: WebhookDispatcher,
+    interval: Duration,
+    shutdown: CancellationToken,
+) {
+    every(interval, shutdown, || async {
+        match dispatcher.dispatch().await {
+            Ok(accepted) if accepted > 0 => info!("{} webhook deliveries accepted", accepted),
+            Ok(_) => {}
+            Err(e) => warn!("failed to dispatch webhook deliveries: {e}"),
+        }
+    })
+    .await
+}
+
+async fn every<F, Fut>(interval: Duration, shutdown: CancellationToken, mut f: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => f().await,
+            _ = shutdown.cancelled() => return,
+        }
+    }
+}
@@ -1,16 +1,38 @@
+mod audit;
+mod bulk;
+mod jobs;
+mod limit;
+mod metrics;
+mod rest;
 mod server;
+mod shutdown;
+mod telemetry;
+mod tenant;
 mod test_util;
+mod webhook;
 
-use abi::Reservation;
+use abi::{ImportResult, ListenResponse, Reservation, WaitlistEntry};
 use futures::Stream;
 use std::pin::Pin;
 use tokio::sync::mpsc;
 use tonic::Status;
 
+pub use audit::*;
+pub use jobs::*;
+pub use limit::*;
+pub use metrics::*;
+pub use rest::*;
 pub use server::*;
+pub use shutdown::*;
+pub use telemetry::*;
+pub use tenant::*;
 pub use test_util::*;
+pub use webhook::*;
 
 type ReservationResponseStream = Pin<Box<dyn Stream<Item = Result<Reservation, Status>> + Send>>;
+type WaitlistResponseStream = Pin<Box<dyn Stream<Item = Result<WaitlistEntry, Status>> + Send>>;
+type EventResponseStream = Pin<Box<dyn Stream<Item = Result<ListenResponse, Status>> + Send>>;
+type ImportResponseStream = Pin<Box<dyn Stream<Item = Result<ImportResult, Status>> + Send>>;
 
 pub struct TonicReceiverStream<T> {
     inner: mpsc::Receiver<Result<T, abi::Error>>,
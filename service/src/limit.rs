@@ -0,0 +1,425 @@
+use abi::{BucketConfig, RateLimitConfig};
+use axum::extract::ConnectInfo;
+use futures::future::BoxFuture;
+use http_body::{Body, SizeHint};
+use order::Actor;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tonic::{transport::server::TcpConnectInfo, Status};
+use tower::{Layer, Service};
+
+use crate::{is_grpc, rpc_name};
+
+/// metadata key of the user which is set by the authentication proxy
+pub const USER_ID_METADATA: &str = "x-user-id";
+
+/// how often the buckets which are refilled are removed
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// token-bucket rate limits per rpc and client, plus a global concurrency limit
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: Arc<RwLock<RateLimitConfig>>,
+    buckets: Arc<Mutex<Buckets>>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    // key is (rpc, client)
+    map: HashMap<(String, String), TokenBucket>,
+    swept: Instant,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// release the concurrency slot when the request is finished
+#[derive(Debug)]
+pub struct ConcurrencyPermit(Arc<AtomicUsize>);
+
+/// response body which holds the concurrency slot until the response, e.g. a stream, is sent
+#[derive(Debug)]
+pub struct PermitBody<B> {
+    inner: B,
+    _permit: Option<ConcurrencyPermit>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            buckets: Arc::new(Mutex::new(Buckets {
+                map: HashMap::new(),
+                swept: Instant::now(),
+            })),
+            in_flight: Default::default(),
+        }
+    }
+
+    /// replace the limits, the buckets are refilled with the new configuration
+    pub fn reload(&self, config: RateLimitConfig) {
+        *self.config.write().unwrap() = config;
+        self.buckets.lock().unwrap().map.clear();
+    }
+
+    /// take a token of the client for the rpc, return the retry-after duration if exhausted
+    pub fn check(&self, rpc: &str, client: &str) -> Result<(), Duration> {
+        let config = self.config.read().unwrap();
+        let bucket_config = match config.rpcs.get(rpc).or(config.default.as_ref()) {
+            Some(bucket_config) => bucket_config,
+            None => return Ok(()),
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        if now.duration_since(buckets.swept) >= SWEEP_INTERVAL {
+            // a refilled bucket is the same as a new one, so the idle clients are not kept
+            buckets.map.retain(|(rpc, _), bucket| {
+                match config.rpcs.get(rpc).or(config.default.as_ref()) {
+                    Some(bucket_config) => !bucket.is_full(bucket_config, now),
+                    None => false,
+                }
+            });
+            buckets.swept = now;
+        }
+        let bucket = buckets
+            .map
+            .entry((rpc.to_string(), client.to_string()))
+            .or_insert_with(|| TokenBucket::new(bucket_config));
+        bucket.take(bucket_config)
+    }
+
+    /// take a slot of the global concurrency limit
+    pub fn try_acquire(&self) -> Option<ConcurrencyPermit> {
+        let max = self.config.read().unwrap().max_concurrency;
+        let acquired = self
+            .in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (max == 0 || n < max).then_some(n + 1)
+            })
+            .is_ok();
+        acquired.then(|| ConcurrencyPermit(self.in_flight.clone()))
+    }
+}
+
+impl TokenBucket {
+    fn new(config: &BucketConfig) -> Self {
+        Self {
+            tokens: config.burst as f64,
+            last: Instant::now(),
+        }
+    }
+
+    fn is_full(&self, config: &BucketConfig, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens + elapsed * config.per_second as f64 >= config.burst as f64
+    }
+
+    fn take(&mut self, config: &BucketConfig) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+        self.tokens = (self.tokens + elapsed * config.per_second as f64).min(config.burst as f64);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if config.per_second == 0 {
+            Err(Duration::from_secs(1))
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / config.per_second as f64,
+            ))
+        }
+    }
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl<B: Default> Default for PermitBody<B> {
+    fn default() -> Self {
+        Self {
+            inner: B::default(),
+            _permit: None,
+        }
+    }
+}
+
+impl<B: Body + Unpin> Body for PermitBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.inner).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// tower layer which rejects the request with RESOURCE_EXHAUSTED when a limit is hit, shared by
+/// the grpc and rest servers. It is applied inside the tenant layer to see the verified user
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+}
+
+impl RateLimitLayer {
+    pub fn new(limiter: RateLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: RateLimiter,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = http::Response<PermitBody<ResBody>>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        let rpc = rpc_name(&req);
+        let client = client_key(&req);
+        let grpc = is_grpc(&req);
+
+        if let Err(retry_after) = self.limiter.check(rpc, &client) {
+            let status = exhausted(format!("rate limit exceeded for {rpc}"), retry_after);
+            return Box::pin(async move { Ok(rejected(status, grpc)) });
+        }
+
+        let permit = match self.limiter.try_acquire() {
+            Some(permit) => permit,
+            None => {
+                let status = exhausted("too many concurrent requests", Duration::from_secs(1));
+                return Box::pin(async move { Ok(rejected(status, grpc)) });
+            }
+        };
+
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            // the permit is released with the body, after the last message of a stream
+            let res = fut.await?;
+            Ok(res.map(|inner| PermitBody {
+                inner,
+                _permit: Some(permit),
+            }))
+        })
+    }
+}
+
+/// the verified user of the tenant layer if present, otherwise the peer address. The user id
+/// metadata is set by the client, so it is never trusted
+fn client_key<B>(req: &http::Request<B>) -> String {
+    if let Some(actor) = req.extensions().get::<Actor>().filter(|a| a.verified) {
+        return format!("user:{}", actor.user_id);
+    }
+    let extensions = req.extensions();
+    extensions
+        .get::<TcpConnectInfo>()
+        .and_then(|info| info.remote_addr())
+        .or_else(|| {
+            extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|info| info.0)
+        })
+        .map(|addr| format!("peer:{}", addr.ip()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// a trailers only grpc error with the http status 200, the rest clients see 429 instead
+fn rejected<B: Default>(status: Status, grpc: bool) -> http::Response<B> {
+    let (mut parts, _) = status.to_http().into_parts();
+    if !grpc {
+        parts.status = http::StatusCode::TOO_MANY_REQUESTS;
+    }
+    http::Response::from_parts(parts, B::default())
+}
+
+fn exhausted(message: impl Into<String>, retry_after: Duration) -> Status {
+    let mut status = Status::resource_exhausted(message);
+    // round up to whole seconds like the HTTP Retry-After header
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    status
+        .metadata_mut()
+        .insert("retry-after", seconds.max(1).into());
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+
+    fn config() -> RateLimitConfig {
+        RateLimitConfig {
+            max_concurrency: 1,
+            default: None,
+            rpcs: HashMap::from([(
+                "add".to_string(),
+                BucketConfig {
+                    burst: 2,
+                    per_second: 1,
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn rate_limit_should_reject_after_burst() {
+        let limiter = RateLimiter::new(config());
+        assert!(limiter.check("add", "user:tosei").is_ok());
+        assert!(limiter.check("add", "user:tosei").is_ok());
+        let retry_after = limiter.check("add", "user:tosei").unwrap_err();
+        assert!(retry_after <= Duration::from_secs(1));
+
+        // other client and other rpc are not affected
+        assert!(limiter.check("add", "user:wxy").is_ok());
+        assert!(limiter.check("query", "user:tosei").is_ok());
+
+        // reload the limits without restart
+        let mut config = config();
+        config.rpcs.get_mut("add").unwrap().burst = 3;
+        limiter.reload(config);
+        for _ in 0..3 {
+            assert!(limiter.check("add", "user:tosei").is_ok());
+        }
+        assert!(limiter.check("add", "user:tosei").is_err());
+    }
+
+    #[test]
+    fn refilled_buckets_should_be_removed() {
+        let limiter = RateLimiter::new(config());
+        assert!(limiter.check("add", "peer:10.0.0.1").is_ok());
+        assert!(limiter.check("add", "peer:10.0.0.2").is_ok());
+        assert!(limiter.check("add", "peer:10.0.0.2").is_ok());
+        {
+            let mut buckets = limiter.buckets.lock().unwrap();
+            buckets.swept -= SWEEP_INTERVAL;
+            // 10.0.0.1 is refilled after a second, 10.0.0.2 after two
+            let last = Instant::now() - Duration::from_millis(1500);
+            buckets
+                .map
+                .values_mut()
+                .for_each(|bucket| bucket.last = last);
+        }
+        assert!(limiter.check("add", "peer:10.0.0.3").is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        let mut clients: Vec<_> = buckets.map.keys().map(|(_, c)| c.as_str()).collect();
+        clients.sort();
+        assert_eq!(vec!["peer:10.0.0.2", "peer:10.0.0.3"], clients);
+    }
+
+    #[test]
+    fn client_should_be_keyed_by_the_verified_user_or_the_peer() {
+        let peer: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let mut req = http::Request::builder()
+            .header(USER_ID_METADATA, "tosei")
+            .body(())
+            .unwrap();
+        req.extensions_mut().insert(ConnectInfo(peer));
+        // the user id metadata and the unverified actor are chosen by the client
+        assert_eq!("peer:10.0.0.1", client_key(&req));
+        req.extensions_mut().insert(Actor::new("tosei", ""));
+        assert_eq!("peer:10.0.0.1", client_key(&req));
+        req.extensions_mut().insert(Actor::verified("tosei", ""));
+        assert_eq!("user:tosei", client_key(&req));
+
+        let req = http::Request::builder().body(()).unwrap();
+        assert_eq!("unknown", client_key(&req));
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_should_be_released() {
+        let limiter = RateLimiter::new(config());
+        let permit = limiter.try_acquire().unwrap();
+        assert!(limiter.try_acquire().is_none());
+        drop(permit);
+        assert!(limiter.try_acquire().is_some());
+
+        // the slot is held until the body of the response is dropped
+        let svc =
+            RateLimitLayer::new(limiter.clone()).layer(service_fn(|_: http::Request<()>| async {
+                Ok::<_, Infallible>(http::Response::new(String::new()))
+            }));
+        let req = http::Request::builder().uri("/rsvp.ReservationService/query");
+        let res = svc.clone().oneshot(req.body(()).unwrap()).await.unwrap();
+        assert_eq!(http::StatusCode::OK, res.status());
+        let req = http::Request::builder().uri("/rsvp.ReservationService/query");
+        let rejected = svc.clone().oneshot(req.body(()).unwrap()).await.unwrap();
+        assert_eq!(http::StatusCode::TOO_MANY_REQUESTS, rejected.status());
+        assert_eq!("8", rejected.headers()["grpc-status"]);
+        // the grpc clients read the code of a trailers only response with the status 200
+        let req = http::Request::builder()
+            .uri("/rsvp.ReservationService/query")
+            .header("content-type", "application/grpc");
+        let rejected = svc.clone().oneshot(req.body(()).unwrap()).await.unwrap();
+        assert_eq!(http::StatusCode::OK, rejected.status());
+        assert_eq!("8", rejected.headers()["grpc-status"]);
+        drop(res);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[test]
+    fn exhausted_status_should_have_retry_after() {
+        let status = exhausted("rate limit exceeded", Duration::from_millis(1500));
+        assert_eq!(tonic::Code::ResourceExhausted, status.code());
+        assert_eq!("2", status.metadata().get("retry-after").unwrap());
+    }
+}
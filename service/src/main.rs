@@ -2,20 +2,142 @@ use abi::reservation_service_server::ReservationServiceServer;
 use abi::Config;
 use anyhow::Ok;
 use anyhow::Result;
-use roder_service::RsvpService;
+use roder_service::{
+    grpc_span, init_tracing, serve_metrics, serve_rest, shutdown_signal, shutdown_tracing,
+    sweep_holds, sweep_no_shows, sweep_retention, sweep_waitlist, sweep_webhooks, AuditLayer,
+    MetricsLayer, RateLimitLayer, RateLimiter, RsvpService, WebhookDispatcher,
+};
+use std::{sync::Arc, time::Duration};
 use tonic::transport::Server;
+use tracing::{error, info, warn};
+
+const CONFIG_FILE: &str = "./reservation.yml";
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config = Config::from_file("./reservation.yml")?;
+    let config = Config::from_file(CONFIG_FILE)?;
+    init_tracing(&config.tracing)?;
 
     let addr = format!("{}:{}", config.server.host, config.server.port).parse()?;
-    println!("ReservationServer listening on: {addr}");
+    info!("ReservationServer listening on: {addr}");
 
     let svc = RsvpService::from_config(&config).await?;
-    let svc = ReservationServiceServer::new(svc);
+    let metrics = svc.metrics().clone();
+    let manager = svc.manager().clone();
+    let shutdown = svc.shutdown_token();
+    let tenants = svc.tenant_layer();
+    let limiter = svc.limiter().clone();
+    let svc = Arc::new(svc);
+
+    let rest_config = config.rest.clone();
+    let rest_svc = svc.clone();
+    let rest_shutdown = shutdown.clone().cancelled_owned();
+    let rest_server = tokio::spawn(async move {
+        if let Err(e) = serve_rest(&rest_config, rest_svc, rest_shutdown).await {
+            error!("rest gateway error: {e}");
+        }
+    });
+    let svc = ReservationServiceServer::from_arc(svc);
+
+    let metrics_config = config.metrics.clone();
+    let metrics_manager = manager.clone();
+    let metrics_shutdown = shutdown.clone().cancelled_owned();
+    let layer = MetricsLayer::new(metrics.clone());
+    let metrics_server = tokio::spawn(async move {
+        if let Err(e) =
+            serve_metrics(&metrics_config, metrics, metrics_manager, metrics_shutdown).await
+        {
+            error!("metrics server error: {e}");
+        }
+    });
+
+    let sweeper = tokio::spawn(sweep_waitlist(
+        manager.clone(),
+        Duration::from_secs(config.waitlist.sweep_interval),
+        shutdown.clone(),
+    ));
+    let hold_sweeper = tokio::spawn(sweep_holds(
+        manager.clone(),
+        Duration::from_secs(config.hold.sweep_interval),
+        shutdown.clone(),
+    ));
+    let no_show_sweeper = tokio::spawn(sweep_no_shows(
+        manager.clone(),
+        Duration::from_secs(config.check_in.sweep_interval),
+        shutdown.clone(),
+    ));
+    let retention_sweeper = tokio::spawn(sweep_retention(
+        manager.clone(),
+        Duration::from_secs(config.retention.sweep_interval),
+        shutdown.clone(),
+    ));
+    let webhook_sweeper = tokio::spawn(sweep_webhooks(
+        WebhookDispatcher::new(manager.clone(), config.webhooks.clone()),
+        Duration::from_secs(config.webhooks.sweep_interval),
+        shutdown.clone(),
+    ));
+
+    tokio::spawn(reload_on_hangup(limiter.clone()));
 
-    Server::builder().add_service(svc).serve(addr).await?;
+    let server = Server::builder()
+        .trace_fn(grpc_span)
+        .layer(layer)
+        .layer(AuditLayer)
+        .layer(tenants)
+        .layer(RateLimitLayer::new(limiter))
+        .add_service(svc)
+        .serve_with_shutdown(addr, shutdown.clone().cancelled_owned());
+    tokio::pin!(server);
 
+    tokio::select! {
+        ret = &mut server => ret?,
+        _ = shutdown_signal() => {
+            info!("shutdown signal received, draining connections");
+            // close the listen streams and stop accepting new connections
+            shutdown.cancel();
+            let timeout = Duration::from_secs(config.server.shutdown_timeout);
+            match tokio::time::timeout(timeout, server).await {
+                std::result::Result::Ok(ret) => ret?,
+                Err(_) => warn!("connections are not drained in {timeout:?}"),
+            }
+        }
+    }
+
+    let _ = rest_server.await;
+    let _ = metrics_server.await;
+    let _ = sweeper.await;
+    let _ = hold_sweeper.await;
+    let _ = no_show_sweeper.await;
+    let _ = retention_sweeper.await;
+    let _ = webhook_sweeper.await;
+    manager.close().await;
+    info!("ReservationServer stopped");
+    shutdown_tracing();
     Ok(())
 }
+
+/// reload the rate limits from the configuration file on SIGHUP
+#[cfg(unix)]
+async fn reload_on_hangup(limiter: RateLimiter) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        std::result::Result::Ok(hangup) => hangup,
+        Err(e) => {
+            error!("failed to install SIGHUP handler: {e}");
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        match Config::from_file(CONFIG_FILE) {
+            std::result::Result::Ok(config) => {
+                info!("rate limits reloaded");
+                limiter.reload(config.rate_limit);
+            }
+            Err(e) => warn!("failed to reload rate limits: {e}"),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn reload_on_hangup(_limiter: RateLimiter) {}
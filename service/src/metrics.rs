@@ -0,0 +1,428 @@
+use abi::MetricsConfig;
+use futures::{future::BoxFuture, ready, Future, Stream, StreamExt};
+use http_body::{Body as HttpBody, SizeHint};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, StatusCode,
+};
+use order::OrderManager;
+use prometheus::{
+    core::Collector, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+
+use crate::{is_grpc, rpc_name};
+
+/// the code of a grpc response dropped before its trailers, e.g. the client went away
+const CANCELLED: &str = "1";
+
+/// prometheus collectors of the reservation service
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    registry: Registry,
+    rpc_duration: HistogramVec,
+    db_pool_connections: IntGauge,
+    db_pool_idle_connections: IntGauge,
+    listen_subscribers: IntGauge,
+}
+
+/// decrease the listen subscribers gauge when the stream is dropped
+#[derive(Debug)]
+pub struct SubscriberGuard(IntGauge);
+
+/// stream which keeps the subscriber guard alive
+pub struct GuardedStream<S> {
+    inner: S,
+    _guard: SubscriberGuard,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("rorder".into()), None).unwrap();
+
+        let rpc_duration = HistogramVec::new(
+            HistogramOpts::new("rpc_duration_seconds", "latency of the gRPC calls"),
+            &["method", "code"],
+        )
+        .unwrap();
+        let db_pool_connections =
+            IntGauge::new("db_pool_connections", "connections opened by the pool").unwrap();
+        let db_pool_idle_connections =
+            IntGauge::new("db_pool_idle_connections", "idle connections of the pool").unwrap();
+        let listen_subscribers =
+            IntGauge::new("listen_subscribers", "active subscribers of listen").unwrap();
+
+        registry.register(Box::new(rpc_duration.clone())).unwrap();
+        registry
+            .register(Box::new(db_pool_connections.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(db_pool_idle_connections.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(listen_subscribers.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            rpc_duration,
+            db_pool_connections,
+            db_pool_idle_connections,
+            listen_subscribers,
+        }
+    }
+
+    pub fn observe_rpc(&self, method: &str, code: &str, elapsed: Duration) {
+        self.rpc_duration
+            .with_label_values(&[method, code])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn subscriber_guard(&self) -> SubscriberGuard {
+        self.listen_subscribers.inc();
+        SubscriberGuard(self.listen_subscribers.clone())
+    }
+
+    /// refresh the pool gauges and encode all metrics in the text format
+    pub fn encode(&self, manager: &OrderManager) -> String {
+        let (size, idle) = manager.pool_state();
+        self.db_pool_connections.set(size as i64);
+        self.db_pool_idle_connections.set(idle as i64);
+
+        // the manager counts the conflicts where they are produced, with their resource
+        let conflicts = IntCounterVec::new(
+            Opts::new("conflicts_total", "reservations rejected by conflict").namespace("rorder"),
+            &["resource"],
+        )
+        .unwrap();
+        for (resource, count) in manager.conflicts() {
+            conflicts.with_label_values(&[&resource]).inc_by(count);
+        }
+        let mut families = self.registry.gather();
+        families.extend(
+            conflicts
+                .collect()
+                .into_iter()
+                .filter(|family| !family.get_metric().is_empty()),
+        );
+
+        let mut buf = vec![];
+        TextEncoder::new().encode(&families, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubscriberGuard {
+    pub fn wrap<S>(self, inner: S) -> GuardedStream<S> {
+        GuardedStream {
+            inner,
+            _guard: self,
+        }
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+impl<S: Stream + Unpin> Stream for GuardedStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+/// tower layer which records the latency and grpc status of every rpc
+#[derive(Debug, Clone)]
+pub struct MetricsLayer {
+    metrics: Metrics,
+}
+
+impl MetricsLayer {
+    pub fn new(metrics: Metrics) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+    metrics: Metrics,
+}
+
+/// body which records the rpc once its grpc status is known. The status of a grpc response
+/// is in the trailers, unless it is an error without messages
+pub struct MetricsBody<B> {
+    inner: B,
+    pending: Option<PendingRpc>,
+}
+
+struct PendingRpc {
+    metrics: Metrics,
+    method: String,
+    start: Instant,
+}
+
+impl PendingRpc {
+    fn finish(self, code: &str) {
+        self.metrics
+            .observe_rpc(&self.method, code, self.start.elapsed());
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for MetricsService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<MetricsBody<ResBody>>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        let grpc = is_grpc(&req);
+        let rpc = PendingRpc {
+            metrics: self.metrics.clone(),
+            method: rpc_name(&req).to_string(),
+            start: Instant::now(),
+        };
+        let fut = self.inner.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+            // the rest errors keep their code in the extensions, a trailers only grpc error has
+            // it in the headers, the other grpc responses are recorded with their trailers
+            let code = match res.extensions().get::<tonic::Code>() {
+                Some(code) => Some((*code as i32).to_string()),
+                None => match res.headers().get("grpc-status") {
+                    Some(code) => Some(code.to_str().unwrap_or_default().to_string()),
+                    None if !grpc => Some("0".to_string()),
+                    None => None,
+                },
+            };
+            let pending = match code {
+                Some(code) => {
+                    rpc.finish(&code);
+                    None
+                }
+                None => Some(rpc),
+            };
+            Ok(res.map(|inner| MetricsBody { inner, pending }))
+        })
+    }
+}
+
+impl<B: Default> Default for MetricsBody<B> {
+    fn default() -> Self {
+        Self {
+            inner: B::default(),
+            pending: None,
+        }
+    }
+}
+
+impl<B: HttpBody + Unpin> HttpBody for MetricsBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        let trailers = ready!(Pin::new(&mut self.inner).poll_trailers(cx));
+        if let Ok(Some(trailers)) = &trailers {
+            if let Some(rpc) = self.pending.take() {
+                let code = trailers
+                    .get("grpc-status")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("0");
+                rpc.finish(code);
+            }
+        }
+        Poll::Ready(trailers)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> Drop for MetricsBody<B> {
+    fn drop(&mut self) {
+        if let Some(rpc) = self.pending.take() {
+            rpc.finish(CANCELLED);
+        }
+    }
+}
+
+/// serve the metrics in the configured address and path until the shutdown future completes
+pub async fn serve_metrics(
+    config: &MetricsConfig,
+    metrics: Metrics,
+    manager: OrderManager,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), anyhow::Error> {
+    let addr = format!("{}:{}", config.host, config.port).parse()?;
+    let path = config.path.clone();
+
+    let make_svc = make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        let manager = manager.clone();
+        let path = path.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: http::Request<Body>| {
+                let res = if req.method() == Method::GET && req.uri().path() == path {
+                    http::Response::new(Body::from(metrics.encode(&manager)))
+                } else {
+                    let mut res = http::Response::new(Body::empty());
+                    *res.status_mut() = StatusCode::NOT_FOUND;
+                    res
+                };
+                async move { Ok::<_, Infallible>(res) }
+            }))
+        }
+    });
+
+    hyper::Server::bind(&addr)
+        .serve(make_svc)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::{service_fn, ServiceExt};
+
+    /// a grpc body without messages, the status is in its trailers
+    #[derive(Default)]
+    struct TrailersBody(Option<http::HeaderMap>);
+
+    impl HttpBody for TrailersBody {
+        type Data = hyper::body::Bytes;
+        type Error = Infallible;
+
+        fn poll_data(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(None)
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+            Poll::Ready(Ok(self.0.take()))
+        }
+    }
+
+    #[test]
+    fn metrics_should_be_recorded() {
+        let metrics = Metrics::new();
+        metrics.observe_rpc("add", "0", Duration::from_millis(10));
+        let guard = metrics.subscriber_guard();
+        assert_eq!(1, metrics.listen_subscribers.get());
+        drop(guard);
+        assert_eq!(0, metrics.listen_subscribers.get());
+
+        let mut buf = vec![];
+        TextEncoder::new()
+            .encode(&metrics.registry.gather(), &mut buf)
+            .unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("rorder_rpc_duration_seconds_count{code=\"0\",method=\"add\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn grpc_status_should_be_read_from_the_trailers() {
+        let metrics = Metrics::new();
+        let svc = |headers: Option<&'static str>, trailers: Option<&'static str>| {
+            MetricsLayer::new(metrics.clone()).layer(service_fn(
+                move |_: http::Request<()>| async move {
+                    let mut res = http::Response::builder();
+                    if let Some(code) = headers {
+                        res = res.header("grpc-status", code);
+                    }
+                    let trailers = trailers.map(|code| {
+                        let mut trailers = http::HeaderMap::new();
+                        trailers.insert("grpc-status", code.parse().unwrap());
+                        trailers
+                    });
+                    Ok::<_, Infallible>(res.body(TrailersBody(trailers)).unwrap())
+                },
+            ))
+        };
+        let req = || {
+            http::Request::builder()
+                .uri("/reservation.ReservationService/add")
+                .header("content-type", "application/grpc")
+                .body(())
+                .unwrap()
+        };
+        let count = |code: &str| {
+            metrics
+                .rpc_duration
+                .with_label_values(&["add", code])
+                .get_sample_count()
+        };
+
+        // a trailers only error is recorded with the headers
+        svc(Some("9"), None).oneshot(req()).await.unwrap();
+        assert_eq!(1, count("9"));
+        // the others when the trailers are read
+        let res = svc(None, Some("0")).oneshot(req()).await.unwrap();
+        assert_eq!(0, count("0"));
+        let mut body = res.into_body();
+        assert!(body.data().await.is_none());
+        body.trailers().await.unwrap();
+        assert_eq!(1, count("0"));
+        // the body dropped before the trailers is a cancelled call
+        drop(svc(None, Some("0")).oneshot(req()).await.unwrap());
+        assert_eq!((1, 1), (count("0"), count(CANCELLED)));
+    }
+}
@@ -0,0 +1,944 @@
+use abi::{
+    conflict_info, reservation_service_server::ReservationService, AddRequest, ApproveRequest,
+    CancelRequest, CheckInRequest, CheckOutRequest, ConfirmRequest, DeleteRequest,
+    ExportCalendarRequest, ExtendRequest, FilterRequest, FilterResponse, GetRequest,
+    HistoryRequest, HistoryResponse, HoldRequest, HoldResponse, ImportCalendarRequest,
+    ImportCalendarResponse, JoinWaitlistRequest, LeaveWaitlistRequest, ListDeadLettersRequest,
+    ListDeadLettersResponse, ListWaitlistRequest, ListWaitlistResponse, ListWebhooksRequest,
+    ListWebhooksResponse, NoShowsRequest, NoShowsResponse, PromoteHoldRequest, PurgeRequest,
+    QueryAuditRequest, QueryAuditResponse, QueryRequest, QuotaRequest, QuotaResponse,
+    RegisterWebhookRequest, RejectRequest, ReleaseEarlyRequest, ReplayWebhookRequest,
+    ReplayWebhookResponse, Reservation, ReservationConflictDetails, ReservationConflictInfo,
+    ReservationFilter, ReservationQuery, RestConfig, UnregisterWebhookRequest, UpdateRequest,
+    WaitlistEntry, Webhook, CONFLICT_TYPE_URL,
+};
+use axum::{
+    extract::{MatchedPath, Path, Query, State},
+    http::{header, Method, StatusCode},
+    middleware::map_request,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use futures::{Future, StreamExt};
+use serde_json::json;
+use std::{net::SocketAddr, sync::Arc};
+use tonic::{Code, Request, Status};
+
+use crate::{AuditLayer, MetricsLayer, RateLimitLayer, RsvpService};
+
+/// OpenAPI document of the rest gateway
+pub const OPENAPI: &str = include_str!("../openapi.yaml");
+
+type RestResult<T> = Result<T, RestError>;
+
+/// tonic status in the google.rpc.Status json representation
+#[derive(Debug)]
+pub struct RestError(Status);
+
+/// the rpc called by a rest route, the rate limits and metrics of the rpc apply to the route
+#[derive(Debug, Clone, Copy)]
+pub struct RestRpc(pub &'static str);
+
+/// routes of the rest gateway, every route calls the grpc implementation with the same layers
+pub fn rest_router(svc: Arc<RsvpService>) -> Router {
+    let tenants = svc.tenant_layer();
+    let limits = RateLimitLayer::new(svc.limiter().clone());
+    let metrics = MetricsLayer::new(svc.metrics().clone());
+    Router::new()
+        .route("/reservations", post(add).get(filter))
+        .route("/reservations/query", get(query))
+        .route(
+            "/reservations/calendar",
+            get(export_calendar).post(import_calendar),
+        )
+        .route(
+            "/reservations/:id",
+            get(get_reservation)
+                .patch(update)
+                .delete(delete_reservation),
+        )
+        .route("/reservations/:id/confirm", post(confirm))
+        .route("/reservations/:id/cancel", post(cancel))
+        .route("/reservations/:id/approve", post(approve))
+        .route("/reservations/:id/reject", post(reject))
+        .route("/reservations/:id/purge", post(purge))
+        .route("/reservations/:id/history", get(history))
+        .route("/reservations/:id/check_in", post(check_in))
+        .route("/reservations/:id/check_out", post(check_out))
+        .route("/reservations/:id/extend", post(extend))
+        .route("/reservations/:id/release_early", post(release_early))
+        .route("/no_shows", get(no_shows))
+        .route("/holds", post(hold))
+        .route("/holds/:id/promote", post(promote_hold))
+        .route("/waitlist", post(join_waitlist).get(list_waitlist))
+        .route("/waitlist/:id", delete(leave_waitlist))
+        .route("/webhooks", post(register_webhook).get(list_webhooks))
+        .route("/webhooks/:id", delete(unregister_webhook))
+        .route("/webhooks/:id/dead_letters", get(list_dead_letters))
+        .route("/webhooks/:id/replay", post(replay_webhook))
+        .route("/audit", get(query_audit))
+        .route("/quota", get(quota))
+        .route("/openapi.yaml", get(openapi))
+        .with_state(svc)
+        // the last layer is the outermost, the same order as the grpc server
+        .layer(limits)
+        .layer(tenants)
+        .layer(AuditLayer)
+        .layer(metrics)
+        .layer(map_request(tag_rpc))
+}
+
+/// the rpc of the matched route, or the last segment of the path like the grpc requests
+pub fn rpc_name<B>(req: &http::Request<B>) -> &str {
+    match req.extensions().get::<RestRpc>() {
+        Some(RestRpc(rpc)) => rpc,
+        None => req.uri().path().rsplit('/').next().unwrap_or_default(),
+    }
+}
+
+/// a grpc call, the layers answer it with a trailers only response instead of an http error
+pub fn is_grpc<B>(req: &http::Request<B>) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/grpc"))
+}
+
+async fn tag_rpc<B>(mut req: http::Request<B>) -> http::Request<B> {
+    let path = req.extensions().get::<MatchedPath>();
+    if let Some(rpc) = path.and_then(|path| route_rpc(req.method(), path.as_str())) {
+        req.extensions_mut().insert(RestRpc(rpc));
+    }
+    req
+}
+
+fn route_rpc(method: &Method, path: &str) -> Option<&'static str> {
+    let rpc = match (method.as_str(), path) {
+        ("POST", "/reservations") => "add",
+        ("GET", "/reservations") => "filter",
+        ("GET", "/reservations/query") => "query",
+        ("GET", "/reservations/calendar") => "export_calendar",
+        ("POST", "/reservations/calendar") => "import_calendar",
+        ("GET", "/reservations/:id") => "get",
+        ("PATCH", "/reservations/:id") => "update",
+        ("DELETE", "/reservations/:id") => "delete",
+        ("POST", "/reservations/:id/confirm") => "confirm",
+        ("POST", "/reservations/:id/cancel") => "cancel",
+        ("POST", "/reservations/:id/approve") => "approve",
+        ("POST", "/reservations/:id/reject") => "reject",
+        ("POST", "/reservations/:id/purge") => "purge",
+        ("GET", "/reservations/:id/history") => "history",
+        ("POST", "/reservations/:id/check_in") => "check_in",
+        ("POST", "/reservations/:id/check_out") => "check_out",
+        ("POST", "/reservations/:id/extend") => "extend",
+        ("POST", "/reservations/:id/release_early") => "release_early",
+        ("GET", "/no_shows") => "no_shows",
+        ("POST", "/holds") => "hold",
+        ("POST", "/holds/:id/promote") => "promote_hold",
+        ("POST", "/waitlist") => "join_waitlist",
+        ("GET", "/waitlist") => "list_waitlist",
+        ("DELETE", "/waitlist/:id") => "leave_waitlist",
+        ("POST", "/webhooks") => "register_webhook",
+        ("GET", "/webhooks") => "list_webhooks",
+        ("DELETE", "/webhooks/:id") => "unregister_webhook",
+        ("GET", "/webhooks/:id/dead_letters") => "list_dead_letters",
+        ("POST", "/webhooks/:id/replay") => "replay_webhook",
+        ("GET", "/audit") => "query_audit",
+        ("GET", "/quota") => "quota",
+        _ => return None,
+    };
+    Some(rpc)
+}
+
+/// serve the rest gateway in the configured address until the shutdown future completes
+pub async fn serve_rest(
+    config: &RestConfig,
+    svc: Arc<RsvpService>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), anyhow::Error> {
+    let addr = format!("{}:{}", config.host, config.port).parse()?;
+    axum::Server::bind(&addr)
+        .serve(rest_router(svc).into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+async fn add(
+    State(svc): State<Arc<RsvpService>>,
+    Json(reservation): Json<Reservation>,
+) -> RestResult<(StatusCode, Json<Reservation>)> {
+    let request = Request::new(AddRequest {
+        reservation: Some(reservation),
+    });
+    let rsvp = svc.add(request).await?.into_inner().reservation;
+    Ok((StatusCode::CREATED, Json(rsvp.unwrap_or_default())))
+}
+
+async fn get_reservation(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc.get(Request::new(GetRequest { id })).await?.into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn update(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateRequest>,
+) -> RestResult<Json<Reservation>> {
+    let request = Request::new(UpdateRequest { id, ..request });
+    let rsvp = svc.update(request).await?.into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn confirm(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .confirm(Request::new(ConfirmRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn cancel(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .cancel(Request::new(CancelRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn delete_reservation(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .delete(Request::new(DeleteRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn purge(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .purge(Request::new(PurgeRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn approve(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+    Json(request): Json<ApproveRequest>,
+) -> RestResult<Json<Reservation>> {
+    let request = Request::new(ApproveRequest { id, ..request });
+    let rsvp = svc.approve(request).await?.into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn reject(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+    Json(request): Json<RejectRequest>,
+) -> RestResult<Json<Reservation>> {
+    let request = Request::new(RejectRequest { id, ..request });
+    let rsvp = svc.reject(request).await?.into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn check_in(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .check_in(Request::new(CheckInRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn check_out(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .check_out(Request::new(CheckOutRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn extend(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+    Json(request): Json<ExtendRequest>,
+) -> RestResult<Json<Reservation>> {
+    let request = Request::new(ExtendRequest { id, ..request });
+    let rsvp = svc.extend(request).await?.into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn release_early(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Reservation>> {
+    let rsvp = svc
+        .release_early(Request::new(ReleaseEarlyRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn filter(
+    State(svc): State<Arc<RsvpService>>,
+    Query(filter): Query<ReservationFilter>,
+) -> RestResult<Json<FilterResponse>> {
+    let request = Request::new(FilterRequest {
+        filter: Some(filter),
+    });
+    Ok(Json(svc.filter(request).await?.into_inner()))
+}
+
+async fn query(
+    State(svc): State<Arc<RsvpService>>,
+    Query(query): Query<ReservationQuery>,
+) -> RestResult<Json<Vec<Reservation>>> {
+    let request = Request::new(QueryRequest { query: Some(query) });
+    let mut stream = svc.query(request).await?.into_inner();
+    let mut rsvps = vec![];
+    while let Some(rsvp) = stream.next().await {
+        rsvps.push(rsvp?);
+    }
+    Ok(Json(rsvps))
+}
+
+async fn export_calendar(
+    State(svc): State<Arc<RsvpService>>,
+    Query(query): Query<ReservationQuery>,
+) -> RestResult<impl IntoResponse> {
+    let request = Request::new(ExportCalendarRequest { query: Some(query) });
+    let calendar = svc.export_calendar(request).await?.into_inner().calendar;
+    Ok(([(header::CONTENT_TYPE, "text/calendar")], calendar))
+}
+
+/// the calendar is the body, the owner and the resource are in the query string
+async fn import_calendar(
+    State(svc): State<Arc<RsvpService>>,
+    Query(request): Query<ImportCalendarRequest>,
+    calendar: String,
+) -> RestResult<Json<ImportCalendarResponse>> {
+    let request = Request::new(ImportCalendarRequest {
+        calendar,
+        ..request
+    });
+    Ok(Json(svc.import_calendar(request).await?.into_inner()))
+}
+
+async fn hold(
+    State(svc): State<Arc<RsvpService>>,
+    Json(request): Json<HoldRequest>,
+) -> RestResult<(StatusCode, Json<HoldResponse>)> {
+    let response = svc.hold(Request::new(request)).await?.into_inner();
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn promote_hold(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+    Json(request): Json<PromoteHoldRequest>,
+) -> RestResult<Json<Reservation>> {
+    let request = Request::new(PromoteHoldRequest { id, ..request });
+    let rsvp = svc.promote_hold(request).await?.into_inner();
+    Ok(Json(rsvp.reservation.unwrap_or_default()))
+}
+
+async fn join_waitlist(
+    State(svc): State<Arc<RsvpService>>,
+    Json(entry): Json<WaitlistEntry>,
+) -> RestResult<(StatusCode, Json<WaitlistEntry>)> {
+    let request = Request::new(JoinWaitlistRequest { entry: Some(entry) });
+    let entry = svc.join_waitlist(request).await?.into_inner().entry;
+    Ok((StatusCode::CREATED, Json(entry.unwrap_or_default())))
+}
+
+async fn list_waitlist(
+    State(svc): State<Arc<RsvpService>>,
+    Query(request): Query<ListWaitlistRequest>,
+) -> RestResult<Json<ListWaitlistResponse>> {
+    let request = Request::new(request);
+    Ok(Json(svc.list_waitlist(request).await?.into_inner()))
+}
+
+async fn leave_waitlist(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<WaitlistEntry>> {
+    let entry = svc
+        .leave_waitlist(Request::new(LeaveWaitlistRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(entry.entry.unwrap_or_default()))
+}
+
+async fn quota(
+    State(svc): State<Arc<RsvpService>>,
+    Query(request): Query<QuotaRequest>,
+) -> RestResult<Json<QuotaResponse>> {
+    Ok(Json(svc.quota(Request::new(request)).await?.into_inner()))
+}
+
+async fn no_shows(
+    State(svc): State<Arc<RsvpService>>,
+    Query(request): Query<NoShowsRequest>,
+) -> RestResult<Json<NoShowsResponse>> {
+    Ok(Json(
+        svc.no_shows(Request::new(request)).await?.into_inner(),
+    ))
+}
+
+async fn history(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<HistoryResponse>> {
+    let request = Request::new(HistoryRequest { id });
+    Ok(Json(svc.history(request).await?.into_inner()))
+}
+
+async fn query_audit(
+    State(svc): State<Arc<RsvpService>>,
+    Query(request): Query<QueryAuditRequest>,
+) -> RestResult<Json<QueryAuditResponse>> {
+    Ok(Json(
+        svc.query_audit(Request::new(request)).await?.into_inner(),
+    ))
+}
+
+async fn register_webhook(
+    State(svc): State<Arc<RsvpService>>,
+    Json(webhook): Json<Webhook>,
+) -> RestResult<(StatusCode, Json<Webhook>)> {
+    let request = Request::new(RegisterWebhookRequest {
+        webhook: Some(webhook),
+    });
+    let webhook = svc.register_webhook(request).await?.into_inner().webhook;
+    Ok((StatusCode::CREATED, Json(webhook.unwrap_or_default())))
+}
+
+async fn list_webhooks(
+    State(svc): State<Arc<RsvpService>>,
+) -> RestResult<Json<ListWebhooksResponse>> {
+    let request = Request::new(ListWebhooksRequest {});
+    Ok(Json(svc.list_webhooks(request).await?.into_inner()))
+}
+
+async fn unregister_webhook(
+    State(svc): State<Arc<RsvpService>>,
+    Path(id): Path<i64>,
+) -> RestResult<Json<Webhook>> {
+    let webhook = svc
+        .unregister_webhook(Request::new(UnregisterWebhookRequest { id }))
+        .await?
+        .into_inner();
+    Ok(Json(webhook.webhook.unwrap_or_default()))
+}
+
+async fn list_dead_letters(
+    State(svc): State<Arc<RsvpService>>,
+    Path(webhook_id): Path<i64>,
+) -> RestResult<Json<ListDeadLettersResponse>> {
+    let request = Request::new(ListDeadLettersRequest { webhook_id });
+    Ok(Json(svc.list_dead_letters(request).await?.into_inner()))
+}
+
+async fn replay_webhook(
+    State(svc): State<Arc<RsvpService>>,
+    Path(webhook_id): Path<i64>,
+    Json(request): Json<ReplayWebhookRequest>,
+) -> RestResult<Json<ReplayWebhookResponse>> {
+    let request = Request::new(ReplayWebhookRequest {
+        webhook_id,
+        ..request
+    });
+    Ok(Json(svc.replay_webhook(request).await?.into_inner()))
+}
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/yaml")], OPENAPI)
+}
+
+impl From<Status> for RestError {
+    fn from(status: Status) -> Self {
+        Self(status)
+    }
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        let code = self.0.code();
+        let mut body = json!({
+            "code": code as i32,
+            "message": self.0.message(),
+        });
+        // the conflict and its alternatives are kept like the details of the grpc status
+        if code == Code::FailedPrecondition {
+            if let ReservationConflictInfo::Parsed(conflict) = conflict_info(&self.0) {
+                let mut detail = json!(ReservationConflictDetails::from(conflict.as_ref()));
+                detail["@type"] = json!(CONFLICT_TYPE_URL);
+                body["details"] = json!([detail]);
+            }
+        }
+        let mut res = (http_status(code), Json(body)).into_response();
+        // the metrics record the grpc code of the rest errors
+        res.extensions_mut().insert(code);
+        res
+    }
+}
+
+/// map the grpc code to http status, the same as grpc-gateway except conflicts
+pub fn http_status(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::Cancelled => StatusCode::from_u16(499).unwrap(),
+        Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+        Code::InvalidArgument | Code::OutOfRange => StatusCode::BAD_REQUEST,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        // failed precondition is returned by the conflict reservation
+        Code::AlreadyExists | Code::Aborted | Code::FailedPrecondition => StatusCode::CONFLICT,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestConfig;
+    use axum::{body::Body, http};
+    use tower::ServiceExt;
+
+    async fn call(router: &Router, req: http::Request<Body>) -> (StatusCode, serde_json::Value) {
+        let res = router.clone().oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn rest_create_and_get_reservation_should_be_work() {
+        let config = TestConfig::default();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let body = json!({
+            "user_id": "tosei",
+            "resource_id": "zoom1",
+            "start_time": "2023-01-25T15:00:00-07:00",
+            "end_time": "2023-02-25T12:00:00-07:00",
+            "status": "pending",
+            "note": "test rest create reservation",
+        });
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (status, created) = call(&router, req).await;
+        assert_eq!(StatusCode::CREATED, status);
+        assert_eq!("pending", created["status"]);
+        assert_eq!("2023-01-25T22:00:00+00:00", created["start_time"]);
+
+        let id = created["id"].as_i64().unwrap();
+        let req = http::Request::get(format!("/reservations/{id}"))
+            .body(Body::empty())
+            .unwrap();
+        let (status, got) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(created, got);
+
+        let req = http::Request::get("/reservations?user_id=tosei&status=pending")
+            .body(Body::empty())
+            .unwrap();
+        let (status, filtered) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(created, filtered["reservations"][0]);
+
+        // conflict reservation is mapped to 409
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (status, error) = call(&router, req).await;
+        assert_eq!(StatusCode::CONFLICT, status);
+        assert_eq!(Code::FailedPrecondition as i32, error["code"]);
+        let conflict = &error["details"][0];
+        assert_eq!(CONFLICT_TYPE_URL, conflict["@type"]);
+        assert_eq!(id, conflict["conflicting_id"]);
+        assert_eq!("pending", conflict["conflicting_status"]);
+        assert_eq!("2023-01-25T22:00:00+00:00", conflict["conflicting_start"]);
+        assert!(conflict["alternatives"].is_array());
+    }
+
+    #[tokio::test]
+    async fn rest_should_share_the_rate_limits_and_metrics_of_the_rpcs() {
+        let mut config = TestConfig::default();
+        config.config.rate_limit.rpcs.get_mut("add").unwrap().burst = 1;
+        let svc = Arc::new(RsvpService::from_config(&config).await.unwrap());
+        let router = rest_router(svc.clone());
+
+        let add = || {
+            let body = json!({
+                "user_id": "tosei",
+                "resource_id": "zoom1",
+                "start_time": "2023-01-25T15:00:00-07:00",
+                "end_time": "2023-01-25T16:00:00-07:00",
+            });
+            http::Request::post("/reservations")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let (status, _) = call(&router, add()).await;
+        assert_eq!(StatusCode::CREATED, status);
+        let res = router.clone().oneshot(add()).await.unwrap();
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, res.status());
+        assert_eq!("1", res.headers()["retry-after"]);
+        let req = http::Request::get("/reservations/10000")
+            .body(Body::empty())
+            .unwrap();
+        let (status, _) = call(&router, req).await;
+        assert_eq!(StatusCode::NOT_FOUND, status);
+
+        let metrics = svc.metrics().encode(svc.manager());
+        assert!(metrics.contains(r#"rpc_duration_seconds_count{code="0",method="add"} 1"#));
+        assert!(metrics.contains(r#"rpc_duration_seconds_count{code="8",method="add"} 1"#));
+        assert!(metrics.contains(r#"rpc_duration_seconds_count{code="5",method="get"} 1"#));
+    }
+
+    /// a bearer token of the user in the default tenant, signed by the secret of `jwt_config`
+    fn bearer(user: &str) -> String {
+        let exp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 600;
+        let claims = json!({"tenant_id": "default", "sub": user, "exp": exp});
+        let key = jsonwebtoken::EncodingKey::from_secret(b"secret");
+        let token = jsonwebtoken::encode(&Default::default(), &claims, &key).unwrap();
+        format!("Bearer {token}")
+    }
+
+    fn jwt_config() -> TestConfig {
+        let mut config = TestConfig::default();
+        config.config.tenancy.jwt_secret = Some("secret".to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn rest_approve_should_be_refused_to_a_caller_not_in_the_step() {
+        let config = jwt_config();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let body = json!({
+            "user_id": "tosei",
+            "resource_id": "boardroom-1",
+            "start_time": "2023-01-25T15:00:00-07:00",
+            "end_time": "2023-01-25T16:00:00-07:00",
+            "status": "pending",
+        });
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::AUTHORIZATION, bearer("tosei"))
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (status, created) = call(&router, req).await;
+        assert_eq!(StatusCode::CREATED, status);
+
+        // the approver of the body and the user id header are not trusted
+        let id = created["id"].as_i64().unwrap();
+        let approve = |user: &str| {
+            http::Request::post(format!("/reservations/{id}/approve"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::AUTHORIZATION, bearer(user))
+                .header(crate::USER_ID_METADATA, "alice")
+                .body(Body::from(json!({"approver": "alice"}).to_string()))
+                .unwrap()
+        };
+        let (status, error) = call(&router, approve("mallory")).await;
+        assert_eq!(StatusCode::FORBIDDEN, status);
+        assert_eq!(
+            "Not an approver of the current step: mallory",
+            error["message"]
+        );
+
+        let (status, approved) = call(&router, approve("alice")).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!("pending", approved["status"]);
+        let req = http::Request::get(format!("/reservations/{id}/history"))
+            .header(header::AUTHORIZATION, bearer("tosei"))
+            .body(Body::empty())
+            .unwrap();
+        let (_, history) = call(&router, req).await;
+        assert_eq!("tosei", history["entries"][0]["actor"]);
+    }
+
+    #[tokio::test]
+    async fn rest_approve_should_require_a_verified_user() {
+        let config = TestConfig::default();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let req = http::Request::post("/reservations/1/approve")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(crate::USER_ID_METADATA, "alice")
+            .body(Body::from(json!({"approver": "alice"}).to_string()))
+            .unwrap();
+        let (status, error) = call(&router, req).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+        assert_eq!(Code::Unauthenticated as i32, error["code"]);
+    }
+
+    #[tokio::test]
+    async fn rest_history_should_record_the_user_of_the_request() {
+        let config = jwt_config();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let body = json!({
+            "user_id": "tosei",
+            "resource_id": "zoom1",
+            "start_time": "2023-01-25T15:00:00-07:00",
+            "end_time": "2023-01-25T16:00:00-07:00",
+        });
+        // the user of the token is recorded rather than the one of the metadata
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::AUTHORIZATION, bearer("tosei"))
+            .header(crate::USER_ID_METADATA, "mallory")
+            .header(crate::REQUEST_ID_METADATA, "req-1")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (_, created) = call(&router, req).await;
+
+        let id = created["id"].as_i64().unwrap();
+        let req = http::Request::get(format!("/reservations/{id}/history"))
+            .header(header::AUTHORIZATION, bearer("tosei"))
+            .body(Body::empty())
+            .unwrap();
+        let (status, history) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+        let entry = &history["entries"][0];
+        assert_eq!("tosei", entry["actor"]);
+        assert_eq!("req-1", entry["request_id"]);
+        assert_eq!("", entry["before"]);
+
+        // the audit log is read by the admins only
+        let audit = |user: &str| {
+            http::Request::get("/audit?actor=tosei")
+                .header(header::AUTHORIZATION, bearer(user))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let (status, _) = call(&router, audit("tosei")).await;
+        assert_eq!(StatusCode::FORBIDDEN, status);
+        let (status, audit) = call(&router, audit("admin")).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(entry, &audit["entries"][0]);
+    }
+
+    #[tokio::test]
+    async fn rest_check_in_should_be_refused_twice() {
+        let config = TestConfig::default();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let now = std::time::SystemTime::now();
+        let rsvp = Reservation {
+            user_id: "tosei".to_string(),
+            resource_id: "zoom1".to_string(),
+            start_time: Some(now.into()),
+            end_time: Some((now + std::time::Duration::from_secs(3600)).into()),
+            status: abi::ReservationStatus::Confirmed as i32,
+            ..Default::default()
+        };
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&rsvp).unwrap()))
+            .unwrap();
+        let (_, created) = call(&router, req).await;
+
+        let id = created["id"].as_i64().unwrap();
+        let check_in = || {
+            http::Request::post(format!("/reservations/{id}/check_in"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let (status, checked_in) = call(&router, check_in()).await;
+        assert_eq!(StatusCode::OK, status);
+        assert!(checked_in["checked_in_at"].is_string());
+        let (status, error) = call(&router, check_in()).await;
+        assert_eq!(StatusCode::BAD_REQUEST, status);
+        assert_eq!(Code::InvalidArgument as i32, error["code"]);
+
+        let req = http::Request::post(format!("/reservations/{id}/check_out"))
+            .body(Body::empty())
+            .unwrap();
+        let (status, checked_out) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+        assert!(checked_out["checked_out_at"].is_string());
+
+        let req = http::Request::get("/no_shows?user_id=tosei")
+            .body(Body::empty())
+            .unwrap();
+        let (status, no_shows) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(0, no_shows["count"]);
+    }
+
+    #[tokio::test]
+    async fn rest_calendar_should_be_exported_and_imported() {
+        let config = TestConfig::default();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let body = json!({
+            "user_id": "tosei",
+            "resource_id": "zoom1",
+            "start_time": "2023-01-25T15:00:00-07:00",
+            "end_time": "2023-01-25T16:00:00-07:00",
+            "note": "sprint review",
+        });
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (_, created) = call(&router, req).await;
+
+        let query = "user_id=tosei&start=2023-01-01T00:00:00Z&end=2023-02-01T00:00:00Z";
+        let req = http::Request::get(format!("/reservations/calendar?{query}"))
+            .body(Body::empty())
+            .unwrap();
+        let res = router.clone().oneshot(req).await.unwrap();
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!("text/calendar", res.headers()[header::CONTENT_TYPE]);
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        let calendar = String::from_utf8(body.to_vec()).unwrap();
+        let uid = format!("reservation-{}@rsvp", created["id"]);
+        assert!(calendar.contains(&format!("UID:{uid}\r\n")));
+
+        // the window of zoom1 is booked, the one of zoom2 is free
+        for (rid, error) in [("zoom1", true), ("zoom2", false)] {
+            let req = http::Request::post(format!(
+                "/reservations/calendar?user_id=wxy&resource_id={rid}"
+            ))
+            .header(header::CONTENT_TYPE, "text/calendar")
+            .body(Body::from(calendar.clone()))
+            .unwrap();
+            let (status, report) = call(&router, req).await;
+            assert_eq!(StatusCode::OK, status);
+            let event = &report["events"][0];
+            assert_eq!(uid, event["uid"]);
+            assert_eq!(error, event["error"] != "");
+            assert_eq!(!error, event["reservation"]["user_id"] == "wxy");
+        }
+
+        let req = http::Request::post("/reservations/calendar?user_id=wxy")
+            .body(Body::from("BEGIN:VCALENDAR\r\n"))
+            .unwrap();
+        let (status, error) = call(&router, req).await;
+        assert_eq!(StatusCode::BAD_REQUEST, status);
+        assert_eq!("Invalid calendar: VCALENDAR is not ended", error["message"]);
+    }
+
+    #[tokio::test]
+    async fn rest_get_missing_reservation_should_be_not_found() {
+        let config = TestConfig::default();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let req = http::Request::get("/reservations/10000")
+            .body(Body::empty())
+            .unwrap();
+        let (status, error) = call(&router, req).await;
+        assert_eq!(StatusCode::NOT_FOUND, status);
+        assert_eq!(Code::NotFound as i32, error["code"]);
+    }
+
+    #[tokio::test]
+    async fn rest_waitlist_should_be_fulfilled_after_cancel() {
+        let config = TestConfig::default();
+        let svc = RsvpService::from_config(&config).await.unwrap();
+        let router = rest_router(Arc::new(svc));
+
+        let body = json!({
+            "user_id": "tosei",
+            "resource_id": "zoom1",
+            "start_time": "2023-01-25T15:00:00-07:00",
+            "end_time": "2023-01-26T12:00:00-07:00",
+            "status": "pending",
+        });
+        let req = http::Request::post("/reservations")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (_, rsvp) = call(&router, req).await;
+
+        let body = json!({
+            "user_id": "wxy",
+            "resource_id": "zoom1",
+            "start_time": "2023-01-25T15:00:00-07:00",
+            "end_time": "2023-01-26T12:00:00-07:00",
+            "expires_at": "2099-01-01T00:00:00Z",
+        });
+        let req = http::Request::post("/waitlist")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let (status, entry) = call(&router, req).await;
+        assert_eq!(StatusCode::CREATED, status);
+        assert_eq!("waiting", entry["status"]);
+
+        let req = http::Request::get("/waitlist?resource_id=zoom1")
+            .body(Body::empty())
+            .unwrap();
+        let (status, listed) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(entry, listed["entries"][0]);
+
+        let id = rsvp["id"].as_i64().unwrap();
+        let req = http::Request::post(format!("/reservations/{id}/cancel"))
+            .body(Body::empty())
+            .unwrap();
+        let (status, _) = call(&router, req).await;
+        assert_eq!(StatusCode::OK, status);
+
+        // the entry is converted into a reservation, so it can not be left
+        let id = entry["id"].as_i64().unwrap();
+        let req = http::Request::delete(format!("/waitlist/{id}"))
+            .body(Body::empty())
+            .unwrap();
+        let (status, _) = call(&router, req).await;
+        assert_eq!(StatusCode::NOT_FOUND, status);
+
+        let req = http::Request::get("/reservations?user_id=wxy&status=pending")
+            .body(Body::empty())
+            .unwrap();
+        let (_, filtered) = call(&router, req).await;
+        assert_eq!("zoom1", filtered["reservations"][0]["resource_id"]);
+    }
+}
@@ -1,30 +1,94 @@
 use futures::Stream;
-use order::{Order, OrderManager};
+use order::{
+    spawn_scoped, Actor, Adjust, Approval, Audit, CheckIn, Hold, Order, OrderManager, Outbox,
+    Quota, Retention, Waitlist,
+};
 use std::{
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 use tokio::sync::mpsc;
-use tonic::{async_trait, Request, Response, Status};
+use tokio_util::sync::CancellationToken;
+use tonic::{async_trait, Request, Response, Status, Streaming};
 
 use abi::{
-    reservation_service_server::ReservationService, AddRequest, AddResponse, CancelRequest,
-    CancelResponse, Config, ConfirmRequest, ConfirmResponse, FilterRequest, FilterResponse,
-    GetRequest, GetResponse, ListenRequest, QueryRequest, UpdateRequest, UpdateResponse,
+    convert_to_timestamp, convert_to_utc_time, reservation_service_server::ReservationService,
+    AddRequest, AddResponse, ApproveRequest, ApproveResponse, CancelRequest, CancelResponse,
+    CheckInRequest, CheckInResponse, CheckOutRequest, CheckOutResponse, Config, ConfirmRequest,
+    ConfirmResponse, DeleteRequest, DeleteResponse, Error, ExportCalendarRequest,
+    ExportCalendarResponse, ExportRequest, ExtendRequest, ExtendResponse, FilterRequest,
+    FilterResponse, GetRequest, GetResponse, HistoryRequest, HistoryResponse, HoldConfig,
+    HoldRequest, HoldResponse, ImportCalendarRequest, ImportCalendarResponse, ImportRequest,
+    ImportedEvent, JoinWaitlistRequest, JoinWaitlistResponse, LeaveWaitlistRequest,
+    LeaveWaitlistResponse, ListDeadLettersRequest, ListDeadLettersResponse, ListWaitlistRequest,
+    ListWaitlistResponse, ListWebhooksRequest, ListWebhooksResponse, ListenRequest, NoShowsRequest,
+    NoShowsResponse, PromoteHoldRequest, PromoteHoldResponse, PurgeRequest, PurgeResponse,
+    QueryAuditRequest, QueryAuditResponse, QueryRequest, QuotaRequest, QuotaResponse,
+    RegisterWebhookRequest, RegisterWebhookResponse, RejectRequest, RejectResponse,
+    ReleaseEarlyRequest, ReleaseEarlyResponse, ReplayWebhookRequest, ReplayWebhookResponse,
+    UnregisterWebhookRequest, UnregisterWebhookResponse, UpdateRequest, UpdateResponse,
 };
 
-use crate::{ReservationResponseStream, TonicReceiverStream};
+use crate::{
+    bulk::{export_rows, import_rows},
+    DrainStream, EventResponseStream, ImportResponseStream, Metrics, RateLimiter,
+    ReservationResponseStream, TenantLayer, TonicReceiverStream, WaitlistResponseStream,
+};
 
 pub struct RsvpService {
     manager: OrderManager,
+    metrics: Metrics,
+    shutdown: CancellationToken,
+    hold: HoldConfig,
+    tenants: TenantLayer,
+    limiter: RateLimiter,
 }
 
 impl RsvpService {
     pub async fn from_config(config: &Config) -> Result<Self, anyhow::Error> {
         Ok(Self {
-            manager: OrderManager::from_config(&config.db).await?,
+            manager: OrderManager::from_config(&config.db)
+                .await?
+                .with_waitlist_mode(config.waitlist.mode)
+                .with_buffers(config.buffers.clone())
+                .with_rules(config.rules.clone())
+                .with_quotas(config.quotas.clone())
+                .with_approvals(config.approvals.clone())
+                .with_auth(config.auth.clone())
+                .with_retention(config.retention.clone())
+                .with_check_in(config.check_in.clone())
+                .with_tenants(config.tenancy.tenants.clone()),
+            metrics: Metrics::new(),
+            shutdown: CancellationToken::new(),
+            hold: config.hold.clone(),
+            tenants: TenantLayer::new(&config.tenancy),
+            limiter: RateLimiter::new(config.rate_limit.clone()),
         })
     }
+
+    pub fn manager(&self) -> &OrderManager {
+        &self.manager
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// resolve the tenant of the requests to the servers
+    pub fn tenant_layer(&self) -> TenantLayer {
+        self.tenants.clone()
+    }
+
+    /// the rate limits shared by the grpc and rest servers
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    /// cancel the token to close the query and listen streams
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
 }
 
 #[async_trait]
@@ -35,10 +99,8 @@ impl ReservationService for RsvpService {
         if request.reservation.is_none() {
             return Err(Status::invalid_argument("reservation is required"));
         }
-        let rsvp = self
-            .manager
-            .create_order(request.reservation.unwrap())
-            .await?;
+        let rsvp = request.reservation.unwrap();
+        let rsvp = self.manager.create_order(rsvp).await?;
         Ok(Response::new(AddResponse {
             reservation: Some(rsvp),
         }))
@@ -106,6 +168,7 @@ impl ReservationService for RsvpService {
             .manager
             .query_reservations(request.query.unwrap())
             .await;
+        // a finite stream is finished before the shutdown timeout instead of being cut
         let stream = TonicReceiverStream::new(rx);
         Ok(Response::new(Box::pin(stream) as Self::queryStream))
     }
@@ -135,7 +198,430 @@ impl ReservationService for RsvpService {
         &self,
         _request: Request<ListenRequest>,
     ) -> Result<Response<Self::listenStream>, Status> {
-        todo!()
+        let rx = self.manager.listen().await;
+        // the guard is dropped together with the stream when the client disconnects
+        let stream = self.metrics.subscriber_guard().wrap(DrainStream::new(
+            TonicReceiverStream::new(rx),
+            self.shutdown.clone(),
+        ));
+        Ok(Response::new(Box::pin(stream) as Self::listenStream))
+    }
+
+    /// queue for a window which is already booked
+    async fn join_waitlist(
+        &self,
+        request: Request<JoinWaitlistRequest>,
+    ) -> Result<Response<JoinWaitlistResponse>, Status> {
+        let request = request.into_inner();
+        if request.entry.is_none() {
+            return Err(Status::invalid_argument("entry is required"));
+        }
+        let entry = self.manager.join_waitlist(request.entry.unwrap()).await?;
+        Ok(Response::new(JoinWaitlistResponse { entry: Some(entry) }))
+    }
+
+    /// leave the waitlist
+    async fn leave_waitlist(
+        &self,
+        request: Request<LeaveWaitlistRequest>,
+    ) -> Result<Response<LeaveWaitlistResponse>, Status> {
+        let request = request.into_inner();
+        let entry = self.manager.leave_waitlist(request.id).await?;
+        Ok(Response::new(LeaveWaitlistResponse { entry: Some(entry) }))
+    }
+
+    /// list the waitlist of a resource or a user
+    async fn list_waitlist(
+        &self,
+        request: Request<ListWaitlistRequest>,
+    ) -> Result<Response<ListWaitlistResponse>, Status> {
+        let request = request.into_inner();
+        let entries = self
+            .manager
+            .list_waitlist(&request.resource_id, &request.user_id)
+            .await?;
+        Ok(Response::new(ListWaitlistResponse { entries }))
+    }
+
+    type listen_waitlistStream = WaitlistResponseStream;
+    /// another system could notify the users whose entries are notified or fulfilled
+    async fn listen_waitlist(
+        &self,
+        _request: Request<ListenRequest>,
+    ) -> Result<Response<Self::listen_waitlistStream>, Status> {
+        let rx = self.manager.listen_waitlist().await;
+        let stream = self.metrics.subscriber_guard().wrap(DrainStream::new(
+            TonicReceiverStream::new(rx),
+            self.shutdown.clone(),
+        ));
+        Ok(Response::new(
+            Box::pin(stream) as Self::listen_waitlistStream
+        ))
+    }
+
+    /// lock a window for a short time, e.g. while the user is paying
+    async fn hold(&self, request: Request<HoldRequest>) -> Result<Response<HoldResponse>, Status> {
+        let request = request.into_inner();
+        if request.reservation.is_none() {
+            return Err(Status::invalid_argument("reservation is required"));
+        }
+        let ttl = match request.ttl_seconds as u64 {
+            0 => self.hold.default_ttl,
+            ttl if ttl > self.hold.max_ttl => {
+                return Err(Status::invalid_argument(format!(
+                    "ttl_seconds must not exceed {}",
+                    self.hold.max_ttl
+                )))
+            }
+            ttl => ttl,
+        };
+        let (rsvp, expires_at) = self
+            .manager
+            .hold(request.reservation.unwrap(), Duration::from_secs(ttl))
+            .await?;
+        Ok(Response::new(HoldResponse {
+            reservation: Some(rsvp),
+            expires_at: Some(convert_to_timestamp(expires_at)),
+        }))
+    }
+
+    /// turn an unexpired hold into a pending or confirmed reservation
+    async fn promote_hold(
+        &self,
+        request: Request<PromoteHoldRequest>,
+    ) -> Result<Response<PromoteHoldResponse>, Status> {
+        let request = request.into_inner();
+        let rsvp = self
+            .manager
+            .promote_hold(request.id, request.confirm)
+            .await?;
+        Ok(Response::new(PromoteHoldResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// the usage and the remaining quotas of a user
+    async fn quota(
+        &self,
+        request: Request<QuotaRequest>,
+    ) -> Result<Response<QuotaResponse>, Status> {
+        let request = request.into_inner();
+        let quotas = self.manager.quota(&request.user_id).await?;
+        Ok(Response::new(QuotaResponse { quotas }))
+    }
+
+    /// approve a pending reservation of a restricted resource as an approver of its current step
+    async fn approve(
+        &self,
+        request: Request<ApproveRequest>,
+    ) -> Result<Response<ApproveResponse>, Status> {
+        let approver = Actor::verified_user()?;
+        let request = request.into_inner();
+        let rsvp = self
+            .manager
+            .approve(request.id, &approver, &request.reason)
+            .await?;
+        Ok(Response::new(ApproveResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// reject a pending reservation of a restricted resource, the window is freed
+    async fn reject(
+        &self,
+        request: Request<RejectRequest>,
+    ) -> Result<Response<RejectResponse>, Status> {
+        let approver = Actor::verified_user()?;
+        let request = request.into_inner();
+        let rsvp = self
+            .manager
+            .reject(request.id, &approver, &request.reason)
+            .await?;
+        Ok(Response::new(RejectResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    type listen_eventsStream = EventResponseStream;
+    /// the changes of the reservations with their update type, e.g. approval requested
+    async fn listen_events(
+        &self,
+        _request: Request<ListenRequest>,
+    ) -> Result<Response<Self::listen_eventsStream>, Status> {
+        let rx = self.manager.listen_events().await;
+        let stream = self.metrics.subscriber_guard().wrap(DrainStream::new(
+            TonicReceiverStream::new(rx),
+            self.shutdown.clone(),
+        ));
+        Ok(Response::new(Box::pin(stream) as Self::listen_eventsStream))
+    }
+
+    /// hide a reservation from get, query and filter, an active one is cancelled
+    async fn delete(
+        &self,
+        request: Request<DeleteRequest>,
+    ) -> Result<Response<DeleteResponse>, Status> {
+        let rsvp = self
+            .manager
+            .delete_reservation(request.into_inner().id)
+            .await?;
+        Ok(Response::new(DeleteResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// remove a reservation and its history of approvals and holds, for the admins
+    async fn purge(
+        &self,
+        request: Request<PurgeRequest>,
+    ) -> Result<Response<PurgeResponse>, Status> {
+        let rsvp = self
+            .manager
+            .purge_reservation(request.into_inner().id)
+            .await?;
+        Ok(Response::new(PurgeResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// post the reservation changes matching the filters to the url
+    async fn register_webhook(
+        &self,
+        request: Request<RegisterWebhookRequest>,
+    ) -> Result<Response<RegisterWebhookResponse>, Status> {
+        let webhook = match request.into_inner().webhook {
+            Some(webhook) => webhook,
+            None => return Err(Status::invalid_argument("webhook is required")),
+        };
+        let webhook = self.manager.register_webhook(webhook).await?;
+        Ok(Response::new(RegisterWebhookResponse {
+            webhook: Some(webhook),
+        }))
+    }
+
+    /// stop posting to the webhook, its pending deliveries are dropped
+    async fn unregister_webhook(
+        &self,
+        request: Request<UnregisterWebhookRequest>,
+    ) -> Result<Response<UnregisterWebhookResponse>, Status> {
+        let webhook = self
+            .manager
+            .unregister_webhook(request.into_inner().id)
+            .await?;
+        Ok(Response::new(UnregisterWebhookResponse {
+            webhook: Some(webhook),
+        }))
+    }
+
+    /// the registered webhooks, without their secrets
+    async fn list_webhooks(
+        &self,
+        _request: Request<ListWebhooksRequest>,
+    ) -> Result<Response<ListWebhooksResponse>, Status> {
+        let webhooks = self.manager.list_webhooks().await?;
+        Ok(Response::new(ListWebhooksResponse { webhooks }))
+    }
+
+    /// the deliveries of a webhook which failed every attempt
+    async fn list_dead_letters(
+        &self,
+        request: Request<ListDeadLettersRequest>,
+    ) -> Result<Response<ListDeadLettersResponse>, Status> {
+        let dead_letters = self
+            .manager
+            .list_dead_letters(request.into_inner().webhook_id)
+            .await?;
+        Ok(Response::new(ListDeadLettersResponse { dead_letters }))
+    }
+
+    /// deliver the dead letters of a webhook again
+    async fn replay_webhook(
+        &self,
+        request: Request<ReplayWebhookRequest>,
+    ) -> Result<Response<ReplayWebhookResponse>, Status> {
+        let request = request.into_inner();
+        let replayed = self
+            .manager
+            .replay_webhook(request.webhook_id, &request.ids)
+            .await?;
+        Ok(Response::new(ReplayWebhookResponse { replayed }))
+    }
+
+    /// the reservations of a user or a resource as an iCalendar
+    async fn export_calendar(
+        &self,
+        request: Request<ExportCalendarRequest>,
+    ) -> Result<Response<ExportCalendarResponse>, Status> {
+        let query = match request.into_inner().query {
+            Some(query) => query,
+            None => return Err(Status::invalid_argument("query is required")),
+        };
+        if query.user_id.is_empty() && query.resource_id.is_empty() {
+            return Err(Status::invalid_argument(
+                "user_id or resource_id is required",
+            ));
+        }
+        let mut rx = self.manager.query_reservations(query).await;
+        let mut rsvps = vec![];
+        while let Some(rsvp) = rx.recv().await {
+            rsvps.push(rsvp?);
+        }
+        Ok(Response::new(ExportCalendarResponse {
+            calendar: abi::to_calendar(&rsvps),
+        }))
+    }
+
+    /// make a reservation of each event the same as add, the failed ones do not stop the rest
+    async fn import_calendar(
+        &self,
+        request: Request<ImportCalendarRequest>,
+    ) -> Result<Response<ImportCalendarResponse>, Status> {
+        let request = request.into_inner();
+        let mut events = vec![];
+        for event in abi::parse_calendar(&request.calendar)? {
+            let rsvp = event.to_reservation(&request.user_id, &request.resource_id);
+            let added = match rsvp {
+                Ok(rsvp) => self
+                    .add(Request::new(AddRequest {
+                        reservation: Some(rsvp),
+                    }))
+                    .await
+                    .map(|response| response.into_inner().reservation)
+                    .map_err(|status| status.message().to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            let (reservation, error) = match added {
+                Ok(reservation) => (reservation, String::new()),
+                Err(error) => (None, error),
+            };
+            events.push(ImportedEvent {
+                uid: event.uid,
+                reservation,
+                error,
+            });
+        }
+        Ok(Response::new(ImportCalendarResponse { events }))
+    }
+
+    type importStream = ImportResponseStream;
+    /// import the streamed rows in chunks, the results are streamed after each chunk
+    async fn import(
+        &self,
+        request: Request<Streaming<ImportRequest>>,
+    ) -> Result<Response<Self::importStream>, Status> {
+        let rows = request.into_inner();
+        let (tx, rx) = mpsc::channel(128);
+        let manager = self.manager.clone();
+        spawn_scoped(async move {
+            if let Err(e) = import_rows(manager, rows, tx.clone()).await {
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+        Ok(Response::new(
+            Box::pin(TonicReceiverStream::new(rx)) as Self::importStream
+        ))
+    }
+
+    type exportStream = ReservationResponseStream;
+    /// stream every reservation matching the filter, order by id
+    async fn export(
+        &self,
+        request: Request<ExportRequest>,
+    ) -> Result<Response<Self::exportStream>, Status> {
+        let filter = match request.into_inner().filter {
+            Some(filter) => filter,
+            None => return Err(Status::invalid_argument("filter is required")),
+        };
+        let (tx, rx) = mpsc::channel(128);
+        let manager = self.manager.clone();
+        spawn_scoped(async move {
+            if let Err(e) = export_rows(manager, filter, tx.clone()).await {
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+        let stream = TonicReceiverStream::new(rx);
+        Ok(Response::new(Box::pin(stream) as Self::exportStream))
+    }
+
+    /// who changed the reservation and what it was before, in the order of the changes
+    async fn history(
+        &self,
+        request: Request<HistoryRequest>,
+    ) -> Result<Response<HistoryResponse>, Status> {
+        let entries = self.manager.history(request.into_inner().id).await?;
+        Ok(Response::new(HistoryResponse { entries }))
+    }
+
+    /// the audit log of an actor in a time range, for the admins
+    async fn query_audit(
+        &self,
+        request: Request<QueryAuditRequest>,
+    ) -> Result<Response<QueryAuditResponse>, Status> {
+        let (entries, next_cursor) = self.manager.query_audit(request.into_inner()).await?;
+        Ok(Response::new(QueryAuditResponse {
+            entries,
+            next_cursor,
+        }))
+    }
+
+    /// check in a confirmed reservation from shortly before its start until its end
+    async fn check_in(
+        &self,
+        request: Request<CheckInRequest>,
+    ) -> Result<Response<CheckInResponse>, Status> {
+        let rsvp = self.manager.check_in(request.into_inner().id).await?;
+        Ok(Response::new(CheckInResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// check out a checked in reservation
+    async fn check_out(
+        &self,
+        request: Request<CheckOutRequest>,
+    ) -> Result<Response<CheckOutResponse>, Status> {
+        let rsvp = self.manager.check_out(request.into_inner().id).await?;
+        Ok(Response::new(CheckOutResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// the count of the no-shows of a user since the time, all of them without it
+    async fn no_shows(
+        &self,
+        request: Request<NoShowsRequest>,
+    ) -> Result<Response<NoShowsResponse>, Status> {
+        let request = request.into_inner();
+        let since = request.since.as_ref().map(convert_to_utc_time);
+        let count = self.manager.no_shows(&request.user_id, since).await?;
+        Ok(Response::new(NoShowsResponse { count }))
+    }
+
+    /// move the end of the reservation later, a conflict has the latest end it could take
+    async fn extend(
+        &self,
+        request: Request<ExtendRequest>,
+    ) -> Result<Response<ExtendResponse>, Status> {
+        let request = request.into_inner();
+        let end = request.end_time.as_ref().ok_or(Error::InvalidTime)?;
+        let rsvp = self
+            .manager
+            .extend(request.id, convert_to_utc_time(end))
+            .await?;
+        Ok(Response::new(ExtendResponse {
+            reservation: Some(rsvp),
+        }))
+    }
+
+    /// end the ongoing reservation now, the rest of its window could be booked again
+    async fn release_early(
+        &self,
+        request: Request<ReleaseEarlyRequest>,
+    ) -> Result<Response<ReleaseEarlyResponse>, Status> {
+        let rsvp = self.manager.release_early(request.into_inner().id).await?;
+        Ok(Response::new(ReleaseEarlyResponse {
+            reservation: Some(rsvp),
+        }))
     }
 }
 
@@ -191,4 +677,104 @@ mod tests {
         assert_eq!(reservation1.note, reservation.note);
         assert_eq!(reservation1.status, reservation.status);
     }
+
+    #[tokio::test]
+    async fn rpc_hold_should_limit_the_ttl() {
+        let config = TestConfig::default();
+        let service = RsvpService::from_config(&config).await.unwrap();
+        let reservation = Reservation::new_pending(
+            "tosei",
+            "zoom1",
+            "2023-01-25T15:00:00-0700".parse().unwrap(),
+            "2023-01-25T16:00:00-0700".parse().unwrap(),
+            "",
+        );
+        let request = Request::new(HoldRequest {
+            reservation: Some(reservation.clone()),
+            ttl_seconds: 7200,
+        });
+        let status = service.hold(request).await.unwrap_err();
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+
+        let request = Request::new(HoldRequest {
+            reservation: Some(reservation),
+            ttl_seconds: 0,
+        });
+        let response = service.hold(request).await.unwrap().into_inner();
+        let held = response.reservation.unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap();
+        let ttl = response.expires_at.unwrap().seconds - now.as_secs() as i64;
+        assert!((590..=600).contains(&ttl));
+
+        let request = Request::new(PromoteHoldRequest {
+            id: held.id,
+            confirm: false,
+        });
+        let rsvp = service.promote_hold(request).await.unwrap().into_inner();
+        assert_eq!(
+            abi::ReservationStatus::Pending as i32,
+            rsvp.reservation.unwrap().status
+        );
+    }
+
+    #[tokio::test]
+    async fn rpc_quota_should_return_the_configured_quotas() {
+        let config = TestConfig::default();
+        let service = RsvpService::from_config(&config).await.unwrap();
+        let request = Request::new(QuotaRequest {
+            user_id: "tosei".to_string(),
+        });
+        let quotas = service.quota(request).await.unwrap().into_inner().quotas;
+        assert_eq!(1, quotas.len());
+        assert_eq!("weekly:meeting-room-*", quotas[0].name);
+        assert_eq!(36000, quotas[0].remaining);
+
+        let request = Request::new(QuotaRequest::default());
+        let status = service.quota(request).await.unwrap_err();
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+    }
+
+    #[tokio::test]
+    async fn rpc_query_should_finish_while_listen_is_closed_on_shutdown() {
+        use futures::StreamExt;
+
+        let config = TestConfig::default();
+        let service = RsvpService::from_config(&config).await.unwrap();
+        let reservation = Reservation::new_pending(
+            "tosei",
+            "zoom1",
+            "2023-01-25T15:00:00-0700".parse().unwrap(),
+            "2023-02-25T12:00:00-0700".parse().unwrap(),
+            "",
+        );
+        let request = Request::new(AddRequest {
+            reservation: Some(reservation),
+        });
+        service.add(request).await.unwrap();
+        let request = Request::new(ListenRequest {});
+        let mut listen = service.listen(request).await.unwrap().into_inner();
+
+        service.shutdown_token().cancel();
+        let query = abi::ReservationQueryBuilder::default()
+            .user_id("tosei")
+            .status(abi::ReservationStatus::Pending)
+            .start(abi::convert_to_timestamp(
+                "2023-01-01T00:00:00Z".parse().unwrap(),
+            ))
+            .end(abi::convert_to_timestamp(
+                "2023-03-01T00:00:00Z".parse().unwrap(),
+            ))
+            .build()
+            .unwrap();
+        let request = Request::new(QueryRequest { query: Some(query) });
+        let mut query = service.query(request).await.unwrap().into_inner();
+        assert!(query.next().await.unwrap().is_ok());
+        assert!(query.next().await.is_none());
+
+        let status = listen.next().await.unwrap().unwrap_err();
+        assert_eq!(tonic::Code::Unavailable, status.code());
+        assert!(listen.next().await.is_none());
+    }
 }
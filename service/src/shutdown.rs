@@ -0,0 +1,90 @@
+use futures::{Future, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::signal;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+use tonic::Status;
+
+/// wait for SIGINT or SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = futures::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// listen stream which is closed with a final `UNAVAILABLE` status when the server is shutting
+/// down, so the client reconnects to another instance. The finite streams, e.g. query and export,
+/// are not wrapped, they finish within the shutdown timeout
+pub struct DrainStream<S> {
+    inner: S,
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+    done: bool,
+}
+
+impl<S> DrainStream<S> {
+    pub fn new(inner: S, token: CancellationToken) -> Self {
+        Self {
+            inner,
+            cancelled: Box::pin(token.cancelled_owned()),
+            done: false,
+        }
+    }
+}
+
+impl<T, S> Stream for DrainStream<S>
+where
+    S: Stream<Item = Result<T, Status>> + Unpin,
+{
+    type Item = Result<T, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        if self.cancelled.as_mut().poll(cx).is_ready() {
+            self.done = true;
+            return Poll::Ready(Some(Err(Status::unavailable("server is shutting down"))));
+        }
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    #[tokio::test]
+    async fn drain_stream_should_be_closed_with_status() {
+        let token = CancellationToken::new();
+        let inner = stream::iter(vec![Ok(1), Ok(2)]).chain(stream::pending());
+        let mut stream = DrainStream::new(inner, token.clone());
+        assert_eq!(1, stream.next().await.unwrap().unwrap());
+        assert_eq!(2, stream.next().await.unwrap().unwrap());
+
+        token.cancel();
+        let status = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(tonic::Code::Unavailable, status.code());
+        assert!(stream.next().await.is_none());
+    }
+}
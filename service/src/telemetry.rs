@@ -0,0 +1,111 @@
+use abi::TracingConfig;
+use http::HeaderMap;
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    sdk::{propagation::TraceContextPropagator, trace, Resource},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use tracing::{info_span, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+/// install the global subscriber: env-filter, fmt (text or json) and optional OTLP exporter
+pub fn init_tracing(config: &TracingConfig) -> Result<(), anyhow::Error> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.filter));
+
+    let fmt = if config.json {
+        tracing_subscriber::fmt::layer().json().boxed()
+    } else {
+        tracing_subscriber::fmt::layer().boxed()
+    };
+
+    let otel = match &config.otlp_endpoint {
+        Some(endpoint) => {
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", config.service_name.clone()),
+                ])))
+                .install_batch(opentelemetry::runtime::Tokio)?;
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(otel)
+        .with(fmt)
+        .with(filter)
+        .try_init()?;
+    Ok(())
+}
+
+/// flush the pending spans to the collector
+pub fn shutdown_tracing() {
+    global::shutdown_tracer_provider();
+}
+
+/// span for every grpc request, its parent is the W3C traceparent in the metadata
+pub fn grpc_span(req: &http::Request<()>) -> Span {
+    let span = info_span!(
+        "grpc",
+        otel.kind = "server",
+        rpc.system = "grpc",
+        rpc.method = req.uri().path(),
+    );
+    span.set_parent(extract_context(req.headers()));
+    span
+}
+
+/// parent context propagated by the client in the traceparent header
+fn extract_context(headers: &HeaderMap) -> opentelemetry::Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+
+    #[test]
+    fn traceparent_should_be_extracted() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+                .parse()
+                .unwrap(),
+        );
+        let cx = extract_context(&headers);
+        let span_context = cx.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert_eq!(
+            "0af7651916cd43dd8448eb211c80319c",
+            span_context.trace_id().to_string()
+        );
+        assert_eq!("b7ad6b7169203331", span_context.span_id().to_string());
+    }
+}
@@ -0,0 +1,258 @@
+use abi::TenancyConfig;
+use futures::future::BoxFuture;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use order::{Actor, Tenant};
+use serde_json::{Map, Value};
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tonic::Status;
+use tower::{Layer, Service};
+
+use crate::{is_grpc, REQUEST_ID_METADATA};
+
+/// isolate a request to the tenant of its token or metadata, shared by the grpc and rest servers.
+/// The user claim of the token is the verified actor of the request
+#[derive(Clone)]
+pub struct TenantLayer {
+    resolver: Arc<Resolver>,
+}
+
+struct Resolver {
+    default: String,
+    header: String,
+    claim: String,
+    user_claim: String,
+    key: Option<DecodingKey>,
+}
+
+impl TenantLayer {
+    pub fn new(config: &TenancyConfig) -> Self {
+        let key = config
+            .jwt_secret
+            .as_ref()
+            .map(|secret| DecodingKey::from_secret(secret.as_bytes()));
+        Self {
+            resolver: Arc::new(Resolver {
+                default: config.default.clone(),
+                header: config.header.clone(),
+                claim: config.claim.clone(),
+                user_claim: config.user_claim.clone(),
+                key,
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for TenantLayer {
+    type Service = TenantService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantService {
+            inner,
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TenantService<S> {
+    inner: S,
+    resolver: Arc<Resolver>,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for TenantService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<ReqBody>) -> Self::Future {
+        match self.resolver.resolve(&req) {
+            Ok((tenant, Some(actor))) => {
+                // the inner layers, e.g. the rate limiter, see the verified user as well
+                req.extensions_mut().insert(actor.clone());
+                Box::pin(tenant.scope(actor.scope(self.inner.call(req))))
+            }
+            Ok((tenant, None)) => Box::pin(tenant.scope(self.inner.call(req))),
+            Err(message) => {
+                let grpc = is_grpc(&req);
+                Box::pin(async move { Ok(unauthenticated(message, grpc)) })
+            }
+        }
+    }
+}
+
+impl Resolver {
+    /// the claim of the bearer token if a secret is set, otherwise the metadata or the default.
+    /// The actor is only verified by the user claim of a token
+    fn resolve<B>(&self, req: &http::Request<B>) -> Result<(Tenant, Option<Actor>), String> {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.is_empty())
+        };
+        let key = match &self.key {
+            Some(key) => key,
+            None => {
+                let id = header(&self.header).unwrap_or(&self.default);
+                return Ok((Tenant::new(id), None));
+            }
+        };
+        let token = header(http::header::AUTHORIZATION.as_str())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| "bearer token is required".to_string())?;
+        let claims = decode::<Map<String, Value>>(token, key, &Validation::new(Algorithm::HS256))
+            .map_err(|e| format!("invalid token: {e}"))?
+            .claims;
+        let tenant = match claims.get(&self.claim) {
+            Some(Value::String(id)) if !id.is_empty() => Tenant::new(id.as_str()),
+            _ => return Err(format!("token has no {} claim", self.claim)),
+        };
+        let actor = match claims.get(&self.user_claim) {
+            Some(Value::String(user)) if !user.is_empty() => Some(Actor::verified(
+                user.as_str(),
+                header(REQUEST_ID_METADATA).unwrap_or_default(),
+            )),
+            _ => None,
+        };
+        Ok((tenant, actor))
+    }
+}
+
+/// a trailers only grpc error with the http status 200, the rest clients see 401 instead
+fn unauthenticated<B: Default>(message: String, grpc: bool) -> http::Response<B> {
+    let (mut parts, _) = Status::unauthenticated(message).to_http().into_parts();
+    if !grpc {
+        parts.status = http::StatusCode::UNAUTHORIZED;
+    }
+    http::Response::from_parts(parts, B::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use std::{
+        convert::Infallible,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+    use tower::{service_fn, ServiceExt};
+
+    async fn call(config: &TenancyConfig, req: http::Request<()>) -> http::Response<String> {
+        let svc = TenantLayer::new(config).layer(service_fn(|_: http::Request<()>| async {
+            let tenant = Tenant::current().map(|t| t.id().to_string());
+            Ok::<_, Infallible>(http::Response::new(tenant.unwrap_or_default()))
+        }));
+        svc.oneshot(req).await.unwrap()
+    }
+
+    fn token(claims: Value) -> String {
+        let key = EncodingKey::from_secret(b"secret");
+        let token = encode(&Header::default(), &claims, &key).unwrap();
+        format!("Bearer {token}")
+    }
+
+    #[tokio::test]
+    async fn tenant_should_be_taken_from_the_metadata() {
+        let config = TenancyConfig::default();
+        let req = http::Request::builder()
+            .header("x-tenant-id", "acme")
+            .body(())
+            .unwrap();
+        assert_eq!("acme", call(&config, req).await.body());
+        let req = http::Request::builder().body(()).unwrap();
+        assert_eq!("default", call(&config, req).await.body());
+    }
+
+    #[tokio::test]
+    async fn tenant_should_be_taken_from_the_token_claim() {
+        let config = TenancyConfig {
+            jwt_secret: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 600;
+        let req = http::Request::builder()
+            .header(
+                "authorization",
+                token(json!({"tenant_id": "acme", "exp": exp})),
+            )
+            .header("x-tenant-id", "globex")
+            .body(())
+            .unwrap();
+        assert_eq!("acme", call(&config, req).await.body());
+
+        // the metadata is not trusted once the tokens are required
+        let req = http::Request::builder()
+            .header("x-tenant-id", "globex")
+            .body(())
+            .unwrap();
+        let res = call(&config, req).await;
+        assert_eq!(http::StatusCode::UNAUTHORIZED, res.status());
+        assert_eq!("16", res.headers()["grpc-status"]);
+        // the grpc clients read the code of a trailers only response with the status 200
+        let req = http::Request::builder()
+            .header("content-type", "application/grpc")
+            .body(())
+            .unwrap();
+        let res = call(&config, req).await;
+        assert_eq!(http::StatusCode::OK, res.status());
+        assert_eq!("16", res.headers()["grpc-status"]);
+
+        let req = http::Request::builder()
+            .header("authorization", token(json!({"sub": "tosei", "exp": exp})))
+            .body(())
+            .unwrap();
+        assert_eq!(
+            http::StatusCode::UNAUTHORIZED,
+            call(&config, req).await.status()
+        );
+    }
+
+    #[tokio::test]
+    async fn actor_should_be_verified_by_the_user_claim() {
+        let config = TenancyConfig {
+            jwt_secret: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let svc = || {
+            TenantLayer::new(&config).layer(service_fn(|_: http::Request<()>| async {
+                Ok::<_, Infallible>(http::Response::new(Actor::current()))
+            }))
+        };
+        let req = http::Request::builder()
+            .header(
+                "authorization",
+                token(json!({"tenant_id": "acme", "sub": "tosei", "exp": u32::MAX})),
+            )
+            .header(REQUEST_ID_METADATA, "req-1")
+            .body(())
+            .unwrap();
+        let actor = svc().oneshot(req).await.unwrap().into_body();
+        assert_eq!(Some(Actor::verified("tosei", "req-1")), actor);
+
+        let req = http::Request::builder()
+            .header(
+                "authorization",
+                token(json!({"tenant_id": "acme", "exp": u32::MAX})),
+            )
+            .body(())
+            .unwrap();
+        assert_eq!(None, svc().oneshot(req).await.unwrap().into_body());
+    }
+}
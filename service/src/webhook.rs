@@ -0,0 +1,234 @@
+use abi::WebhookConfig;
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use order::{Delivery, OrderManager, Outbox};
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// `sha256=<hex>` of the HMAC-SHA256 of `{timestamp}.{payload}` keyed by the secret
+pub const SIGNATURE_HEADER: &str = "x-rsvp-signature";
+/// unix seconds when the delivery is signed, the receivers could reject the stale ones
+pub const TIMESTAMP_HEADER: &str = "x-rsvp-timestamp";
+/// the id of the change, the same for every attempt so the receivers could dedupe it
+pub const DELIVERY_HEADER: &str = "x-rsvp-delivery";
+
+/// the deliveries posted in one sweep
+const DISPATCH_BATCH: i64 = 100;
+
+/// sign the payload of a delivery with the secret of its webhook
+pub fn sign(secret: &str, timestamp: u64, payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("hmac could take a key of any size");
+    mac.update(format!("{timestamp}.{payload}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// post the queued changes to the webhooks, retried with exponential backoff
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    manager: OrderManager,
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookDispatcher {
+    pub fn new(manager: OrderManager, config: WebhookConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()
+            .expect("the http client should be built");
+        Self {
+            manager,
+            client,
+            config,
+        }
+    }
+
+    /// queue the new changes and post the due deliveries, returns how many are accepted
+    pub async fn dispatch(&self) -> Result<usize, abi::Error> {
+        self.manager.fan_out().await?;
+        // the lease outlives the request, so a delivery is not posted twice at once
+        let lease = Duration::from_secs(self.config.timeout * 2 + 1);
+        let deliveries = self.manager.claim_deliveries(DISPATCH_BATCH, lease).await?;
+        let results = join_all(deliveries.into_iter().map(|d| self.deliver(d))).await;
+        let mut accepted = 0;
+        for result in results {
+            accepted += result? as usize;
+        }
+        Ok(accepted)
+    }
+
+    async fn deliver(&self, delivery: Delivery) -> Result<bool, abi::Error> {
+        let error = match self.post(&delivery).await {
+            Ok(()) => {
+                self.manager.complete_delivery(delivery.id).await?;
+                return Ok(true);
+            }
+            Err(e) => e,
+        };
+
+        let attempts = delivery.attempts as u32 + 1;
+        let retry_after =
+            (attempts < self.config.max_attempts).then(|| backoff(&self.config, attempts));
+        match retry_after {
+            Some(after) => warn!(
+                "delivery {} to webhook {} failed, retry in {:?}: {}",
+                delivery.change_id, delivery.webhook_id, after, error
+            ),
+            None => warn!(
+                "delivery {} to webhook {} is dead after {} attempts: {}",
+                delivery.change_id, delivery.webhook_id, attempts, error
+            ),
+        }
+        self.manager
+            .fail_delivery(delivery.id, &error, retry_after)
+            .await?;
+        Ok(false)
+    }
+
+    async fn post(&self, delivery: &Delivery) -> Result<(), String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let response = self
+            .client
+            .post(&delivery.url)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                sign(&delivery.secret, timestamp, &delivery.payload),
+            )
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(DELIVERY_HEADER, delivery.change_id)
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("webhook responded {}", response.status()))
+        }
+    }
+}
+
+/// the backoff before the next attempt of a delivery failed `attempts` times
+fn backoff(config: &WebhookConfig, attempts: u32) -> Duration {
+    let secs = config
+        .initial_backoff
+        .saturating_mul(1 << (attempts - 1).min(32))
+        .min(config.max_backoff);
+    Duration::from_secs(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestConfig;
+    use abi::{Reservation, Webhook};
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use order::Order;
+    use std::{
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn sign_should_match_the_known_digest() {
+        assert_eq!(
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163",
+            sign("secret", 1700000000, "{}")
+        );
+    }
+
+    #[test]
+    fn backoff_should_be_doubled_up_to_the_max() {
+        let config = WebhookConfig::default();
+        assert_eq!(Duration::from_secs(10), backoff(&config, 1));
+        assert_eq!(Duration::from_secs(40), backoff(&config, 3));
+        assert_eq!(Duration::from_secs(3600), backoff(&config, 20));
+    }
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        status: Arc<Mutex<StatusCode>>,
+        received: Arc<Mutex<Vec<String>>>,
+    }
+
+    async fn receive(
+        State(receiver): State<Receiver>,
+        headers: HeaderMap,
+        body: String,
+    ) -> StatusCode {
+        let header = |name| headers.get(name).unwrap().to_str().unwrap().to_string();
+        let timestamp = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert_eq!(header(SIGNATURE_HEADER), sign("secret", timestamp, &body));
+        receiver.received.lock().unwrap().push(body);
+        *receiver.status.lock().unwrap()
+    }
+
+    #[tokio::test]
+    async fn failed_webhook_should_be_retried_then_dead() {
+        let receiver = Receiver::default();
+        *receiver.status.lock().unwrap() = StatusCode::INTERNAL_SERVER_ERROR;
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver.clone());
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let config = TestConfig::default();
+        let manager = OrderManager::from_config(&config.db).await.unwrap();
+        let webhook = manager
+            .register_webhook(Webhook {
+                url: format!("http://{addr}/hook"),
+                secret: "secret".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let rsvp = manager
+            .create_order(Reservation::new_pending(
+                "tosei",
+                "room-1",
+                "2030-01-01T10:00:00Z".parse().unwrap(),
+                "2030-01-01T12:00:00Z".parse().unwrap(),
+                "",
+            ))
+            .await
+            .unwrap();
+
+        let dispatcher = WebhookDispatcher::new(
+            manager.clone(),
+            WebhookConfig {
+                max_attempts: 2,
+                initial_backoff: 0,
+                ..Default::default()
+            },
+        );
+        assert_eq!(0, dispatcher.dispatch().await.unwrap());
+        assert_eq!(0, dispatcher.dispatch().await.unwrap());
+        let dead = manager.list_dead_letters(webhook.id).await.unwrap();
+        assert_eq!(1, dead.len());
+        assert_eq!(2, dead[0].attempts);
+        assert_eq!(2, receiver.received.lock().unwrap().len());
+
+        *receiver.status.lock().unwrap() = StatusCode::NO_CONTENT;
+        assert_eq!(1, manager.replay_webhook(webhook.id, &[]).await.unwrap());
+        assert_eq!(1, dispatcher.dispatch().await.unwrap());
+        assert!(manager
+            .list_dead_letters(webhook.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let body: serde_json::Value =
+            serde_json::from_str(receiver.received.lock().unwrap().last().unwrap()).unwrap();
+        assert_eq!("create", body["event"]);
+        assert_eq!(rsvp.id, body["reservation"]["id"].as_i64().unwrap());
+    }
+}